@@ -84,7 +84,7 @@
 //! [`animated_field`]: crate::animated_field
 
 use core::{
-    any::TypeId,
+    any::{Any, TypeId},
     fmt::{self, Debug, Formatter},
     marker::PhantomData,
 };
@@ -389,6 +389,14 @@ where
             });
         Ok(())
     }
+
+    fn sample_into(&self, t: f32, value: &mut dyn Any) -> bool {
+        let Some(value) = value.downcast_mut::<P::Property>() else {
+            return false;
+        };
+        *value = self.curve.sample_clamped(t);
+        true
+    }
 }
 
 impl<A: Animatable> AnimationCurveEvaluator for AnimatableCurveEvaluator<A> {
@@ -799,6 +807,17 @@ pub trait AnimationCurve: Debug + Send + Sync + 'static {
         weight: f32,
         graph_node: AnimationNodeIndex,
     ) -> Result<(), AnimationEvaluationError>;
+
+    /// Samples the curve at the given time `t`, and writes the sampled value
+    /// into `value` if it has the type that this curve animates.
+    ///
+    /// Returns false if the type doesn't match, or if this curve can't be
+    /// sampled outside of evaluation. This is used to extract
+    /// [root motion](crate::root_motion).
+    fn sample_into(&self, t: f32, value: &mut dyn Any) -> bool {
+        let _ = (t, value);
+        false
+    }
 }
 
 /// The [`EvaluatorId`] is used to look up the [`AnimationCurveEvaluator`] for an [`AnimatableProperty`].
@@ -0,0 +1,544 @@
+//! Animation blend spaces.
+//!
+//! A *blend space node* in an [`AnimationGraph`] places its children at points
+//! in a one- or two-dimensional parameter space, for example speed × direction.
+//! Every frame, the value of the blend space's parameters is read from the
+//! [`AnimationParameters`] on the same entity as the [`AnimationPlayer`], and
+//! the children are weighted according to how close their points are to it.
+//!
+//! In one dimension, the two points surrounding the value are linearly
+//! interpolated. In two dimensions, the points are triangulated, and the three
+//! points of the triangle containing the value are weighted by the value's
+//! barycentric coordinates.
+
+use bevy_asset::Assets;
+use bevy_ecs::system::{Query, Res};
+use bevy_math::{Vec2, Vec3};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use thiserror::Error;
+
+use crate::{
+    graph::{
+        AnimationGraph, AnimationGraphHandle, AnimationNodeIndex, AnimationNodeType,
+        ThreadedAnimationGraphs,
+    },
+    state_machine::{AnimationParameter, AnimationParameters},
+    AnimationPlayer,
+};
+
+/// The data of a [blend space node](AnimationNodeType::BlendSpace).
+///
+/// Each [point](BlendSpacePoint) refers to a child node of the blend space
+/// node. Like with blend nodes, the animation clips of the children must be
+/// played on the [`AnimationPlayer`] for them to have an effect, unless the
+/// blend space is a state of a [state machine](crate::state_machine).
+#[derive(Clone, Default, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Default, Debug, PartialEq)]
+pub struct AnimationBlendSpace {
+    /// The name of the parameter that provides the position along the x axis.
+    pub x_parameter: String,
+
+    /// The name of the parameter that provides the position along the y axis.
+    ///
+    /// If this is `None`, the blend space is one-dimensional, and the y
+    /// coordinates of the points are ignored.
+    #[serde(default)]
+    pub y_parameter: Option<String>,
+
+    /// The points of the blend space.
+    pub points: Vec<BlendSpacePoint>,
+}
+
+/// A child node of a blend space, placed at a position in its parameter space.
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub struct BlendSpacePoint {
+    /// The child node of the blend space node.
+    pub node: AnimationNodeIndex,
+
+    /// The position of the point in the parameter space.
+    pub position: Vec2,
+}
+
+impl AnimationBlendSpace {
+    /// Creates a new, empty one-dimensional blend space, driven by the
+    /// parameter with the given name.
+    pub fn new_1d(parameter: impl Into<String>) -> Self {
+        Self {
+            x_parameter: parameter.into(),
+            y_parameter: None,
+            points: Vec::new(),
+        }
+    }
+
+    /// Creates a new, empty two-dimensional blend space, driven by the
+    /// parameters with the given names.
+    pub fn new_2d(x_parameter: impl Into<String>, y_parameter: impl Into<String>) -> Self {
+        Self {
+            x_parameter: x_parameter.into(),
+            y_parameter: Some(y_parameter.into()),
+            points: Vec::new(),
+        }
+    }
+
+    /// Places the given child `node` at `x` in a one-dimensional blend space.
+    pub fn with_point_1d(self, node: AnimationNodeIndex, x: f32) -> Self {
+        self.with_point_2d(node, Vec2::new(x, 0.0))
+    }
+
+    /// Places the given child `node` at `position` in a two-dimensional blend
+    /// space.
+    pub fn with_point_2d(mut self, node: AnimationNodeIndex, position: Vec2) -> Self {
+        self.points.push(BlendSpacePoint { node, position });
+        self
+    }
+
+    /// Returns true if this blend space is two-dimensional.
+    pub fn is_2d(&self) -> bool {
+        self.y_parameter.is_some()
+    }
+
+    /// Checks that every point of this blend space refers to a child of the
+    /// blend space node `node` in `animation_graph`.
+    pub fn validate(
+        &self,
+        animation_graph: &AnimationGraph,
+        node: AnimationNodeIndex,
+    ) -> Result<(), AnimationBlendSpaceError> {
+        for point in &self.points {
+            if !animation_graph
+                .graph
+                .neighbors_directed(node, Direction::Outgoing)
+                .any(|child| child == point.node)
+            {
+                return Err(AnimationBlendSpaceError::PointNodeNotAChild(point.node));
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the Delaunay triangulation of the points of this blend space,
+    /// as triples of indices into [`Self::points`].
+    ///
+    /// This is empty for one-dimensional blend spaces, and for two-dimensional
+    /// blend spaces whose points are all collinear.
+    pub fn triangulate(&self) -> Vec<[u32; 3]> {
+        if !self.is_2d() {
+            return Vec::new();
+        }
+        let positions: Vec<Vec2> = self.points.iter().map(|point| point.position).collect();
+        delaunay_triangulation(&positions)
+    }
+
+    /// Computes the weight of each point of this blend space for the given
+    /// `position` in parameter space, writing them to `weights`.
+    ///
+    /// `triangles` must be the result of [`Self::triangulate`]. Positions
+    /// outside of the points are clamped to the closest position that's
+    /// covered by them. The weights add up to 1.0, unless there are no points.
+    pub fn compute_weights(&self, position: Vec2, triangles: &[[u32; 3]], weights: &mut Vec<f32>) {
+        weights.clear();
+        weights.resize(self.points.len(), 0.0);
+        if self.points.is_empty() {
+            return;
+        }
+
+        if !self.is_2d() {
+            self.compute_weights_1d(position.x, weights);
+            return;
+        }
+
+        // Find the triangle closest to the position. This is the triangle that
+        // contains it, unless it's outside of all triangles.
+        let mut closest: Option<(f32, [u32; 3], Vec3)> = None;
+        for &triangle in triangles {
+            let [a, b, c] = triangle.map(|index| self.points[index as usize].position);
+            let (distance_squared, barycentric) = closest_point_on_triangle(position, a, b, c);
+            if closest.is_none_or(|(closest_distance_squared, ..)| {
+                distance_squared < closest_distance_squared
+            }) {
+                closest = Some((distance_squared, triangle, barycentric));
+            }
+        }
+
+        match closest {
+            Some((_, triangle, barycentric)) => {
+                for (index, weight) in triangle.into_iter().zip(barycentric.to_array()) {
+                    weights[index as usize] += weight;
+                }
+            }
+            None => {
+                // Without any triangles, fall back to the closest point.
+                weights[closest_point(self.points.iter().map(|point| point.position), position)] =
+                    1.0;
+            }
+        }
+    }
+
+    fn compute_weights_1d(&self, x: f32, weights: &mut [f32]) {
+        // Find the closest points on either side of `x`.
+        let mut below: Option<(usize, f32)> = None;
+        let mut above: Option<(usize, f32)> = None;
+        for (index, point) in self.points.iter().enumerate() {
+            let point_x = point.position.x;
+            if point_x <= x && below.is_none_or(|(_, below_x)| point_x > below_x) {
+                below = Some((index, point_x));
+            }
+            if point_x >= x && above.is_none_or(|(_, above_x)| point_x < above_x) {
+                above = Some((index, point_x));
+            }
+        }
+
+        match (below, above) {
+            (Some((below, below_x)), Some((above, above_x))) if above_x > below_x => {
+                let t = (x - below_x) / (above_x - below_x);
+                weights[below] = 1.0 - t;
+                weights[above] = t;
+            }
+            (Some((index, _)), _) | (None, Some((index, _))) => weights[index] = 1.0,
+            (None, None) => {}
+        }
+    }
+}
+
+/// A system that updates the weights of the children of the blend spaces in
+/// every [`AnimationPlayer`]'s graph, according to its [`AnimationParameters`].
+pub fn update_blend_spaces(
+    animation_graphs: Res<Assets<AnimationGraph>>,
+    threaded_animation_graphs: Res<ThreadedAnimationGraphs>,
+    mut players: Query<(
+        &mut AnimationPlayer,
+        &AnimationGraphHandle,
+        Option<&AnimationParameters>,
+    )>,
+) {
+    let mut weights = Vec::new();
+    for (mut player, graph_handle, parameters) in &mut players {
+        let Some(animation_graph) = animation_graphs.get(graph_handle) else {
+            continue;
+        };
+        let Some(threaded_animation_graph) = threaded_animation_graphs.0.get(&graph_handle.id())
+        else {
+            continue;
+        };
+
+        for node_index in animation_graph.graph.node_indices() {
+            let AnimationNodeType::BlendSpace(ref blend_space) =
+                animation_graph[node_index].node_type
+            else {
+                continue;
+            };
+
+            let parameter = |name: &str| {
+                parameters
+                    .and_then(|parameters| parameters.get(name))
+                    .map_or(0.0, AnimationParameter::as_f32)
+            };
+            let position = Vec2::new(
+                parameter(&blend_space.x_parameter),
+                blend_space.y_parameter.as_deref().map_or(0.0, parameter),
+            );
+            let triangles = threaded_animation_graph
+                .blend_space_triangles
+                .get(&node_index)
+                .map_or(&[][..], Vec::as_slice);
+            blend_space.compute_weights(position, triangles, &mut weights);
+
+            for point in &blend_space.points {
+                player.node_weights.insert(point.node, 0.0);
+            }
+            for (point, &weight) in blend_space.points.iter().zip(&weights) {
+                *player.node_weights.get_mut(&point.node).unwrap() += weight;
+            }
+        }
+    }
+}
+
+/// An error found by [`AnimationBlendSpace::validate`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AnimationBlendSpaceError {
+    /// A point refers to a node that isn't a child of the blend space node.
+    #[error("A point refers to the node {0:?}, which isn't a child of its blend space node")]
+    PointNodeNotAChild(AnimationNodeIndex),
+}
+
+/// Returns the index of the position closest to `target`.
+fn closest_point(positions: impl Iterator<Item = Vec2>, target: Vec2) -> usize {
+    positions
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            a.distance_squared(target)
+                .total_cmp(&b.distance_squared(target))
+        })
+        .map_or(0, |(index, _)| index)
+}
+
+/// Returns the squared distance from `p` to the closest point on the triangle
+/// `abc`, and the barycentric coordinates of that point.
+///
+/// See "Real-Time Collision Detection" by Christer Ericson, section 5.1.5.
+fn closest_point_on_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> (f32, Vec3) {
+    let barycentric = (|| {
+        let ab = b - a;
+        let ac = c - a;
+        let ap = p - a;
+        let d1 = ab.dot(ap);
+        let d2 = ac.dot(ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return Vec3::X;
+        }
+
+        let bp = p - b;
+        let d3 = ab.dot(bp);
+        let d4 = ac.dot(bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return Vec3::Y;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            let v = d1 / (d1 - d3);
+            return Vec3::new(1.0 - v, v, 0.0);
+        }
+
+        let cp = p - c;
+        let d5 = ab.dot(cp);
+        let d6 = ac.dot(cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return Vec3::Z;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            let w = d2 / (d2 - d6);
+            return Vec3::new(1.0 - w, 0.0, w);
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return Vec3::new(0.0, 1.0 - w, w);
+        }
+
+        let denominator = 1.0 / (va + vb + vc);
+        let v = vb * denominator;
+        let w = vc * denominator;
+        Vec3::new(1.0 - v - w, v, w)
+    })();
+
+    let closest = a * barycentric.x + b * barycentric.y + c * barycentric.z;
+    (closest.distance_squared(p), barycentric)
+}
+
+/// Triangulates `points` with the Bowyer-Watson algorithm.
+fn delaunay_triangulation(points: &[Vec2]) -> Vec<[u32; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    // Start with a triangle that contains all the points.
+    let min = points.iter().copied().fold(Vec2::MAX, Vec2::min);
+    let max = points.iter().copied().fold(Vec2::MIN, Vec2::max);
+    let center = (min + max) * 0.5;
+    let extent = (max - min).max_element().max(1.0) * 20.0;
+    let mut vertices = points.to_vec();
+    vertices.extend([
+        center + Vec2::new(-extent, -extent),
+        center + Vec2::new(extent, -extent),
+        center + Vec2::new(0.0, extent),
+    ]);
+    let super_triangle = [points.len(), points.len() + 1, points.len() + 2];
+    let mut triangles = vec![super_triangle];
+
+    for (index, &point) in points.iter().enumerate() {
+        // Remove the triangles whose circumcircle contains the point, and fill
+        // the hole by connecting its boundary to the point.
+        let (bad_triangles, good_triangles): (Vec<_>, Vec<_>) = triangles
+            .into_iter()
+            .partition(|&triangle| in_circumcircle(triangle.map(|i| vertices[i]), point));
+
+        let mut boundary = SmallVec::<[[usize; 2]; 16]>::new();
+        for (i, triangle) in bad_triangles.iter().enumerate() {
+            for edge in [
+                [triangle[0], triangle[1]],
+                [triangle[1], triangle[2]],
+                [triangle[2], triangle[0]],
+            ] {
+                let shared = bad_triangles.iter().enumerate().any(|(j, other)| {
+                    i != j && other.contains(&edge[0]) && other.contains(&edge[1])
+                });
+                if !shared {
+                    boundary.push(edge);
+                }
+            }
+        }
+
+        triangles = good_triangles;
+        triangles.extend(boundary.into_iter().map(|[a, b]| [a, b, index]));
+    }
+
+    triangles
+        .into_iter()
+        .filter(|triangle| {
+            triangle.iter().all(|&i| i < points.len())
+                && orientation(triangle.map(|i| vertices[i])).abs() > f32::EPSILON
+        })
+        .map(|triangle| triangle.map(|i| i as u32))
+        .collect()
+}
+
+/// Returns twice the signed area of the triangle, which is positive if its
+/// vertices are in counterclockwise order.
+fn orientation([a, b, c]: [Vec2; 3]) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+
+/// Returns true if `p` is strictly inside the circumcircle of the triangle.
+fn in_circumcircle(triangle @ [a, b, c]: [Vec2; 3], p: Vec2) -> bool {
+    let [a, b, c] = [a - p, b - p, c - p];
+    let determinant = a.length_squared() * b.perp_dot(c) - b.length_squared() * a.perp_dot(c)
+        + c.length_squared() * a.perp_dot(b);
+    if orientation(triangle) > 0.0 {
+        determinant > 0.0
+    } else {
+        determinant < 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{SerializedAnimationGraph, SerializedAnimationNodeType};
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+
+    fn node(index: u32) -> AnimationNodeIndex {
+        AnimationNodeIndex::new(index as usize)
+    }
+
+    fn assert_weights(blend_space: &AnimationBlendSpace, position: Vec2, expected: &[f32]) {
+        let mut weights = Vec::new();
+        blend_space.compute_weights(position, &blend_space.triangulate(), &mut weights);
+        for (weight, expected) in weights.iter().zip(expected) {
+            assert!(
+                (weight - expected).abs() < 1e-5,
+                "{weights:?} != {expected:?} at {position}"
+            );
+        }
+    }
+
+    #[test]
+    fn blend_space_1d_interpolates_neighbors() {
+        let blend_space = AnimationBlendSpace::new_1d("speed")
+            .with_point_1d(node(1), 0.0)
+            .with_point_1d(node(3), 4.0)
+            .with_point_1d(node(2), 2.0);
+
+        assert_weights(&blend_space, Vec2::new(1.0, 0.0), &[0.5, 0.0, 0.5]);
+        assert_weights(&blend_space, Vec2::new(3.5, 0.0), &[0.0, 0.75, 0.25]);
+        assert_weights(&blend_space, Vec2::new(2.0, 0.0), &[0.0, 0.0, 1.0]);
+        assert_weights(&blend_space, Vec2::new(-1.0, 0.0), &[1.0, 0.0, 0.0]);
+        assert_weights(&blend_space, Vec2::new(9.0, 0.0), &[0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn blend_space_2d_uses_barycentric_coordinates() {
+        // A square with a point in the center.
+        let blend_space = AnimationBlendSpace::new_2d("x", "y")
+            .with_point_2d(node(1), Vec2::new(-1.0, -1.0))
+            .with_point_2d(node(2), Vec2::new(1.0, -1.0))
+            .with_point_2d(node(3), Vec2::new(1.0, 1.0))
+            .with_point_2d(node(4), Vec2::new(-1.0, 1.0))
+            .with_point_2d(node(5), Vec2::ZERO);
+        assert_eq!(blend_space.triangulate().len(), 4);
+
+        assert_weights(&blend_space, Vec2::ZERO, &[0.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_weights(
+            &blend_space,
+            Vec2::new(1.0, 1.0),
+            &[0.0, 0.0, 1.0, 0.0, 0.0],
+        );
+        assert_weights(
+            &blend_space,
+            Vec2::new(0.0, -0.5),
+            &[0.25, 0.25, 0.0, 0.0, 0.5],
+        );
+        // Positions outside are clamped to the edges of the triangulation.
+        assert_weights(
+            &blend_space,
+            Vec2::new(3.0, 0.0),
+            &[0.0, 0.5, 0.5, 0.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn blend_space_weights_children() {
+        let mut graph = AnimationGraph::new();
+        let blend_space =
+            graph.add_blend_space(AnimationBlendSpace::new_1d("speed"), 1.0, graph.root);
+        let walk = graph.add_blend(1.0, blend_space);
+        let run = graph.add_blend(1.0, blend_space);
+        *graph.blend_space_mut(blend_space).unwrap() = AnimationBlendSpace::new_1d("speed")
+            .with_point_1d(walk, 1.0)
+            .with_point_1d(run, 5.0);
+
+        // Blend spaces round-trip through RON.
+        let mut ron = String::new();
+        graph.save(&mut ron).unwrap();
+        let serialized: SerializedAnimationGraph = ron::de::from_str(&ron).unwrap();
+        let SerializedAnimationNodeType::BlendSpace(ref deserialized) =
+            serialized.graph[blend_space].node_type
+        else {
+            panic!("expected a blend space node");
+        };
+        assert_eq!(
+            Some(deserialized),
+            graph.blend_space_mut(blend_space).as_deref()
+        );
+
+        let mut world = World::new();
+        let mut graphs = Assets::<AnimationGraph>::default();
+        let graph = graphs.add(graph);
+        world.insert_resource(graphs);
+        world.init_resource::<ThreadedAnimationGraphs>();
+        world
+            .resource_mut::<ThreadedAnimationGraphs>()
+            .0
+            .insert(graph.id(), Default::default());
+
+        let mut parameters = AnimationParameters::new();
+        parameters.set_float("speed", 2.0);
+        let player = world
+            .spawn((
+                AnimationPlayer::default(),
+                AnimationGraphHandle(graph),
+                parameters,
+            ))
+            .id();
+        world.run_system_once(update_blend_spaces).unwrap();
+
+        let player = world.get::<AnimationPlayer>(player).unwrap();
+        assert_eq!(player.node_weight(walk), 0.75);
+        assert_eq!(player.node_weight(run), 0.25);
+    }
+
+    #[test]
+    fn validate_rejects_nodes_that_arent_children() {
+        let mut graph = AnimationGraph::new();
+        let blend_space =
+            graph.add_blend_space(AnimationBlendSpace::new_1d("speed"), 1.0, graph.root);
+        let walk = graph.add_blend(1.0, blend_space);
+        let not_a_child = graph.add_blend(1.0, graph.root);
+
+        let valid = AnimationBlendSpace::new_1d("speed").with_point_1d(walk, 1.0);
+        assert_eq!(valid.validate(&graph, blend_space), Ok(()));
+
+        let invalid = valid.with_point_1d(not_a_child, 5.0);
+        assert_eq!(
+            invalid.validate(&graph, blend_space),
+            Err(AnimationBlendSpaceError::PointNodeNotAChild(not_a_child))
+        );
+    }
+}
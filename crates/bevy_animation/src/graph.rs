@@ -31,7 +31,11 @@ use smallvec::SmallVec;
 use thiserror::Error;
 use tracing::warn;
 
-use crate::{AnimationClip, AnimationTargetId};
+use crate::{
+    blend_space::{AnimationBlendSpace, AnimationBlendSpaceError},
+    state_machine::{AnimationStateMachine, AnimationStateMachineError},
+    AnimationClip, AnimationTargetId,
+};
 
 /// A graph structure that describes how animation clips are to be blended
 /// together.
@@ -51,6 +55,13 @@ use crate::{AnimationClip, AnimationTargetId};
 /// animation clip to play. When a graph is created, it starts with only a
 /// single blend node, the root node.
 ///
+/// Additionally, *state machine nodes* play one of their children at a time,
+/// and crossfade between them as the [`AnimationParameters`] of the player
+/// change. See [`AnimationStateMachine`] for more information. *Blend space
+/// nodes* compute the weights of their children from the position of those
+/// parameters in a 1D or 2D space. See [`AnimationBlendSpace`] for more
+/// information.
+///
 /// For example, consider the following graph:
 ///
 /// ```text
@@ -108,6 +119,8 @@ use crate::{AnimationClip, AnimationTargetId};
 ///
 /// [RON]: https://github.com/ron-rs/ron
 ///
+/// [`AnimationParameters`]: crate::state_machine::AnimationParameters
+///
 /// [RFC 51]: https://github.com/bevyengine/rfcs/blob/main/rfcs/51-animation-composition.md
 #[derive(Asset, Reflect, Clone, Debug)]
 #[reflect(Debug, Clone)]
@@ -161,9 +174,11 @@ pub type AnimationNodeIndex = NodeIndex<u32>;
 /// An individual node within an animation graph.
 ///
 /// The [`AnimationGraphNode::node_type`] field specifies the type of node: one
-/// of a *clip node*, a *blend node*, or an *add node*. Clip nodes, the leaves
-/// of the graph, contain animation clips to play. Blend and add nodes describe
-/// how to combine their children to produce a final animation.
+/// of a *clip node*, a *blend node*, an *add node*, a *state machine node*, or
+/// a *blend space node*.
+/// Clip nodes, the leaves of the graph, contain animation clips to play. The
+/// other nodes describe how to combine their children to produce a final
+/// animation.
 #[derive(Clone, Reflect, Debug)]
 #[reflect(Clone)]
 pub struct AnimationGraphNode {
@@ -233,6 +248,21 @@ pub enum AnimationNodeType {
     /// top of a running animation to produce an animation of a character
     /// attacking while running.
     Add,
+
+    /// A *state machine node*, which blends its children according to the
+    /// weights that its [`AnimationStateMachine`] assigns to its states.
+    ///
+    /// At any time, only the current state, and the states that are being
+    /// faded out, have a weight. The weights of the states are normalized to
+    /// 1.0.
+    StateMachine(AnimationStateMachine),
+
+    /// A *blend space node*, which blends its children according to the
+    /// weights that its [`AnimationBlendSpace`] computes from the parameters
+    /// of the player.
+    ///
+    /// The weights of the children are normalized to 1.0.
+    BlendSpace(AnimationBlendSpace),
 }
 
 /// An [`AssetLoader`] that can load [`AnimationGraph`]s as assets.
@@ -276,6 +306,22 @@ pub enum AnimationGraphLoadError {
     type and determine how to migrate any SerializedAnimationClip::AssetId animation clips"
     )]
     GraphContainsLegacyAssetId,
+    /// A state machine node of the deserialized graph is invalid.
+    #[error("The state machine node {node:?} is invalid: {error}")]
+    InvalidStateMachine {
+        /// The state machine node.
+        node: AnimationNodeIndex,
+        /// What's wrong with the state machine.
+        error: AnimationStateMachineError,
+    },
+    /// A blend space node of the deserialized graph is invalid.
+    #[error("The blend space node {node:?} is invalid: {error}")]
+    InvalidBlendSpace {
+        /// The blend space node.
+        node: AnimationNodeIndex,
+        /// What's wrong with the blend space.
+        error: AnimationBlendSpaceError,
+    },
 }
 
 /// Acceleration structures for animation graphs that allows Bevy to evaluate
@@ -369,6 +415,10 @@ pub struct ThreadedAnimationGraph {
     /// A 1 in bit position N indicates that this node doesn't animate any
     /// targets of mask group N.
     pub computed_masks: Vec<u64>,
+
+    /// The Delaunay triangulation of the points of each two-dimensional blend
+    /// space node, as computed by [`AnimationBlendSpace::triangulate`].
+    pub blend_space_triangles: HashMap<AnimationNodeIndex, Vec<[u32; 3]>>,
 }
 
 /// A version of [`AnimationGraph`] suitable for serializing as an asset.
@@ -413,6 +463,10 @@ pub enum SerializedAnimationNodeType {
     Blend,
     /// Corresponds to [`AnimationNodeType::Add`].
     Add,
+    /// Corresponds to [`AnimationNodeType::StateMachine`].
+    StateMachine(AnimationStateMachine),
+    /// Corresponds to [`AnimationNodeType::BlendSpace`].
+    BlendSpace(AnimationBlendSpace),
 }
 
 /// A type to facilitate migration from the legacy format of [`SerializedAnimationGraph`] to the
@@ -638,6 +692,82 @@ impl AnimationGraph {
         node_index
     }
 
+    /// Adds a state machine node to the animation graph with the given weight
+    /// and returns its index.
+    ///
+    /// The state machine node will be placed under the supplied `parent` node.
+    /// Its states must be children of the state machine node, so they're
+    /// typically added afterwards, and registered with
+    /// [`AnimationGraph::state_machine_mut`]. The state machine node will have
+    /// no mask.
+    pub fn add_state_machine(
+        &mut self,
+        state_machine: AnimationStateMachine,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.graph.add_node(AnimationGraphNode {
+            node_type: AnimationNodeType::StateMachine(state_machine),
+            mask: 0,
+            weight,
+        });
+        self.graph.add_edge(parent, node_index, ());
+        node_index
+    }
+
+    /// Returns a mutable reference to the [`AnimationStateMachine`] of the
+    /// state machine node with the given index.
+    ///
+    /// If no node with the given index exists, or it isn't a state machine
+    /// node, returns `None`.
+    pub fn state_machine_mut(
+        &mut self,
+        animation: AnimationNodeIndex,
+    ) -> Option<&mut AnimationStateMachine> {
+        match self.graph.node_weight_mut(animation)?.node_type {
+            AnimationNodeType::StateMachine(ref mut state_machine) => Some(state_machine),
+            _ => None,
+        }
+    }
+
+    /// Adds a blend space node to the animation graph with the given weight
+    /// and returns its index.
+    ///
+    /// The blend space node will be placed under the supplied `parent` node.
+    /// Its points must be children of the blend space node, so they're
+    /// typically added afterwards, and registered with
+    /// [`AnimationGraph::blend_space_mut`]. The blend space node will have no
+    /// mask.
+    pub fn add_blend_space(
+        &mut self,
+        blend_space: AnimationBlendSpace,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.graph.add_node(AnimationGraphNode {
+            node_type: AnimationNodeType::BlendSpace(blend_space),
+            mask: 0,
+            weight,
+        });
+        self.graph.add_edge(parent, node_index, ());
+        node_index
+    }
+
+    /// Returns a mutable reference to the [`AnimationBlendSpace`] of the blend
+    /// space node with the given index.
+    ///
+    /// If no node with the given index exists, or it isn't a blend space node,
+    /// returns `None`.
+    pub fn blend_space_mut(
+        &mut self,
+        animation: AnimationNodeIndex,
+    ) -> Option<&mut AnimationBlendSpace> {
+        match self.graph.node_weight_mut(animation)?.node_type {
+            AnimationNodeType::BlendSpace(ref mut blend_space) => Some(blend_space),
+            _ => None,
+        }
+    }
+
     /// Adds an edge from the edge `from` to `to`, making `to` a child of
     /// `from`.
     ///
@@ -832,6 +962,12 @@ impl AssetLoader for AnimationGraphAssetLoader {
                     },
                     SerializedAnimationNodeType::Blend => AnimationNodeType::Blend,
                     SerializedAnimationNodeType::Add => AnimationNodeType::Add,
+                    SerializedAnimationNodeType::StateMachine(ref state_machine) => {
+                        AnimationNodeType::StateMachine(state_machine.clone())
+                    }
+                    SerializedAnimationNodeType::BlendSpace(ref blend_space) => {
+                        AnimationNodeType::BlendSpace(blend_space.clone())
+                    }
                 },
                 mask: serialized_node.mask,
                 weight: serialized_node.weight,
@@ -840,11 +976,26 @@ impl AssetLoader for AnimationGraphAssetLoader {
         for edge in serialized_animation_graph.graph.raw_edges() {
             animation_graph.add_edge(edge.source(), edge.target(), ());
         }
-        Ok(AnimationGraph {
+        let animation_graph = AnimationGraph {
             graph: animation_graph,
             root: serialized_animation_graph.root,
             mask_groups: serialized_animation_graph.mask_groups,
-        })
+        };
+        for node in animation_graph.graph.node_indices() {
+            match animation_graph[node].node_type {
+                AnimationNodeType::StateMachine(ref state_machine) => state_machine
+                    .validate(&animation_graph, node)
+                    .map_err(|error| AnimationGraphLoadError::InvalidStateMachine {
+                        node,
+                        error,
+                    })?,
+                AnimationNodeType::BlendSpace(ref blend_space) => blend_space
+                    .validate(&animation_graph, node)
+                    .map_err(|error| AnimationGraphLoadError::InvalidBlendSpace { node, error })?,
+                _ => {}
+            }
+        }
+        Ok(animation_graph)
     }
 
     fn extensions(&self) -> &[&str] {
@@ -877,6 +1028,12 @@ impl TryFrom<AnimationGraph> for SerializedAnimationGraph {
                     },
                     AnimationNodeType::Blend => SerializedAnimationNodeType::Blend,
                     AnimationNodeType::Add => SerializedAnimationNodeType::Add,
+                    AnimationNodeType::StateMachine(ref state_machine) => {
+                        SerializedAnimationNodeType::StateMachine(state_machine.clone())
+                    }
+                    AnimationNodeType::BlendSpace(ref blend_space) => {
+                        SerializedAnimationNodeType::BlendSpace(blend_space.clone())
+                    }
                 },
             });
         }
@@ -935,7 +1092,7 @@ pub(crate) fn thread_animation_graphs(
                     .insert(id, threaded_animation_graph);
             }
 
-            AssetEvent::Removed { id } => {
+            AssetEvent::Removed { id } | AssetEvent::Evicted { id } => {
                 threaded_animation_graphs.0.remove(&id);
             }
             AssetEvent::Unused { .. } => {}
@@ -950,6 +1107,7 @@ impl ThreadedAnimationGraph {
         self.threaded_graph.clear();
         self.sorted_edge_ranges.clear();
         self.sorted_edges.clear();
+        self.blend_space_triangles.clear();
     }
 
     /// Prepares the [`ThreadedAnimationGraph`] for recursion.
@@ -966,6 +1124,16 @@ impl ThreadedAnimationGraph {
 
         self.computed_masks.clear();
         self.computed_masks.extend(iter::repeat_n(0, node_count));
+
+        for node_index in animation_graph.graph.node_indices() {
+            if let AnimationNodeType::BlendSpace(ref blend_space) =
+                animation_graph[node_index].node_type
+                && blend_space.is_2d()
+            {
+                self.blend_space_triangles
+                    .insert(node_index, blend_space.triangulate());
+            }
+        }
     }
 
     /// Recursively constructs the [`ThreadedAnimationGraph`] for the subtree
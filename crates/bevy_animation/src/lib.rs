@@ -11,8 +11,11 @@ extern crate alloc;
 
 pub mod animatable;
 pub mod animation_curves;
+pub mod blend_space;
 pub mod gltf_curves;
 pub mod graph;
+pub mod root_motion;
+pub mod state_machine;
 pub mod transition;
 mod util;
 
@@ -38,7 +41,7 @@ use bevy_math::FloatOrd;
 use bevy_platform::{collections::HashMap, hash::NoOpHash};
 use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
 use bevy_time::Time;
-use bevy_transform::TransformSystems;
+use bevy_transform::{components::Transform, TransformSystems};
 use bevy_utils::{PreHashMap, PreHashMapExt, TypeIdMap};
 use serde::{Deserialize, Serialize};
 use thread_local::ThreadLocal;
@@ -51,14 +54,18 @@ use uuid::Uuid;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        animatable::*, animation_curves::*, graph::*, transition::*, AnimationClip,
-        AnimationPlayer, AnimationPlugin, VariableCurve,
+        animatable::*, animation_curves::*, blend_space::*, graph::*, root_motion::*,
+        state_machine::*, transition::*, AnimationClip, AnimationPlayer, AnimationPlugin,
+        VariableCurve,
     };
 }
 
 use crate::{
     animation_curves::AnimationCurve,
+    blend_space::update_blend_spaces,
     graph::{AnimationGraph, AnimationGraphAssetLoader, AnimationNodeIndex},
+    root_motion::{extract_root_motion, strip_root_motion},
+    state_machine::{advance_state_machines, ActiveStateMachine},
     transition::{advance_transitions, expire_completed_transitions},
 };
 use alloc::sync::Arc;
@@ -484,6 +491,9 @@ pub struct ActiveAnimation {
     seek_time: f32,
     /// The `seek_time` of the previous tick, if any.
     last_seek_time: Option<f32>,
+    /// The amount by which `seek_time` advanced in the previous tick, before
+    /// wrapping around. This is negative if the animation is playing in reverse.
+    last_seek_advance: f32,
     /// Number of times the animation has completed.
     /// If the animation is playing in reverse, this increments when the animation passes the start.
     completions: u32,
@@ -501,6 +511,7 @@ impl Default for ActiveAnimation {
             elapsed: 0.0,
             seek_time: 0.0,
             last_seek_time: None,
+            last_seek_advance: 0.0,
             completions: 0,
             just_completed: false,
             paused: false,
@@ -526,13 +537,15 @@ impl ActiveAnimation {
     fn update(&mut self, delta: f32, clip_duration: f32) {
         self.just_completed = false;
         self.last_seek_time = Some(self.seek_time);
+        self.last_seek_advance = 0.0;
 
         if self.is_finished() {
             return;
         }
 
         self.elapsed += delta;
-        self.seek_time += delta * self.speed;
+        self.last_seek_advance = delta * self.speed;
+        self.seek_time += self.last_seek_advance;
 
         let over_time = self.speed > 0.0 && self.seek_time >= clip_duration;
         let under_time = self.speed < 0.0 && self.seek_time < 0.0;
@@ -548,9 +561,8 @@ impl ActiveAnimation {
         if self.seek_time >= clip_duration {
             self.seek_time %= clip_duration;
         }
-        // Note: assumes delta is never lower than -clip_duration
         if self.seek_time < 0.0 {
-            self.seek_time += clip_duration;
+            self.seek_time = self.seek_time.rem_euclid(clip_duration);
         }
     }
 
@@ -560,6 +572,7 @@ impl ActiveAnimation {
         self.completions = 0;
         self.elapsed = 0.0;
         self.last_seek_time = None;
+        self.last_seek_advance = 0.0;
         self.seek_time = 0.0;
     }
 
@@ -684,6 +697,16 @@ impl ActiveAnimation {
 #[reflect(Component, Default, Clone)]
 pub struct AnimationPlayer {
     active_animations: HashMap<AnimationNodeIndex, ActiveAnimation>,
+    /// The state machines of the graph, keyed by the index of their node.
+    state_machines: HashMap<AnimationNodeIndex, ActiveStateMachine>,
+    /// The factors by which the weights of nodes are multiplied, as assigned
+    /// by their parent state machine and blend space nodes.
+    ///
+    /// This is rebuilt every frame. Nodes not in this map aren't affected.
+    node_weights: HashMap<AnimationNodeIndex, f32>,
+    /// The animation target whose motion is extracted into a
+    /// [`RootMotionDelta`](root_motion::RootMotionDelta), if any.
+    root_motion_target: Option<AnimationTargetId>,
 }
 
 // This is needed since `#[derive(Clone)]` does not generate optimized `clone_from`.
@@ -691,11 +714,17 @@ impl Clone for AnimationPlayer {
     fn clone(&self) -> Self {
         Self {
             active_animations: self.active_animations.clone(),
+            state_machines: self.state_machines.clone(),
+            node_weights: self.node_weights.clone(),
+            root_motion_target: self.root_motion_target,
         }
     }
 
     fn clone_from(&mut self, source: &Self) {
         self.active_animations.clone_from(&source.active_animations);
+        self.state_machines.clone_from(&source.state_machines);
+        self.node_weights.clone_from(&source.node_weights);
+        self.root_motion_target = source.root_motion_target;
     }
 }
 
@@ -936,6 +965,36 @@ impl AnimationPlayer {
     pub fn animation_mut(&mut self, animation: AnimationNodeIndex) -> Option<&mut ActiveAnimation> {
         self.active_animations.get_mut(&animation)
     }
+
+    /// Returns the animation target whose motion is extracted as root motion,
+    /// if any.
+    pub fn root_motion_target(&self) -> Option<AnimationTargetId> {
+        self.root_motion_target
+    }
+
+    /// Sets the animation target whose motion is extracted as root motion.
+    ///
+    /// While a root motion target is set, the horizontal translation and the
+    /// rotation around the vertical axis of that target are removed when
+    /// animations are applied, and are instead reported every frame in a
+    /// [`RootMotionDelta`](root_motion::RootMotionDelta) on this entity. Pass
+    /// `None` to disable root motion.
+    pub fn set_root_motion_target(&mut self, target: Option<AnimationTargetId>) -> &mut Self {
+        self.root_motion_target = target;
+        self
+    }
+
+    /// Returns the state of the [state machine node](AnimationNodeType::StateMachine)
+    /// with the given index, if it has started playing.
+    pub fn state_machine(&self, state_machine: AnimationNodeIndex) -> Option<&ActiveStateMachine> {
+        self.state_machines.get(&state_machine)
+    }
+
+    /// Returns the factor by which the weight of the given node is multiplied
+    /// by its parent state machine or blend space node, or 1.0 if it has none.
+    pub fn node_weight(&self, animation: AnimationNodeIndex) -> f32 {
+        self.node_weights.get(&animation).copied().unwrap_or(1.0)
+    }
 }
 
 /// A system that triggers untargeted animation events for the currently-playing animations.
@@ -960,7 +1019,10 @@ fn trigger_untargeted_animation_events(
                 .get(*index)
                 .and_then(|node| match &node.node_type {
                     AnimationNodeType::Clip(handle) => Some(handle),
-                    AnimationNodeType::Blend | AnimationNodeType::Add => None,
+                    AnimationNodeType::Blend
+                    | AnimationNodeType::Add
+                    | AnimationNodeType::StateMachine(_)
+                    | AnimationNodeType::BlendSpace(_) => None,
                 })
                 .and_then(|id| clips.get(id))
             else {
@@ -1036,7 +1098,7 @@ pub fn animate_targets(
     // Evaluate all animation targets in parallel.
     targets
         .par_iter_mut()
-        .for_each(|(entity, target, entity_mut)| {
+        .for_each(|(entity, target, mut entity_mut)| {
             let &AnimationTarget {
                 id: target_id,
                 player: player_id,
@@ -1084,9 +1146,15 @@ pub fn animate_targets(
                     continue;
                 };
 
+                let node_weight = animation_player.node_weight(animation_graph_node_index);
+
                 match animation_graph_node.node_type {
-                    AnimationNodeType::Blend => {
-                        // This is a blend node.
+                    AnimationNodeType::Blend
+                    | AnimationNodeType::StateMachine(_)
+                    | AnimationNodeType::BlendSpace(_) => {
+                        // This is a blend node. State machine and blend space
+                        // nodes blend their children with the weights assigned
+                        // in `advance_state_machines` and `update_blend_spaces`.
                         for edge_index in threaded_animation_graph.sorted_edge_ranges
                             [animation_graph_node_index.index()]
                         .clone()
@@ -1099,7 +1167,7 @@ pub fn animate_targets(
                         }
 
                         if let Err(err) = evaluation_state.push_blend_register_all(
-                            animation_graph_node.weight,
+                            animation_graph_node.weight * node_weight,
                             animation_graph_node_index,
                         ) {
                             warn!("Animation blending failed: {:?}", err);
@@ -1120,7 +1188,7 @@ pub fn animate_targets(
                         }
 
                         if let Err(err) = evaluation_state.push_blend_register_all(
-                            animation_graph_node.weight,
+                            animation_graph_node.weight * node_weight,
                             animation_graph_node_index,
                         ) {
                             warn!("Animation blending failed: {:?}", err);
@@ -1139,6 +1207,7 @@ pub fn animate_targets(
                         // If the weight is zero or the current animation target is
                         // masked out, stop here.
                         if active_animation.weight == 0.0
+                            || node_weight == 0.0
                             || (target_mask
                                 & threaded_animation_graph.computed_masks
                                     [animation_graph_node_index.index()])
@@ -1178,7 +1247,8 @@ pub fn animate_targets(
                             continue;
                         };
 
-                        let weight = active_animation.weight * animation_graph_node.weight;
+                        let weight =
+                            active_animation.weight * animation_graph_node.weight * node_weight;
                         let seek_time = active_animation.seek_time;
 
                         for curve in curves {
@@ -1214,9 +1284,22 @@ pub fn animate_targets(
                 }
             }
 
-            if let Err(err) = evaluation_state.commit_all(entity_mut) {
+            if let Err(err) = evaluation_state.commit_all(entity_mut.reborrow()) {
                 warn!("Animation application failed: {:?}", err);
             }
+
+            // Leave the motion of the root target to the character controller.
+            if animation_player.root_motion_target == Some(target_id)
+                && let Some(mut transform) = entity_mut.get_mut::<Transform>()
+            {
+                strip_root_motion(
+                    &mut transform,
+                    animation_graph,
+                    &clips,
+                    animation_player,
+                    target_id,
+                );
+            }
         });
 }
 
@@ -1237,7 +1320,10 @@ impl Plugin for AnimationPlugin {
                 (
                     graph::thread_animation_graphs.before(AssetEventSystems),
                     advance_transitions,
+                    advance_state_machines,
+                    update_blend_spaces,
                     advance_animations,
+                    extract_root_motion,
                     // TODO: `animate_targets` can animate anything, so
                     // ambiguity testing currently considers it ambiguous with
                     // every other system in `PostUpdate`. We may want to move
@@ -0,0 +1,606 @@
+//! Root motion extraction.
+//!
+//! Many animations, such as walk cycles, move the root bone of the character
+//! forward. When an [`AnimationPlayer`] has a [root motion target], the
+//! horizontal movement and rotation around the vertical axis that the
+//! animation adds on top of its first frame are stripped from the root target
+//! when the animation is applied, and are instead
+//! reported every frame as a [`RootMotionDelta`] on the entity of the
+//! [`AnimationPlayer`]. Character controllers can then move the character by
+//! that amount, keeping the character's [`Transform`] in sync with its
+//! animation.
+//!
+//! [root motion target]: AnimationPlayer::set_root_motion_target
+
+use core::f32::consts::PI;
+
+use bevy_asset::Assets;
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    component::Component,
+    entity::Entity,
+    reflect::ReflectComponent,
+    system::{Commands, Query, Res},
+};
+use bevy_math::{ops, Quat, Vec3};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypeInfo, Typed};
+use bevy_transform::components::Transform;
+use smallvec::SmallVec;
+
+use crate::{
+    animation_curves::EvaluatorId,
+    graph::{AnimationGraph, AnimationGraphHandle, AnimationNodeIndex, AnimationNodeType},
+    ActiveAnimation, AnimationClip, AnimationPlayer, AnimationTargetId, VariableCurve,
+};
+
+/// The movement of the [root motion target] of an [`AnimationPlayer`] since
+/// the previous frame.
+///
+/// This is inserted on the entity of the [`AnimationPlayer`], and updated
+/// every frame, while the player has a root motion target. The values are
+/// blended across the graph in the same way as the animations themselves,
+/// and are expressed in the space of the parent of the root target.
+///
+/// [root motion target]: AnimationPlayer::set_root_motion_target
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default, Clone, Debug, PartialEq)]
+pub struct RootMotionDelta {
+    /// The horizontal translation of the root target. The `y` component is
+    /// always zero, since vertical motion stays on the root target.
+    pub translation: Vec3,
+    /// The rotation of the root target around the vertical axis.
+    pub rotation: Quat,
+}
+
+/// The movement of the root target between two times of a single clip, or
+/// the blend of several of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RootMotionSample {
+    translation: Vec3,
+    yaw: f32,
+}
+
+impl RootMotionSample {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            yaw: self.yaw + (other.yaw - self.yaw) * t,
+        }
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        Self {
+            translation: self.translation * factor,
+            yaw: self.yaw * factor,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            translation: self.translation + other.translation,
+            yaw: self.yaw + other.yaw,
+        }
+    }
+}
+
+/// The curves of a clip that animate the root target's [`Transform`].
+struct RootCurves<'a> {
+    translation: Option<&'a VariableCurve>,
+    rotation: Option<&'a VariableCurve>,
+}
+
+impl RootCurves<'_> {
+    /// Samples the horizontal translation and yaw of the root target at time `t`.
+    fn sample(&self, t: f32) -> RootMotionSample {
+        let mut translation = Vec3::ZERO;
+        if let Some(curve) = self.translation {
+            curve.0.sample_into(t, &mut translation);
+        }
+        let mut rotation = Quat::IDENTITY;
+        if let Some(curve) = self.rotation {
+            curve.0.sample_into(t, &mut rotation);
+        }
+        RootMotionSample {
+            translation: Vec3::new(translation.x, 0.0, translation.z),
+            yaw: yaw(rotation),
+        }
+    }
+
+    /// Returns the motion between the times `from` and `to` of the clip,
+    /// without wrapping around.
+    fn segment(&self, from: f32, to: f32) -> RootMotionSample {
+        let (from, to) = (self.sample(from), self.sample(to));
+        RootMotionSample {
+            translation: to.translation - from.translation,
+            yaw: wrap_angle(to.yaw - from.yaw),
+        }
+    }
+
+    /// Returns the motion of a clip of the given duration that was advanced
+    /// by `advance` from `from` to `to`, where `to` has wrapped around the
+    /// ends of the clip if `wrapped` is true.
+    ///
+    /// An advance longer than the clip wraps around more than once, and adds
+    /// the motion of a full cycle of the clip for each extra wraparound.
+    fn delta(
+        &self,
+        from: f32,
+        to: f32,
+        advance: f32,
+        wrapped: bool,
+        duration: f32,
+    ) -> RootMotionSample {
+        if !wrapped || duration <= 0.0 {
+            return self.segment(from, to);
+        }
+
+        let unwrapped = from + advance;
+        if advance >= 0.0 {
+            // Playing forward, the clip wrapped from its end back to its start.
+            let extra_cycles = (ops::floor(unwrapped / duration) - 1.0).max(0.0);
+            self.segment(from, duration)
+                .add(self.segment(0.0, duration).scale(extra_cycles))
+                .add(self.segment(0.0, to))
+        } else {
+            // Playing in reverse, the clip wrapped from its start back to its end.
+            let extra_cycles = (ops::ceil(-unwrapped / duration) - 1.0).max(0.0);
+            self.segment(from, 0.0)
+                .add(self.segment(duration, 0.0).scale(extra_cycles))
+                .add(self.segment(duration, to))
+        }
+    }
+}
+
+/// A system that computes the [`RootMotionDelta`] of every [`AnimationPlayer`]
+/// that has a [root motion target](AnimationPlayer::set_root_motion_target).
+///
+/// This must run after the animations have been advanced for this frame.
+pub fn extract_root_motion(
+    mut commands: Commands,
+    clips: Res<Assets<AnimationClip>>,
+    graphs: Res<Assets<AnimationGraph>>,
+    mut players: Query<(
+        Entity,
+        &AnimationPlayer,
+        &AnimationGraphHandle,
+        Option<&mut RootMotionDelta>,
+    )>,
+) {
+    let transform_fields = TransformFields::new();
+    for (entity, player, graph_handle, root_motion_delta) in &mut players {
+        let Some(root_target) = player.root_motion_target else {
+            continue;
+        };
+
+        let sample = graphs
+            .get(graph_handle)
+            .and_then(|graph| {
+                let target_mask = graph.mask_groups.get(&root_target).copied();
+                let context = RootMotionContext {
+                    graph,
+                    clips: &clips,
+                    player,
+                    root_target,
+                    target_mask: target_mask.unwrap_or_default(),
+                    transform_fields: &transform_fields,
+                    mode: RootMotionMode::Delta,
+                };
+                context.evaluate(graph.root, 0)
+            })
+            .map(|(sample, _)| sample)
+            .unwrap_or_default();
+
+        let delta = RootMotionDelta {
+            translation: sample.translation,
+            rotation: Quat::from_rotation_y(sample.yaw),
+        };
+        match root_motion_delta {
+            Some(mut root_motion_delta) => {
+                root_motion_delta.set_if_neq(delta);
+            }
+            None => {
+                commands.entity(entity).insert(delta);
+            }
+        }
+    }
+}
+
+/// The reflected field indices of the [`Transform`] fields that root motion
+/// is extracted from.
+struct TransformFields {
+    translation: usize,
+    rotation: usize,
+}
+
+impl TransformFields {
+    fn new() -> Self {
+        let TypeInfo::Struct(struct_info) = Transform::type_info() else {
+            unreachable!("`Transform` is a struct");
+        };
+        Self {
+            translation: struct_info.index_of("translation").unwrap(),
+            rotation: struct_info.index_of("rotation").unwrap(),
+        }
+    }
+
+    /// Returns the index of the field animated by a curve with the given
+    /// evaluator, if it's a field of [`Transform`].
+    fn field(&self, evaluator_id: EvaluatorId) -> Option<usize> {
+        match evaluator_id {
+            EvaluatorId::ComponentField(component_field) => {
+                let &(type_id, field) = &**component_field;
+                (type_id == core::any::TypeId::of::<Transform>()).then_some(field)
+            }
+            EvaluatorId::Type(_) => None,
+        }
+    }
+}
+
+/// What [`RootMotionContext::evaluate`] computes for each clip.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RootMotionMode {
+    /// The motion of the root target since the previous frame.
+    Delta,
+    /// The offset of the root target from the first frame of the clip.
+    Offset,
+}
+
+struct RootMotionContext<'a> {
+    graph: &'a AnimationGraph,
+    clips: &'a Assets<AnimationClip>,
+    player: &'a AnimationPlayer,
+    root_target: AnimationTargetId,
+    target_mask: u64,
+    transform_fields: &'a TransformFields,
+    mode: RootMotionMode,
+}
+
+impl RootMotionContext<'_> {
+    /// Computes the root motion of the subgraph rooted at `node_index`, as
+    /// selected by the [`RootMotionMode`], and the weight with which it's
+    /// blended into its parent.
+    ///
+    /// This mirrors the way that [`animate_targets`](crate::animate_targets)
+    /// blends the animations themselves. `mask` is the computed mask of the
+    /// parent node.
+    fn evaluate(
+        &self,
+        node_index: AnimationNodeIndex,
+        mut mask: u64,
+    ) -> Option<(RootMotionSample, f32)> {
+        let node = self.graph.get(node_index)?;
+        mask |= node.mask;
+        if self.target_mask & mask != 0 {
+            return None;
+        }
+        let node_weight = self.player.node_weight(node_index);
+
+        let additive = match node.node_type {
+            AnimationNodeType::Clip(ref clip) => {
+                let active_animation = self.player.animation(node_index)?;
+                let weight = active_animation.weight * node.weight * node_weight;
+                if weight == 0.0 {
+                    return None;
+                }
+                let clip = self.clips.get(clip)?;
+                let sample = match self.mode {
+                    // Paused animations don't move the root, but still pose it.
+                    RootMotionMode::Delta if active_animation.is_paused() => return None,
+                    RootMotionMode::Delta => self.clip_delta(clip, active_animation)?,
+                    RootMotionMode::Offset => self.clip_offset(clip, active_animation)?,
+                };
+                return Some((sample, weight));
+            }
+            AnimationNodeType::Add => true,
+            AnimationNodeType::Blend
+            | AnimationNodeType::StateMachine(_)
+            | AnimationNodeType::BlendSpace(_) => false,
+        };
+
+        // Blend the children in ascending order of node index, like the
+        // animation curve evaluators do.
+        let mut children: SmallVec<[AnimationNodeIndex; 8]> =
+            self.graph.graph.neighbors(node_index).collect();
+        children.sort_unstable();
+
+        let mut blend_register: Option<(RootMotionSample, f32)> = None;
+        for child in children {
+            let Some((sample, weight)) = self.evaluate(child, mask) else {
+                continue;
+            };
+            blend_register = Some(match blend_register {
+                None if additive => (sample.scale(weight), weight),
+                None => (sample, weight),
+                Some((current, current_weight)) if additive => {
+                    (current.add(sample.scale(weight)), current_weight + weight)
+                }
+                Some((current, current_weight)) => {
+                    let total_weight = current_weight + weight;
+                    (current.lerp(sample, weight / total_weight), total_weight)
+                }
+            });
+        }
+
+        blend_register.map(|(sample, _)| (sample, node.weight * node_weight))
+    }
+
+    /// Returns the curves of the clip that animate the root target, if any.
+    fn root_curves<'c>(&self, clip: &'c AnimationClip) -> Option<RootCurves<'c>> {
+        let mut root_curves = RootCurves {
+            translation: None,
+            rotation: None,
+        };
+        for curve in clip.curves_for_target(self.root_target)? {
+            match self.transform_fields.field(curve.0.evaluator_id()) {
+                Some(field) if field == self.transform_fields.translation => {
+                    root_curves.translation = Some(curve);
+                }
+                Some(field) if field == self.transform_fields.rotation => {
+                    root_curves.rotation = Some(curve);
+                }
+                _ => {}
+            }
+        }
+        if root_curves.translation.is_none() && root_curves.rotation.is_none() {
+            return None;
+        }
+        Some(root_curves)
+    }
+
+    /// Computes the root motion of a single clip since the previous frame.
+    fn clip_delta(
+        &self,
+        clip: &AnimationClip,
+        active_animation: &ActiveAnimation,
+    ) -> Option<RootMotionSample> {
+        let root_curves = self.root_curves(clip)?;
+        let last_seek_time = active_animation.last_seek_time?;
+        // If the animation completed this frame without finishing, it wrapped
+        // around.
+        let wrapped = active_animation.just_completed && !active_animation.is_finished();
+        Some(root_curves.delta(
+            last_seek_time,
+            active_animation.seek_time,
+            active_animation.last_seek_advance,
+            wrapped,
+            clip.duration,
+        ))
+    }
+
+    /// Computes the offset of the root target of a single clip from its
+    /// first frame.
+    fn clip_offset(
+        &self,
+        clip: &AnimationClip,
+        active_animation: &ActiveAnimation,
+    ) -> Option<RootMotionSample> {
+        let root_curves = self.root_curves(clip)?;
+        Some(root_curves.segment(0.0, active_animation.seek_time))
+    }
+}
+
+/// Removes the root motion of the playing animations from the given root
+/// target [`Transform`].
+///
+/// Only the horizontal translation and the rotation around the vertical axis
+/// that the animations add on top of their first frames are removed, so a
+/// root target that's posed away from the origin stays where it is.
+pub(crate) fn strip_root_motion(
+    transform: &mut Transform,
+    graph: &AnimationGraph,
+    clips: &Assets<AnimationClip>,
+    player: &AnimationPlayer,
+    root_target: AnimationTargetId,
+) {
+    let transform_fields = TransformFields::new();
+    let context = RootMotionContext {
+        graph,
+        clips,
+        player,
+        root_target,
+        target_mask: graph
+            .mask_groups
+            .get(&root_target)
+            .copied()
+            .unwrap_or_default(),
+        transform_fields: &transform_fields,
+        mode: RootMotionMode::Offset,
+    };
+    let Some((offset, _)) = context.evaluate(graph.root, 0) else {
+        return;
+    };
+
+    transform.translation.x -= offset.translation.x;
+    transform.translation.z -= offset.translation.z;
+    transform.rotation = Quat::from_rotation_y(-offset.yaw) * transform.rotation;
+}
+
+/// Returns the angle of the rotation of `rotation` around the vertical axis.
+fn yaw(rotation: Quat) -> f32 {
+    // This is the angle of the twist of the swing-twist decomposition around
+    // the y axis.
+    if rotation.y == 0.0 && rotation.w == 0.0 {
+        return 0.0;
+    }
+    wrap_angle(2.0 * ops::atan2(rotation.y, rotation.w))
+}
+
+/// Wraps an angle to the range `[-PI, PI]`.
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animated_field,
+        prelude::{AnimatableCurve, AnimatableKeyframeCurve, AnimatedField},
+        RepeatAnimation,
+    };
+    use bevy_ecs::{name::Name, system::RunSystemOnce, world::World};
+
+    #[test]
+    fn root_motion_blends_and_wraps_around() {
+        let root = AnimationTargetId::from_name(&Name::new("root"));
+        let walk_clip = |distance: f32| {
+            let mut clip = AnimationClip::default();
+            clip.add_curve_to_target(
+                root,
+                AnimatableCurve::new(
+                    animated_field!(Transform::translation),
+                    AnimatableKeyframeCurve::new([
+                        (0.0, Vec3::new(0.0, 1.0, 0.0)),
+                        (1.0, Vec3::new(0.0, 1.0, distance)),
+                    ])
+                    .unwrap(),
+                ),
+            );
+            clip
+        };
+
+        let mut clips = Assets::<AnimationClip>::default();
+        let mut graph = AnimationGraph::new();
+        let walk = graph.add_clip(clips.add(walk_clip(2.0)), 1.0, graph.root);
+        let run = graph.add_clip(clips.add(walk_clip(6.0)), 1.0, graph.root);
+        let mut graphs = Assets::<AnimationGraph>::default();
+        let graph = graphs.add(graph);
+
+        let mut world = World::new();
+        world.insert_resource(clips);
+        world.insert_resource(graphs);
+
+        let mut player = AnimationPlayer::default();
+        player.set_root_motion_target(Some(root));
+        player.play(walk).repeat();
+        player.play(run).repeat().set_weight(3.0);
+        let player = world.spawn((player, AnimationGraphHandle(graph))).id();
+
+        let update = |world: &mut World, delta: f32| {
+            let mut animation_player = world.get_mut::<AnimationPlayer>(player).unwrap();
+            for (_, active_animation) in animation_player.playing_animations_mut() {
+                active_animation.update(delta, 1.0);
+            }
+            world.run_system_once(extract_root_motion).unwrap();
+            world.get::<RootMotionDelta>(player).unwrap().translation
+        };
+
+        // The clips are blended with weights 1/4 and 3/4.
+        let expected_speed = 0.25 * 2.0 + 0.75 * 6.0;
+        assert!(update(&mut world, 0.5).abs_diff_eq(Vec3::Z * 0.5 * expected_speed, 1e-5));
+        assert!(update(&mut world, 0.25).abs_diff_eq(Vec3::Z * 0.25 * expected_speed, 1e-5));
+        // Wrapping around the end of the clips doesn't move the root back.
+        assert!(update(&mut world, 0.5).abs_diff_eq(Vec3::Z * 0.5 * expected_speed, 1e-5));
+
+        // A clip that finishes stops moving the root.
+        world
+            .get_mut::<AnimationPlayer>(player)
+            .unwrap()
+            .stop(run)
+            .animation_mut(walk)
+            .unwrap()
+            .set_repeat(RepeatAnimation::Count(2));
+        assert!(update(&mut world, 1.0).abs_diff_eq(Vec3::Z * 2.0 * 0.75, 1e-5));
+        assert_eq!(update(&mut world, 0.5), Vec3::ZERO);
+    }
+
+    #[test]
+    fn root_motion_counts_every_cycle_of_a_long_frame() {
+        let root = AnimationTargetId::from_name(&Name::new("root"));
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            root,
+            AnimatableCurve::new(
+                animated_field!(Transform::translation),
+                AnimatableKeyframeCurve::new([(0.0, Vec3::ZERO), (1.0, Vec3::new(0.0, 0.0, 2.0))])
+                    .unwrap(),
+            ),
+        );
+
+        let mut clips = Assets::<AnimationClip>::default();
+        let (graph, walk) = AnimationGraph::from_clip(clips.add(clip));
+        let mut graphs = Assets::<AnimationGraph>::default();
+        let graph = graphs.add(graph);
+
+        let mut world = World::new();
+        world.insert_resource(clips);
+        world.insert_resource(graphs);
+
+        let mut player = AnimationPlayer::default();
+        player.set_root_motion_target(Some(root));
+        player.play(walk).repeat();
+        let player = world.spawn((player, AnimationGraphHandle(graph))).id();
+
+        let update = |world: &mut World, delta: f32| {
+            let mut animation_player = world.get_mut::<AnimationPlayer>(player).unwrap();
+            animation_player
+                .animation_mut(walk)
+                .unwrap()
+                .update(delta, 1.0);
+            world.run_system_once(extract_root_motion).unwrap();
+            world.get::<RootMotionDelta>(player).unwrap().translation
+        };
+
+        assert!(update(&mut world, 0.25).abs_diff_eq(Vec3::Z * 0.5, 1e-5));
+        // A frame longer than two cycles of the clip moves the root by all of
+        // them.
+        assert!(update(&mut world, 2.5).abs_diff_eq(Vec3::Z * 5.0, 1e-5));
+
+        // The same goes for clips playing in reverse.
+        world
+            .get_mut::<AnimationPlayer>(player)
+            .unwrap()
+            .animation_mut(walk)
+            .unwrap()
+            .set_speed(-1.0);
+        assert!(update(&mut world, 2.5).abs_diff_eq(Vec3::Z * -5.0, 1e-5));
+        assert!(update(&mut world, 1.0).abs_diff_eq(Vec3::Z * -2.0, 1e-5));
+    }
+
+    #[test]
+    fn strip_root_motion_keeps_the_first_frame_and_vertical_motion() {
+        let root = AnimationTargetId::from_name(&Name::new("root"));
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            root,
+            AnimatableCurve::new(
+                animated_field!(Transform::translation),
+                AnimatableKeyframeCurve::new([
+                    (0.0, Vec3::new(1.0, 0.0, 1.0)),
+                    (1.0, Vec3::new(1.0, 4.0, 3.0)),
+                ])
+                .unwrap(),
+            ),
+        );
+        clip.add_curve_to_target(
+            root,
+            AnimatableCurve::new(
+                animated_field!(Transform::rotation),
+                AnimatableKeyframeCurve::new([
+                    (0.0, Quat::from_rotation_y(0.5)),
+                    (1.0, Quat::from_rotation_y(1.5)),
+                ])
+                .unwrap(),
+            ),
+        );
+
+        let mut clips = Assets::<AnimationClip>::default();
+        let (graph, walk) = AnimationGraph::from_clip(clips.add(clip));
+        let mut player = AnimationPlayer::default();
+        player.play(walk).seek_to(0.5);
+
+        // The pose of the root target halfway through the clip.
+        let mut transform = Transform::from_xyz(1.0, 2.0, 2.0)
+            .with_rotation(Quat::from_rotation_y(1.0) * Quat::from_rotation_x(0.5));
+        strip_root_motion(&mut transform, &graph, &clips, &player, root);
+
+        // Only the motion since the first frame is removed.
+        assert!(transform
+            .translation
+            .abs_diff_eq(Vec3::new(1.0, 2.0, 1.0), 1e-5));
+        assert!(transform.rotation.abs_diff_eq(
+            Quat::from_rotation_y(0.5) * Quat::from_rotation_x(0.5),
+            1e-5
+        ));
+    }
+}
@@ -0,0 +1,891 @@
+//! Animation state machines.
+//!
+//! A *state machine node* in an [`AnimationGraph`] plays one of its children,
+//! the *current state*, at a time. Every frame, the transitions of the state
+//! machine are checked against the [`AnimationParameters`] on the same entity
+//! as the [`AnimationPlayer`], and when one of them applies, the state machine
+//! crossfades from the current state to the transition's target state.
+//!
+//! State machines are plain data, so they can be authored in `.animgraph.ron`
+//! files alongside the rest of the [`AnimationGraph`].
+
+use bevy_asset::Assets;
+use bevy_ecs::{
+    component::Component,
+    reflect::ReflectComponent,
+    system::{Query, Res},
+};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_time::Time;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use thiserror::Error;
+
+use crate::{
+    graph::{AnimationGraph, AnimationGraphHandle, AnimationNodeIndex, AnimationNodeType},
+    ActiveAnimation, AnimationClip, AnimationPlayer, RepeatAnimation,
+};
+
+/// The data of a [state machine node](AnimationNodeType::StateMachine).
+///
+/// Each [state](AnimationState) refers to a child node of the state machine
+/// node. Only the current state, and the states that are being faded out, are
+/// played.
+#[derive(Clone, Default, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Default, Debug, PartialEq)]
+pub struct AnimationStateMachine {
+    /// The states of this state machine.
+    ///
+    /// The first state is the initial state.
+    pub states: Vec<AnimationState>,
+
+    /// The transitions between the states of this state machine.
+    ///
+    /// Transitions are checked in order, and at most one transition is taken
+    /// per frame.
+    #[serde(default)]
+    pub transitions: Vec<AnimationStateTransition>,
+}
+
+/// A named state of an [`AnimationStateMachine`].
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub struct AnimationState {
+    /// The name of the state, which transitions use to refer to it.
+    pub name: String,
+
+    /// The child node of the state machine node that is played while this
+    /// state is active.
+    pub node: AnimationNodeIndex,
+
+    /// Whether the animation clips of this state repeat forever while the
+    /// state is active.
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+/// A transition between two states of an [`AnimationStateMachine`].
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub struct AnimationStateTransition {
+    /// The name of the state this transition starts from.
+    ///
+    /// If this is `None`, the transition can be taken from any state other
+    /// than [`Self::to`].
+    #[serde(default)]
+    pub from: Option<String>,
+
+    /// The name of the state this transition leads to.
+    pub to: String,
+
+    /// The conditions that must all be met for the transition to be taken.
+    #[serde(default)]
+    pub conditions: Vec<AnimationCondition>,
+
+    /// The time, in seconds, over which the previous state is faded out.
+    #[serde(default)]
+    pub crossfade_duration: f32,
+
+    /// The time after entering the current state before which this transition
+    /// can't be taken.
+    ///
+    /// If the current state is a clip node, this is measured in multiples of
+    /// the duration of its clip, so `Some(1.0)` waits for the clip to play
+    /// through once. Otherwise, it's measured in seconds.
+    #[serde(default)]
+    pub exit_time: Option<f32>,
+}
+
+/// A condition on an [`AnimationParameters`] value that guards an
+/// [`AnimationStateTransition`].
+///
+/// Parameters that are missing are treated as `false` or `0`.
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum AnimationCondition {
+    /// The boolean parameter with the given name is `true`.
+    If(String),
+    /// The boolean parameter with the given name is `false`.
+    IfNot(String),
+    /// The numeric parameter with the given name is greater than the value.
+    Greater(String, f32),
+    /// The numeric parameter with the given name is less than the value.
+    Less(String, f32),
+    /// The trigger with the given name is set.
+    ///
+    /// Taking the transition resets the trigger.
+    Triggered(String),
+}
+
+/// The value of a parameter in [`AnimationParameters`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum AnimationParameter {
+    /// A boolean parameter.
+    Bool(bool),
+    /// A floating-point parameter.
+    Float(f32),
+    /// An integer parameter.
+    Int(i32),
+    /// A trigger, which is reset once a transition has been taken because of
+    /// it.
+    Trigger,
+}
+
+/// The named parameters that drive the [`AnimationStateMachine`]s and
+/// [`AnimationBlendSpace`](crate::blend_space::AnimationBlendSpace)s played by
+/// the [`AnimationPlayer`] on the same entity.
+#[derive(Component, Clone, Default, Debug, Reflect)]
+#[reflect(Component, Default, Clone, Debug)]
+pub struct AnimationParameters {
+    values: HashMap<String, AnimationParameter>,
+}
+
+/// The state of an [`AnimationStateMachine`] being played by an
+/// [`AnimationPlayer`].
+#[derive(Clone, Default, Debug, Reflect)]
+#[reflect(Clone, Default, Debug)]
+pub struct ActiveStateMachine {
+    /// The index of the current state in [`AnimationStateMachine::states`].
+    current_state: usize,
+    /// The time, in seconds, since the current state was entered.
+    state_elapsed: f32,
+    /// The states that are being faded out, oldest first.
+    fading_states: Vec<FadingState>,
+}
+
+/// A state that is being faded out as part of a transition.
+#[derive(Clone, Copy, Debug, Reflect)]
+#[reflect(Clone, Debug)]
+struct FadingState {
+    /// The index of the state in [`AnimationStateMachine::states`].
+    state: usize,
+    /// The current weight. Starts at 1.0 and goes to 0.0 during the fade-out.
+    current_weight: f32,
+    /// How much to decrease `current_weight` per second.
+    weight_decline_per_sec: f32,
+}
+
+impl AnimationStateMachine {
+    /// Creates a new, empty state machine.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a state playing the given child `node`.
+    pub fn with_state(mut self, name: impl Into<String>, node: AnimationNodeIndex) -> Self {
+        self.states.push(AnimationState {
+            name: name.into(),
+            node,
+            repeat: false,
+        });
+        self
+    }
+
+    /// Adds a state playing the given child `node`, whose clips repeat forever
+    /// while the state is active.
+    pub fn with_repeating_state(
+        mut self,
+        name: impl Into<String>,
+        node: AnimationNodeIndex,
+    ) -> Self {
+        self.states.push(AnimationState {
+            name: name.into(),
+            node,
+            repeat: true,
+        });
+        self
+    }
+
+    /// Adds a transition, which is checked after all the transitions that
+    /// were added before it.
+    pub fn with_transition(mut self, transition: AnimationStateTransition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    /// Returns the index of the state with the given name, if any.
+    pub fn state_index(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|state| state.name == name)
+    }
+
+    /// Checks that every state of this state machine plays a child of the
+    /// state machine node `node` in `animation_graph`, and that every
+    /// transition refers to existing states.
+    pub fn validate(
+        &self,
+        animation_graph: &AnimationGraph,
+        node: AnimationNodeIndex,
+    ) -> Result<(), AnimationStateMachineError> {
+        for state in &self.states {
+            if !animation_graph
+                .graph
+                .neighbors_directed(node, Direction::Outgoing)
+                .any(|child| child == state.node)
+            {
+                return Err(AnimationStateMachineError::StateNodeNotAChild {
+                    state: state.name.clone(),
+                    node: state.node,
+                });
+            }
+        }
+        for transition in &self.transitions {
+            for name in transition.from.iter().chain([&transition.to]) {
+                if self.state_index(name).is_none() {
+                    return Err(AnimationStateMachineError::UnknownState(name.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error found by [`AnimationStateMachine::validate`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AnimationStateMachineError {
+    /// A state plays a node that isn't a child of the state machine node.
+    #[error("The state `{state}` plays the node {node:?}, which isn't a child of its state machine node")]
+    StateNodeNotAChild {
+        /// The name of the state.
+        state: String,
+        /// The node the state plays.
+        node: AnimationNodeIndex,
+    },
+    /// A transition refers to a state that doesn't exist.
+    #[error("A transition refers to the state `{0}`, which doesn't exist")]
+    UnknownState(String),
+}
+
+impl AnimationStateTransition {
+    /// Creates an unconditional transition from the state named `from` to the
+    /// state named `to`, without a crossfade.
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: Some(from.into()),
+            ..Self::from_any(to)
+        }
+    }
+
+    /// Creates an unconditional transition from any state to the state named
+    /// `to`, without a crossfade.
+    pub fn from_any(to: impl Into<String>) -> Self {
+        Self {
+            from: None,
+            to: to.into(),
+            conditions: Vec::new(),
+            crossfade_duration: 0.0,
+            exit_time: None,
+        }
+    }
+
+    /// Adds a condition that must be met for this transition to be taken.
+    pub fn with_condition(mut self, condition: AnimationCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Sets the time, in seconds, over which the previous state is faded out.
+    pub fn with_crossfade(mut self, duration: f32) -> Self {
+        self.crossfade_duration = duration;
+        self
+    }
+
+    /// Sets the [exit time](Self::exit_time) of this transition.
+    pub fn with_exit_time(mut self, exit_time: f32) -> Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+}
+
+impl AnimationCondition {
+    /// Returns true if this condition is met by the given `parameters`.
+    pub fn is_met(&self, parameters: Option<&AnimationParameters>) -> bool {
+        let get = |name: &str| parameters.and_then(|parameters| parameters.get(name));
+        match self {
+            AnimationCondition::If(name) => get(name) == Some(AnimationParameter::Bool(true)),
+            AnimationCondition::IfNot(name) => get(name) != Some(AnimationParameter::Bool(true)),
+            AnimationCondition::Greater(name, value) => {
+                get(name).map_or(0.0, AnimationParameter::as_f32) > *value
+            }
+            AnimationCondition::Less(name, value) => {
+                get(name).map_or(0.0, AnimationParameter::as_f32) < *value
+            }
+            AnimationCondition::Triggered(name) => get(name) == Some(AnimationParameter::Trigger),
+        }
+    }
+}
+
+impl AnimationParameter {
+    /// Returns the numeric value of this parameter.
+    ///
+    /// Booleans and triggers are treated as `1.0` when set and `0.0` otherwise.
+    pub fn as_f32(self) -> f32 {
+        match self {
+            AnimationParameter::Bool(value) => value as u8 as f32,
+            AnimationParameter::Float(value) => value,
+            AnimationParameter::Int(value) => value as f32,
+            AnimationParameter::Trigger => 1.0,
+        }
+    }
+}
+
+impl AnimationParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of the parameter with the given name, if it's set.
+    pub fn get(&self, name: &str) -> Option<AnimationParameter> {
+        self.values.get(name).copied()
+    }
+
+    /// Sets the parameter with the given name to `value`.
+    pub fn set(&mut self, name: impl Into<String>, value: AnimationParameter) -> &mut Self {
+        self.values.insert(name.into(), value);
+        self
+    }
+
+    /// Sets the boolean parameter with the given name.
+    pub fn set_bool(&mut self, name: impl Into<String>, value: bool) -> &mut Self {
+        self.set(name, AnimationParameter::Bool(value))
+    }
+
+    /// Sets the floating-point parameter with the given name.
+    pub fn set_float(&mut self, name: impl Into<String>, value: f32) -> &mut Self {
+        self.set(name, AnimationParameter::Float(value))
+    }
+
+    /// Sets the integer parameter with the given name.
+    pub fn set_int(&mut self, name: impl Into<String>, value: i32) -> &mut Self {
+        self.set(name, AnimationParameter::Int(value))
+    }
+
+    /// Sets the trigger with the given name.
+    ///
+    /// The trigger stays set until a transition is taken because of it, or
+    /// until it's [removed](Self::remove).
+    pub fn set_trigger(&mut self, name: impl Into<String>) -> &mut Self {
+        self.set(name, AnimationParameter::Trigger)
+    }
+
+    /// Removes the parameter with the given name, returning its value if it
+    /// was set.
+    pub fn remove(&mut self, name: &str) -> Option<AnimationParameter> {
+        self.values.remove(name)
+    }
+}
+
+impl ActiveStateMachine {
+    /// Returns the index of the current state in
+    /// [`AnimationStateMachine::states`].
+    pub fn current_state(&self) -> usize {
+        self.current_state
+    }
+
+    /// Returns the time, in seconds, since the current state was entered.
+    pub fn state_elapsed(&self) -> f32 {
+        self.state_elapsed
+    }
+
+    /// Returns true if previous states are still being faded out.
+    pub fn is_transitioning(&self) -> bool {
+        !self.fading_states.is_empty()
+    }
+
+    /// Drops the states that no longer exist in `state_machine`, for example
+    /// because its graph was reloaded with fewer states. Returns true if the
+    /// current state no longer exists, in which case the state machine is
+    /// reset to its initial state.
+    fn forget_missing_states(&mut self, state_machine: &AnimationStateMachine) -> bool {
+        let state_count = state_machine.states.len();
+        self.fading_states
+            .retain(|fading_state| fading_state.state < state_count);
+        if self.current_state < state_count {
+            return false;
+        }
+        *self = Self::default();
+        true
+    }
+
+    /// Returns true if the state with the given index is the current state or
+    /// is being faded out.
+    fn is_state_active(&self, state: usize) -> bool {
+        self.current_state == state
+            || self
+                .fading_states
+                .iter()
+                .any(|fading_state| fading_state.state == state)
+    }
+}
+
+/// A system that takes the transitions of the state machines in every
+/// [`AnimationPlayer`]'s graph, and updates the weights of their states.
+///
+/// The state machines start and stop the animation clips of their states on
+/// the [`AnimationPlayer`] as states are entered and faded out.
+pub fn advance_state_machines(
+    time: Res<Time>,
+    animation_clips: Res<Assets<AnimationClip>>,
+    animation_graphs: Res<Assets<AnimationGraph>>,
+    mut players: Query<(
+        &mut AnimationPlayer,
+        &AnimationGraphHandle,
+        Option<&mut AnimationParameters>,
+    )>,
+) {
+    let delta_seconds = time.delta_secs();
+    for (mut player, graph_handle, mut parameters) in &mut players {
+        let Some(animation_graph) = animation_graphs.get(graph_handle) else {
+            continue;
+        };
+
+        let AnimationPlayer {
+            ref mut active_animations,
+            ref mut state_machines,
+            ref mut node_weights,
+            ..
+        } = *player;
+
+        // The weights are reassigned from scratch every frame, so that nodes
+        // that stopped being states or blend space points, for example because
+        // the graph was reloaded, fall back to their own weight.
+        node_weights.clear();
+        state_machines.retain(|&node_index, _| {
+            matches!(
+                animation_graph.get(node_index).map(|node| &node.node_type),
+                Some(AnimationNodeType::StateMachine(_))
+            )
+        });
+
+        for node_index in animation_graph.graph.node_indices() {
+            let AnimationNodeType::StateMachine(ref state_machine) =
+                animation_graph[node_index].node_type
+            else {
+                continue;
+            };
+            if state_machine.states.is_empty() {
+                continue;
+            }
+
+            let active_state_machine = state_machines.entry(node_index).or_insert_with(|| {
+                enter_state(animation_graph, state_machine, 0, active_animations);
+                ActiveStateMachine::default()
+            });
+            if active_state_machine.forget_missing_states(state_machine) {
+                enter_state(animation_graph, state_machine, 0, active_animations);
+            }
+            active_state_machine.state_elapsed += delta_seconds;
+
+            // Take the first transition whose conditions are met, if any.
+            let current_state = &state_machine.states[active_state_machine.current_state];
+            let transition = state_machine.transitions.iter().find_map(|transition| {
+                let to = state_machine.state_index(&transition.to)?;
+                let applies = match transition.from {
+                    Some(ref from) => *from == current_state.name,
+                    None => to != active_state_machine.current_state,
+                };
+                let exited = transition.exit_time.is_none_or(|exit_time| {
+                    let state_duration = match animation_graph
+                        .get(current_state.node)
+                        .map(|node| &node.node_type)
+                    {
+                        Some(AnimationNodeType::Clip(clip)) => {
+                            animation_clips.get(clip).map_or(1.0, |clip| clip.duration)
+                        }
+                        _ => 1.0,
+                    };
+                    active_state_machine.state_elapsed >= exit_time * state_duration
+                });
+                let conditions_met = transition
+                    .conditions
+                    .iter()
+                    .all(|condition| condition.is_met(parameters.as_deref()));
+                (applies && exited && conditions_met).then_some((transition, to))
+            });
+
+            if let Some((transition, to)) = transition {
+                if let Some(ref mut parameters) = parameters {
+                    for condition in &transition.conditions {
+                        if let AnimationCondition::Triggered(name) = condition {
+                            parameters.remove(name);
+                        }
+                    }
+                }
+
+                let previous_state = active_state_machine.current_state;
+                active_state_machine.fading_states.push(FadingState {
+                    state: previous_state,
+                    current_weight: 1.0,
+                    weight_decline_per_sec: 1.0 / transition.crossfade_duration,
+                });
+                // If already fading out the new state, cancel that fade-out, so
+                // that its clips aren't stopped once it completes.
+                active_state_machine
+                    .fading_states
+                    .retain(|fading_state| fading_state.state != to);
+                active_state_machine.current_state = to;
+                active_state_machine.state_elapsed = 0.0;
+                enter_state(animation_graph, state_machine, to, active_animations);
+            }
+
+            // Fade out the previous states. Like in `advance_transitions`, the
+            // most recent fade-out takes as much weight as it wants, and the
+            // current state receives whatever's left.
+            let mut remaining_weight = 1.0;
+            for state in &state_machine.states {
+                node_weights.insert(state.node, 0.0);
+            }
+            for fading_state in active_state_machine.fading_states.iter_mut().rev() {
+                fading_state.current_weight = (fading_state.current_weight
+                    - fading_state.weight_decline_per_sec * delta_seconds)
+                    .max(0.0);
+                let weight = fading_state.current_weight * remaining_weight;
+                *node_weights
+                    .get_mut(&state_machine.states[fading_state.state].node)
+                    .unwrap() += weight;
+                remaining_weight -= weight;
+            }
+            *node_weights
+                .get_mut(&state_machine.states[active_state_machine.current_state].node)
+                .unwrap() += remaining_weight;
+
+            // Stop the clips of the states that have been faded out completely.
+            let mut expired_states = SmallVec::<[usize; 4]>::new();
+            active_state_machine.fading_states.retain(|fading_state| {
+                let expired = fading_state.current_weight <= 0.0;
+                if expired {
+                    expired_states.push(fading_state.state);
+                }
+                !expired
+            });
+            for state in expired_states {
+                if !active_state_machine.is_state_active(state) {
+                    exit_state(
+                        animation_graph,
+                        state_machine,
+                        state,
+                        active_state_machine,
+                        active_animations,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Starts all the animation clips of the given state from the beginning.
+fn enter_state(
+    animation_graph: &AnimationGraph,
+    state_machine: &AnimationStateMachine,
+    state: usize,
+    active_animations: &mut HashMap<AnimationNodeIndex, ActiveAnimation>,
+) {
+    let Some(state) = state_machine.states.get(state) else {
+        return;
+    };
+    for_each_clip(animation_graph, state.node, |clip_node| {
+        let active_animation = active_animations.entry(clip_node).or_default();
+        active_animation.replay();
+        if state.repeat {
+            active_animation.set_repeat(RepeatAnimation::Forever);
+        }
+    });
+}
+
+/// Stops the animation clips of the given state, unless they're also part of
+/// a state that's still active.
+fn exit_state(
+    animation_graph: &AnimationGraph,
+    state_machine: &AnimationStateMachine,
+    state: usize,
+    active_state_machine: &ActiveStateMachine,
+    active_animations: &mut HashMap<AnimationNodeIndex, ActiveAnimation>,
+) {
+    let mut still_playing = SmallVec::<[AnimationNodeIndex; 8]>::new();
+    for (index, other_state) in state_machine.states.iter().enumerate() {
+        if active_state_machine.is_state_active(index) {
+            for_each_clip(animation_graph, other_state.node, |clip_node| {
+                still_playing.push(clip_node);
+            });
+        }
+    }
+
+    let Some(state) = state_machine.states.get(state) else {
+        return;
+    };
+    for_each_clip(animation_graph, state.node, |clip_node| {
+        if !still_playing.contains(&clip_node) {
+            active_animations.remove(&clip_node);
+        }
+    });
+}
+
+/// Calls `f` with every clip node in the subgraph rooted at `node`.
+fn for_each_clip(
+    animation_graph: &AnimationGraph,
+    node: AnimationNodeIndex,
+    mut f: impl FnMut(AnimationNodeIndex),
+) {
+    let mut stack: SmallVec<[AnimationNodeIndex; 8]> = SmallVec::new();
+    stack.push(node);
+    while let Some(node) = stack.pop() {
+        let Some(animation_graph_node) = animation_graph.get(node) else {
+            continue;
+        };
+        if let AnimationNodeType::Clip(_) = animation_graph_node.node_type {
+            f(node);
+        }
+        stack.extend(
+            animation_graph
+                .graph
+                .neighbors_directed(node, Direction::Outgoing),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{SerializedAnimationGraph, SerializedAnimationNodeType};
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use core::time::Duration;
+
+    fn locomotion_state_machine(
+        idle: AnimationNodeIndex,
+        run: AnimationNodeIndex,
+    ) -> AnimationStateMachine {
+        AnimationStateMachine::new()
+            .with_repeating_state("idle", idle)
+            .with_state("run", run)
+            .with_transition(
+                AnimationStateTransition::new("idle", "run")
+                    .with_condition(AnimationCondition::Greater("speed".into(), 0.5))
+                    .with_crossfade(0.5),
+            )
+            .with_transition(AnimationStateTransition::new("run", "idle").with_exit_time(1.0))
+            .with_transition(
+                AnimationStateTransition::from_any("idle")
+                    .with_condition(AnimationCondition::Triggered("reset".into())),
+            )
+    }
+
+    #[test]
+    fn state_machine_round_trips_through_ron() {
+        let mut graph = AnimationGraph::new();
+        let state_machine = graph.add_state_machine(AnimationStateMachine::new(), 1.0, graph.root);
+        let idle = graph.add_blend(1.0, state_machine);
+        let run = graph.add_blend(1.0, state_machine);
+        *graph.state_machine_mut(state_machine).unwrap() = locomotion_state_machine(idle, run);
+
+        let mut ron = String::new();
+        graph.save(&mut ron).unwrap();
+        let serialized: SerializedAnimationGraph = ron::de::from_str(&ron).unwrap();
+
+        let SerializedAnimationNodeType::StateMachine(ref deserialized) =
+            serialized.graph[state_machine].node_type
+        else {
+            panic!("expected a state machine node");
+        };
+        assert_eq!(*deserialized, locomotion_state_machine(idle, run));
+    }
+
+    #[test]
+    fn transitions_crossfade_between_states() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+
+        let mut clips = Assets::<AnimationClip>::default();
+        let mut clip = AnimationClip::default();
+        clip.set_duration(1.0);
+        let idle_clip = clips.add(clip.clone());
+        let run_clip = clips.add(clip);
+        world.insert_resource(clips);
+
+        let mut graph = AnimationGraph::new();
+        let state_machine = graph.add_state_machine(AnimationStateMachine::new(), 1.0, graph.root);
+        let idle = graph.add_clip(idle_clip, 1.0, state_machine);
+        let run = graph.add_clip(run_clip, 1.0, state_machine);
+        *graph.state_machine_mut(state_machine).unwrap() = locomotion_state_machine(idle, run);
+        let mut graphs = Assets::<AnimationGraph>::default();
+        let graph = graphs.add(graph);
+        world.insert_resource(graphs);
+
+        let player = world
+            .spawn((
+                AnimationPlayer::default(),
+                AnimationGraphHandle(graph),
+                AnimationParameters::new(),
+            ))
+            .id();
+
+        let update = |world: &mut World| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(0.25));
+            world.run_system_once(advance_state_machines).unwrap();
+            let player = world.get::<AnimationPlayer>(player).unwrap();
+            let current_state = player.state_machine(state_machine).unwrap().current_state();
+            let weights = (player.node_weight(idle), player.node_weight(run));
+            (current_state, weights, player.is_playing_animation(idle))
+        };
+
+        assert_eq!(update(&mut world), (0, (1.0, 0.0), true));
+        world
+            .get_mut::<AnimationParameters>(player)
+            .unwrap()
+            .set_float("speed", 1.0);
+        assert_eq!(update(&mut world), (1, (0.5, 0.5), true));
+        assert_eq!(update(&mut world), (1, (0.0, 1.0), false));
+
+        // `run` can only be left once its clip has played through once.
+        world
+            .get_mut::<AnimationParameters>(player)
+            .unwrap()
+            .set_float("speed", 0.0);
+        assert_eq!(update(&mut world).0, 1);
+        assert_eq!(update(&mut world).0, 1);
+        assert_eq!(update(&mut world), (0, (1.0, 0.0), true));
+
+        // Any-state transitions don't lead back into their own target state.
+        world
+            .get_mut::<AnimationParameters>(player)
+            .unwrap()
+            .set_trigger("reset");
+        assert_eq!(update(&mut world).0, 0);
+        world
+            .get_mut::<AnimationParameters>(player)
+            .unwrap()
+            .set_float("speed", 1.0);
+        assert_eq!(update(&mut world).0, 1);
+
+        // Triggers are reset once a transition has been taken because of them.
+        assert_eq!(update(&mut world).0, 0);
+        let parameters = world.get::<AnimationParameters>(player).unwrap();
+        assert_eq!(parameters.get("reset"), None);
+    }
+
+    #[test]
+    fn reloading_with_fewer_states_resets_the_state_machine() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Assets<AnimationClip>>();
+
+        let mut graph = AnimationGraph::new();
+        let state_machine = graph.add_state_machine(AnimationStateMachine::new(), 1.0, graph.root);
+        let idle = graph.add_blend(1.0, state_machine);
+        let run = graph.add_blend(1.0, state_machine);
+        *graph.state_machine_mut(state_machine).unwrap() = locomotion_state_machine(idle, run);
+        let mut graphs = Assets::<AnimationGraph>::default();
+        let graph_handle = graphs.add(graph.clone());
+        world.insert_resource(graphs);
+
+        let mut parameters = AnimationParameters::new();
+        parameters.set_float("speed", 1.0);
+        let player = world
+            .spawn((
+                AnimationPlayer::default(),
+                AnimationGraphHandle(graph_handle.clone()),
+                parameters,
+            ))
+            .id();
+        let update = |world: &mut World| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(0.25));
+            world.run_system_once(advance_state_machines).unwrap();
+            let player = world.get::<AnimationPlayer>(player).unwrap();
+            let active_state_machine = player.state_machine(state_machine).unwrap();
+            (
+                active_state_machine.current_state(),
+                active_state_machine.is_transitioning(),
+            )
+        };
+        assert_eq!(update(&mut world), (1, true));
+
+        // Reload the graph with only the `run` state, whose node is removed
+        // from the graph as well.
+        graph.graph.remove_node(idle);
+        let run = graph.graph.node_indices().next_back().unwrap();
+        *graph.state_machine_mut(state_machine).unwrap() =
+            AnimationStateMachine::new().with_state("run", run);
+        world
+            .resource_mut::<Assets<AnimationGraph>>()
+            .insert(&graph_handle, graph)
+            .unwrap();
+        assert_eq!(update(&mut world), (0, false));
+        assert_eq!(update(&mut world), (0, false));
+    }
+
+    #[test]
+    fn reloading_without_the_state_machine_forgets_its_weights() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Assets<AnimationClip>>();
+
+        let mut graph = AnimationGraph::new();
+        let state_machine = graph.add_state_machine(AnimationStateMachine::new(), 1.0, graph.root);
+        let idle = graph.add_blend(1.0, state_machine);
+        let run = graph.add_blend(1.0, state_machine);
+        *graph.state_machine_mut(state_machine).unwrap() = locomotion_state_machine(idle, run);
+        let mut graphs = Assets::<AnimationGraph>::default();
+        let graph_handle = graphs.add(graph.clone());
+        world.insert_resource(graphs);
+
+        let player = world
+            .spawn((
+                AnimationPlayer::default(),
+                AnimationGraphHandle(graph_handle.clone()),
+            ))
+            .id();
+        let update = |world: &mut World| {
+            world.run_system_once(advance_state_machines).unwrap();
+            let player = world.get::<AnimationPlayer>(player).unwrap();
+            (
+                player.state_machine(state_machine).is_some(),
+                player.node_weight(run),
+            )
+        };
+        assert_eq!(update(&mut world), (true, 0.0));
+
+        // Reload the graph with a plain blend node in place of the state
+        // machine.
+        graph[state_machine].node_type = AnimationNodeType::Blend;
+        world
+            .resource_mut::<Assets<AnimationGraph>>()
+            .insert(&graph_handle, graph)
+            .unwrap();
+        assert_eq!(update(&mut world), (false, 1.0));
+    }
+
+    #[test]
+    fn validate_rejects_unknown_states_and_nodes() {
+        let mut graph = AnimationGraph::new();
+        let state_machine = graph.add_state_machine(AnimationStateMachine::new(), 1.0, graph.root);
+        let idle = graph.add_blend(1.0, state_machine);
+        let run = graph.add_blend(1.0, state_machine);
+        assert_eq!(
+            locomotion_state_machine(idle, run).validate(&graph, state_machine),
+            Ok(())
+        );
+
+        let not_a_child = graph.add_blend(1.0, graph.root);
+        assert_eq!(
+            locomotion_state_machine(idle, not_a_child).validate(&graph, state_machine),
+            Err(AnimationStateMachineError::StateNodeNotAChild {
+                state: "run".into(),
+                node: not_a_child,
+            })
+        );
+
+        let unknown_state = AnimationStateMachine::new()
+            .with_state("idle", idle)
+            .with_transition(AnimationStateTransition::new("idle", "jump"));
+        assert_eq!(
+            unknown_state.validate(&graph, state_machine),
+            Err(AnimationStateMachineError::UnknownState("jump".into()))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    First, Main, MainSchedulePlugin, PlaceholderPlugin, Plugin, Plugins, PluginsState, SubApp,
-    SubApps,
+    First, Main, MainScheduleOrder, MainSchedulePlugin, PlaceholderPlugin, Plugin, Plugins,
+    PluginsState, PostUpdate, PreUpdate, SubApp, SubApps, Update,
 };
 use alloc::{
     boxed::Box,
@@ -10,8 +10,10 @@ use alloc::{
 pub use bevy_derive::AppLabel;
 use bevy_ecs::{
     component::RequiredComponentsError,
+    derived::{DeriveComponents, DeriveFn, DerivedComponent, DerivedInputs},
     error::{DefaultErrorHandler, ErrorHandler},
     event::{event_update_system, EventCursor},
+    index::IndexableComponent,
     intern::Interned,
     prelude::*,
     schedule::{InternedSystemSet, ScheduleBuildSettings, ScheduleLabel},
@@ -910,6 +912,65 @@ impl App {
         self
     }
 
+    /// Creates a [`ComponentValueIndex`](bevy_ecs::index::ComponentValueIndex) for the component `C`,
+    /// allowing entities to be looked up by the value of `C` with
+    /// [`QueryByIndex`](bevy_ecs::index::QueryByIndex).
+    ///
+    /// See [`World::register_component_index`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` already defines its own `on_insert` or `on_replace` hook, or if it has
+    /// already been added to an entity.
+    pub fn register_component_index<C: IndexableComponent>(&mut self) -> &mut Self {
+        self.world_mut().register_component_index::<C>();
+        self
+    }
+
+    /// Registers `Out` as a component derived from the components `Ins`, computed with `derive`,
+    /// and runs the [`DeriveComponents`](bevy_ecs::derived::DeriveComponents) schedule after
+    /// [`PreUpdate`], [`Update`] and [`PostUpdate`].
+    ///
+    /// See [`World::register_derived_component`] for more details.
+    pub fn register_derived_component<Out: DerivedComponent, Ins: DerivedInputs>(
+        &mut self,
+        derive: impl DeriveFn<Out, Ins>,
+    ) -> &mut Self {
+        let world = self.world_mut();
+        world.register_derived_component::<Out, Ins>(derive);
+        if let Some(mut order) = world.get_resource_mut::<MainScheduleOrder>()
+            && !order
+                .labels
+                .iter()
+                .any(|label| (**label).eq(&DeriveComponents))
+        {
+            for after in [PreUpdate.intern(), Update.intern(), PostUpdate.intern()] {
+                if order.labels.contains(&after) {
+                    order.insert_after(after, DeriveComponents);
+                }
+            }
+        }
+        self
+    }
+
+    /// Registers the component `C` for rollback, so that its values are saved in the checkpoints
+    /// of the [`RollbackCheckpoints`](bevy_ecs::rollback::RollbackCheckpoints) resource.
+    ///
+    /// See [`World::register_rollback_component`] for more details.
+    pub fn register_rollback_component<C: Component + Clone>(&mut self) -> &mut Self {
+        self.world_mut().register_rollback_component::<C>();
+        self
+    }
+
+    /// Registers the resource `R` for rollback, so that its value is saved in the checkpoints
+    /// of the [`RollbackCheckpoints`](bevy_ecs::rollback::RollbackCheckpoints) resource.
+    ///
+    /// See [`World::register_rollback_resource`] for more details.
+    pub fn register_rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.world_mut().register_rollback_resource::<R>();
+        self
+    }
+
     /// Tries to register the given component `R` as a [required component] for `T`.
     ///
     /// When `T` is added to an entity, `R` and its own required components will also be added
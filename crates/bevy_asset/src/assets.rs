@@ -1,5 +1,8 @@
 use crate::asset_changed::AssetChanges;
-use crate::{Asset, AssetEvent, AssetHandleProvider, AssetId, AssetServer, Handle, UntypedHandle};
+use crate::budget::{AssetBudget, AssetEviction};
+use crate::{
+    Asset, AssetEvent, AssetHandleProvider, AssetId, AssetServer, Handle, LoadState, UntypedHandle,
+};
 use alloc::{sync::Arc, vec::Vec};
 use bevy_ecs::{
     prelude::EventWriter,
@@ -292,6 +295,8 @@ pub struct Assets<A: Asset> {
     /// Assets managed by the `Assets` struct with live strong `Handle`s
     /// originating from `get_strong_handle`.
     duplicate_handles: HashMap<AssetId<A>, u16>,
+    /// The budget of this collection, if one was set with [`Assets::set_budget`].
+    eviction: Option<AssetEviction<A>>,
 }
 
 impl<A: Asset> Default for Assets<A> {
@@ -305,6 +310,7 @@ impl<A: Asset> Default for Assets<A> {
             hash_map: Default::default(),
             queued_events: Default::default(),
             duplicate_handles: Default::default(),
+            eviction: None,
         }
     }
 }
@@ -370,6 +376,9 @@ impl<A: Asset> Assets<A> {
 
     pub(crate) fn insert_with_uuid(&mut self, uuid: Uuid, asset: A) -> Option<A> {
         let result = self.hash_map.insert(uuid, asset);
+        if let Some(eviction) = &mut self.eviction {
+            eviction.inserted(uuid.into());
+        }
         if result.is_some() {
             self.queued_events
                 .push(AssetEvent::Modified { id: uuid.into() });
@@ -385,6 +394,9 @@ impl<A: Asset> Assets<A> {
         asset: A,
     ) -> Result<bool, InvalidGenerationError> {
         let replaced = self.dense_storage.insert(index, asset)?;
+        if let Some(eviction) = &mut self.eviction {
+            eviction.inserted(index.into());
+        }
         if replaced {
             self.queued_events
                 .push(AssetEvent::Modified { id: index.into() });
@@ -427,9 +439,16 @@ impl<A: Asset> Assets<A> {
 
     /// Retrieves a reference to the [`Asset`] with the given `id`, if it exists.
     /// Note that this supports anything that implements `Into<AssetId<A>>`, which includes [`Handle`] and [`AssetId`].
+    ///
+    /// If the asset was evicted to stay within the [`AssetBudget`] of this collection, this returns `None`
+    /// and the asset is reloaded.
     #[inline]
     pub fn get(&self, id: impl Into<AssetId<A>>) -> Option<&A> {
-        match id.into() {
+        let id: AssetId<A> = id.into();
+        if let Some(eviction) = &self.eviction {
+            eviction.touch(id);
+        }
+        match id {
             AssetId::Index { index, .. } => self.dense_storage.get(index),
             AssetId::Uuid { uuid } => self.hash_map.get(&uuid),
         }
@@ -440,6 +459,9 @@ impl<A: Asset> Assets<A> {
     #[inline]
     pub fn get_mut(&mut self, id: impl Into<AssetId<A>>) -> Option<&mut A> {
         let id: AssetId<A> = id.into();
+        if let Some(eviction) = &self.eviction {
+            eviction.touch(id);
+        }
         let result = match id {
             AssetId::Index { index, .. } => self.dense_storage.get_mut(index),
             AssetId::Uuid { uuid } => self.hash_map.get_mut(&uuid),
@@ -480,6 +502,9 @@ impl<A: Asset> Assets<A> {
     pub fn remove_untracked(&mut self, id: impl Into<AssetId<A>>) -> Option<A> {
         let id: AssetId<A> = id.into();
         self.duplicate_handles.remove(&id);
+        if let Some(eviction) = &mut self.eviction {
+            eviction.removed(id);
+        }
         match id {
             AssetId::Index { index, .. } => self.dense_storage.remove_still_alive(index),
             AssetId::Uuid { uuid } => self.hash_map.remove(&uuid),
@@ -503,6 +528,9 @@ impl<A: Asset> Assets<A> {
             AssetId::Index { index, .. } => self.dense_storage.remove_dropped(index).is_some(),
             AssetId::Uuid { uuid } => self.hash_map.remove(&uuid).is_some(),
         };
+        if let Some(eviction) = &mut self.eviction {
+            eviction.removed(id);
+        }
 
         self.queued_events.push(AssetEvent::Unused { id });
         if existed {
@@ -510,6 +538,41 @@ impl<A: Asset> Assets<A> {
         }
     }
 
+    /// Sets the memory budget of this collection. Once its assets use more memory than the budget allows,
+    /// evictable assets are unloaded in least-recently-used order. See [`AssetBudget`] for details.
+    pub fn set_budget(&mut self, budget: AssetBudget<A>) {
+        match &mut self.eviction {
+            Some(eviction) => eviction.budget = budget,
+            None => self.eviction = Some(AssetEviction::new(budget, self.ids())),
+        }
+    }
+
+    /// Returns the memory budget of this collection, if one was set with [`Assets::set_budget`].
+    pub fn budget(&self) -> Option<&AssetBudget<A>> {
+        self.eviction.as_ref().map(|eviction| &eviction.budget)
+    }
+
+    /// Sets whether the asset with the given `id` can be evicted to stay within the budget of this collection,
+    /// overriding [`AssetBudget::evictable_by_default`]. Only assets loaded from a path can be evicted, and they
+    /// can be evicted while strong [`Handle`]s keep them alive.
+    ///
+    /// Evicting a labeled asset reloads the whole file it was loaded from the next time it's accessed.
+    ///
+    /// This has no effect until a budget is set with [`Assets::set_budget`].
+    pub fn set_evictable(&mut self, id: impl Into<AssetId<A>>, evictable: bool) {
+        if let Some(eviction) = &mut self.eviction {
+            eviction.set_evictable(id.into(), evictable);
+        }
+    }
+
+    /// Returns `true` if the asset with the given `id` was evicted to stay within the budget of this collection
+    /// and hasn't been reloaded yet.
+    pub fn is_evicted(&self, id: impl Into<AssetId<A>>) -> bool {
+        self.eviction
+            .as_ref()
+            .is_some_and(|eviction| eviction.is_evicted(id.into()))
+    }
+
     /// Returns `true` if there are no assets in this collection.
     pub fn is_empty(&self) -> bool {
         self.dense_storage.is_empty() && self.hash_map.is_empty()
@@ -590,6 +653,87 @@ impl<A: Asset> Assets<A> {
         }
     }
 
+    /// A system that enforces the [`AssetBudget`] of this collection. This reloads evicted assets that were
+    /// accessed since it last ran, then evicts the least recently used evictable assets while the collection
+    /// is over budget.
+    pub fn evict_assets(mut assets: ResMut<Self>, asset_server: Res<AssetServer>) {
+        let assets = &mut *assets;
+        let Some(mut eviction) = assets.eviction.take() else {
+            return;
+        };
+        // Like `track_assets`, hold the lock for the whole system so that load states stay in sync with
+        // this collection.
+        let mut infos = asset_server.data.infos.write();
+
+        let mut reloads = Vec::new();
+        for id in eviction.take_requested() {
+            let Some(info) = infos.get_mut(id.untyped()) else {
+                continue;
+            };
+            if let Some(path) = info.path.clone() {
+                info.load_state = LoadState::Loading;
+                reloads.push(path);
+            }
+        }
+
+        let size_of = eviction.budget.size_of;
+        let mut used_bytes = 0;
+        let mut candidates = Vec::new();
+        for (id, asset) in assets.iter() {
+            let size = size_of(asset);
+            used_bytes += size;
+            let Some(info) = infos.get(id.untyped()) else {
+                continue;
+            };
+            if info.path.is_none() || !matches!(info.load_state, LoadState::Loaded) {
+                continue;
+            }
+            if let Some(age) = eviction.eviction_candidate(id) {
+                candidates.push((age, id, size));
+            }
+        }
+
+        if used_bytes > eviction.budget.max_bytes {
+            // Evict the assets that went unused for the longest first.
+            candidates.sort_unstable_by_key(|(age, ..)| core::cmp::Reverse(*age));
+            for (_, id, size) in candidates {
+                if used_bytes <= eviction.budget.max_bytes {
+                    break;
+                }
+                match id {
+                    AssetId::Index { index, .. } => {
+                        assets.dense_storage.remove_still_alive(index);
+                    }
+                    AssetId::Uuid { uuid } => {
+                        assets.hash_map.remove(&uuid);
+                    }
+                }
+                used_bytes -= size;
+                eviction.evicted(id);
+                if let Some(info) = infos.get_mut(id.untyped()) {
+                    info.load_state = LoadState::Evicted;
+                }
+                assets.queued_events.push(AssetEvent::Evicted { id });
+            }
+        }
+
+        eviction.advance();
+        assets.eviction = Some(eviction);
+
+        // Reloads can run immediately on single-threaded task pools, so they must start after the lock is released.
+        drop(infos);
+        for path in reloads {
+            asset_server.reload(path);
+        }
+    }
+
+    /// A run condition for [`evict_assets`]. The system will not run if this collection has no budget.
+    ///
+    /// [`evict_assets`]: Self::evict_assets
+    pub(crate) fn has_budget(assets: Res<Self>) -> bool {
+        assets.eviction.is_some()
+    }
+
     /// A system that applies accumulated asset change events to the [`Events`] resource.
     ///
     /// [`Events`]: bevy_ecs::event::Events
@@ -604,7 +748,9 @@ impl<A: Asset> Assets<A> {
         if let Some(mut asset_changes) = asset_changes {
             for new_event in &assets.queued_events {
                 match new_event {
-                    Removed { id } | AssetEvent::Unused { id } => asset_changes.remove(id),
+                    Removed { id } | AssetEvent::Unused { id } | AssetEvent::Evicted { id } => {
+                        asset_changes.remove(id);
+                    }
                     Added { id } | Modified { id } | LoadedWithDependencies { id } => {
                         asset_changes.insert(*id, ticks.this_run());
                     }
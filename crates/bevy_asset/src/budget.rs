@@ -0,0 +1,186 @@
+use crate::{Asset, AssetId};
+use alloc::vec::Vec;
+use bevy_platform::collections::HashMap;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// A memory budget for the assets in an [`Assets`](crate::Assets) collection, set with
+/// [`Assets::set_budget`](crate::Assets::set_budget).
+///
+/// Once the assets of the collection use more than [`AssetBudget::max_bytes`], evictable assets are unloaded
+/// in least-recently-used order until the collection is back within budget. An asset is evictable if it was
+/// loaded from a path by the [`AssetServer`](crate::AssetServer) and is either marked with
+/// [`Assets::set_evictable`](crate::Assets::set_evictable), or [`AssetBudget::evictable_by_default`] is set and
+/// it isn't marked otherwise. Assets loaded from a path are only kept alive by their strong
+/// [`Handle`](crate::Handle)s, so these handles don't prevent eviction: the asset is reloaded when it's used again.
+///
+/// Evicting a labeled asset, like a mesh of a glTF scene, reloads the whole file it was loaded from, including
+/// its other labeled assets, the next time it's accessed.
+///
+/// Evicting a render asset, like an `Image` or a `Mesh`, also releases its copy on the GPU. Rendering doesn't
+/// access [`Assets`](crate::Assets) in the main world, so it doesn't count as using the asset, nor does it reload
+/// evicted assets: assets that are drawn every frame should not be evictable.
+///
+/// Evicted assets keep their handles and ids. [`Assets::get`](crate::Assets::get) returns `None` for them,
+/// and accessing them that way schedules a reload from their path. Eviction emits
+/// [`AssetEvent::Evicted`](crate::AssetEvent::Evicted) and sets the asset's load state to
+/// [`LoadState::Evicted`](crate::LoadState::Evicted); the reload emits
+/// [`AssetEvent::Added`](crate::AssetEvent::Added) as usual.
+pub struct AssetBudget<A: Asset> {
+    /// The number of bytes the assets of the collection can use before assets are evicted.
+    pub max_bytes: usize,
+    /// Returns the number of bytes used by an asset. This is called for every asset of the collection each
+    /// time the budget is checked, so it should be cheap.
+    pub size_of: fn(&A) -> usize,
+    /// Whether every asset loaded from a path can be evicted unless
+    /// [`Assets::set_evictable`](crate::Assets::set_evictable) says otherwise.
+    pub evictable_by_default: bool,
+}
+
+impl<A: Asset> AssetBudget<A> {
+    /// Creates a budget of `max_bytes`, using `size_of` to measure assets. Only assets marked with
+    /// [`Assets::set_evictable`](crate::Assets::set_evictable) are evicted.
+    pub fn new(max_bytes: usize, size_of: fn(&A) -> usize) -> Self {
+        Self {
+            max_bytes,
+            size_of,
+            evictable_by_default: false,
+        }
+    }
+
+    /// Makes every asset loaded from a path evictable unless it's marked otherwise with
+    /// [`Assets::set_evictable`](crate::Assets::set_evictable).
+    pub fn evictable_by_default(mut self) -> Self {
+        self.evictable_by_default = true;
+        self
+    }
+}
+
+impl<A: Asset> Clone for AssetBudget<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Asset> Copy for AssetBudget<A> {}
+
+/// The state [`Assets`](crate::Assets) keeps to enforce an [`AssetBudget`].
+pub(crate) struct AssetEviction<A: Asset> {
+    pub(crate) budget: AssetBudget<A>,
+    /// Incremented every time the budget is checked. Accessed assets are stamped with it.
+    generation: u32,
+    entries: HashMap<AssetId<A>, EvictionEntry>,
+}
+
+struct EvictionEntry {
+    evictable: Option<bool>,
+    last_used: AtomicU32,
+    state: EvictionState,
+    /// Set when an evicted asset is accessed.
+    requested: AtomicBool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EvictionState {
+    Resident,
+    Evicted,
+    Reloading,
+}
+
+impl EvictionEntry {
+    fn new(generation: u32) -> Self {
+        Self {
+            evictable: None,
+            last_used: AtomicU32::new(generation),
+            state: EvictionState::Resident,
+            requested: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<A: Asset> AssetEviction<A> {
+    pub(crate) fn new(budget: AssetBudget<A>, ids: impl Iterator<Item = AssetId<A>>) -> Self {
+        Self {
+            budget,
+            generation: 0,
+            entries: ids.map(|id| (id, EvictionEntry::new(0))).collect(),
+        }
+    }
+
+    /// Records an access of the asset with the given `id`, requesting a reload if it was evicted.
+    #[inline]
+    pub(crate) fn touch(&self, id: AssetId<A>) {
+        let Some(entry) = self.entries.get(&id) else {
+            return;
+        };
+        entry.last_used.store(self.generation, Ordering::Relaxed);
+        if entry.state == EvictionState::Evicted {
+            entry.requested.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn inserted(&mut self, id: AssetId<A>) {
+        let generation = self.generation;
+        let entry = self
+            .entries
+            .entry(id)
+            .or_insert_with(|| EvictionEntry::new(generation));
+        *entry.last_used.get_mut() = generation;
+        entry.state = EvictionState::Resident;
+        *entry.requested.get_mut() = false;
+    }
+
+    pub(crate) fn removed(&mut self, id: AssetId<A>) {
+        self.entries.remove(&id);
+    }
+
+    pub(crate) fn set_evictable(&mut self, id: AssetId<A>, evictable: bool) {
+        let generation = self.generation;
+        self.entries
+            .entry(id)
+            .or_insert_with(|| EvictionEntry::new(generation))
+            .evictable = Some(evictable);
+    }
+
+    pub(crate) fn is_evicted(&self, id: AssetId<A>) -> bool {
+        self.entries
+            .get(&id)
+            .is_some_and(|entry| entry.state != EvictionState::Resident)
+    }
+
+    /// Returns how many budget checks ago the asset with the given `id` was last used, if it can be evicted now.
+    /// Assets used since the budget was last checked are never evicted.
+    pub(crate) fn eviction_candidate(&self, id: AssetId<A>) -> Option<u32> {
+        let entry = self.entries.get(&id)?;
+        let last_used = entry.last_used.load(Ordering::Relaxed);
+        let evictable = entry.evictable.unwrap_or(self.budget.evictable_by_default);
+        (entry.state == EvictionState::Resident && evictable && last_used != self.generation)
+            .then_some(self.generation.wrapping_sub(last_used))
+    }
+
+    pub(crate) fn evicted(&mut self, id: AssetId<A>) {
+        if let Some(entry) = self.entries.get_mut(&id) {
+            entry.state = EvictionState::Evicted;
+            *entry.requested.get_mut() = false;
+        }
+    }
+
+    /// Returns the evicted assets that were accessed since the budget was last checked, marking them as reloading.
+    pub(crate) fn take_requested(&mut self) -> Vec<AssetId<A>> {
+        self.entries
+            .iter_mut()
+            .filter(|(_, entry)| {
+                entry.state == EvictionState::Evicted && entry.requested.load(Ordering::Relaxed)
+            })
+            .map(|(id, entry)| {
+                entry.state = EvictionState::Reloading;
+                *entry.requested.get_mut() = false;
+                *id
+            })
+            .collect()
+    }
+
+    /// Starts a new generation. Assets accessed from now on count as recently used.
+    pub(crate) fn advance(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
@@ -58,6 +58,9 @@ pub enum AssetEvent<A: Asset> {
     Unused { id: AssetId<A> },
     /// Emitted whenever an [`Asset`] has been fully loaded (including its dependencies and all "recursive dependencies").
     LoadedWithDependencies { id: AssetId<A> },
+    /// Emitted whenever an [`Asset`] is unloaded to stay within its [`AssetBudget`](crate::AssetBudget).
+    /// Its handles remain valid, and it will be reloaded from its path the next time it is accessed.
+    Evicted { id: AssetId<A> },
 }
 
 impl<A: Asset> AssetEvent<A> {
@@ -85,6 +88,11 @@ impl<A: Asset> AssetEvent<A> {
     pub fn is_unused(&self, asset_id: impl Into<AssetId<A>>) -> bool {
         matches!(self, AssetEvent::Unused { id } if *id == asset_id.into())
     }
+
+    /// Returns `true` if this event is [`AssetEvent::Evicted`] and matches the given `id`.
+    pub fn is_evicted(&self, asset_id: impl Into<AssetId<A>>) -> bool {
+        matches!(self, AssetEvent::Evicted { id } if *id == asset_id.into())
+    }
 }
 
 impl<A: Asset> Clone for AssetEvent<A> {
@@ -106,6 +114,7 @@ impl<A: Asset> Debug for AssetEvent<A> {
                 .debug_struct("LoadedWithDependencies")
                 .field("id", id)
                 .finish(),
+            Self::Evicted { id } => f.debug_struct("Evicted").field("id", id).finish(),
         }
     }
 }
@@ -120,7 +129,8 @@ impl<A: Asset> PartialEq for AssetEvent<A> {
             | (
                 Self::LoadedWithDependencies { id: l_id },
                 Self::LoadedWithDependencies { id: r_id },
-            ) => l_id == r_id,
+            )
+            | (Self::Evicted { id: l_id }, Self::Evicted { id: r_id }) => l_id == r_id,
             _ => false,
         }
     }
@@ -0,0 +1,394 @@
+//! Reads assets from a remote HTTP or HTTPS server, caching them on disk.
+//!
+//! [`HttpAssetReader`] loads the assets of a source relative to a base URL, so that `dlc://maps/forest.ron`
+//! can be loaded from `https://cdn.example.com/dlc/maps/forest.ron`. HTTPS requires the `https_source` feature.
+//!
+//! When a cache directory is set with [`HttpAssetReader::with_cache`], downloaded assets are stored there along
+//! with their `ETag` and `Last-Modified` headers. Every read then sends a conditional request, so unchanged assets
+//! are served from the cache without being downloaded again, and [`AssetServer::reload`](crate::AssetServer::reload)
+//! picks up assets that changed on the server. If the server can't be reached, cached assets are used as is.
+//!
+//! ```no_run
+//! # use bevy_app::App;
+//! # use bevy_asset::{prelude::*, io::{AssetSourceBuilder, http::HttpAssetReader}};
+//! App::new().register_asset_source(
+//!     "dlc",
+//!     AssetSourceBuilder::http(
+//!         HttpAssetReader::new("https://cdn.example.com/dlc").with_cache("cache/dlc"),
+//!     ),
+//! );
+//! ```
+//!
+//! Remote servers rarely serve `.meta` files, so each of them costs a request that fails.
+//! Consider setting [`AssetPlugin::meta_check`](crate::AssetPlugin::meta_check) to
+//! [`AssetMetaCheck::Never`](crate::AssetMetaCheck::Never) for remote sources.
+
+use crate::io::{get_meta_path, AssetReader, AssetReaderError, PathStream, Reader, VecReader};
+use alloc::{borrow::ToOwned, boxed::Box, format, string::String, sync::Arc, vec::Vec};
+use core::{fmt::Write as _, ops::Range};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// An [`AssetReader`] that reads assets over HTTP or HTTPS, relative to a base URL.
+///
+/// See the [module-level documentation](self) for more details.
+///
+/// [`HttpAssetReader`] can be cloned. Clones share their connections.
+#[derive(Clone)]
+pub struct HttpAssetReader {
+    base_url: Arc<str>,
+    cache: Option<Arc<Path>>,
+    agent: ureq::Agent,
+}
+
+/// The `ETag` and `Last-Modified` headers of a cached asset, used to revalidate it.
+#[derive(Serialize, Deserialize, Default)]
+struct CacheValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+struct CachedAsset {
+    validators: CacheValidators,
+    bytes: Vec<u8>,
+}
+
+impl HttpAssetReader {
+    /// Creates an [`HttpAssetReader`] that reads assets relative to `base_url`, like `https://cdn.example.com/dlc`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into();
+        Self {
+            base_url: base_url.into(),
+            cache: None,
+            agent,
+        }
+    }
+
+    /// Stores downloaded assets in `directory`, which is created if needed, and revalidates them
+    /// instead of downloading them again.
+    pub fn with_cache(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.cache = Some(directory.into().into());
+        self
+    }
+
+    /// Returns the URL of the asset at `path`.
+    pub fn url(&self, path: &Path) -> String {
+        let mut url = String::from(&*self.base_url);
+        for component in path.components() {
+            url.push('/');
+            for byte in component.as_os_str().to_string_lossy().bytes() {
+                if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                    url.push(byte as char);
+                } else {
+                    let _ = write!(url, "%{byte:02X}");
+                }
+            }
+        }
+        url
+    }
+
+    /// Reads the bytes in `range` of the asset at `path` with an HTTP range request.
+    ///
+    /// If the asset is cached, it is revalidated instead, and the range is read from the cache. The asset is only
+    /// downloaded again if it changed on the server.
+    pub async fn read_range(
+        &self,
+        path: &Path,
+        range: Range<u64>,
+    ) -> Result<Vec<u8>, AssetReaderError> {
+        self.fetch_in_background(path.to_owned(), Some(range)).await
+    }
+
+    async fn fetch_in_background(
+        &self,
+        path: PathBuf,
+        range: Option<Range<u64>>,
+    ) -> Result<Vec<u8>, AssetReaderError> {
+        let reader = self.clone();
+        blocking::unblock(move || reader.fetch(&path, range)).await
+    }
+
+    /// Downloads the asset at `path`, or the given `range` of it, revalidating the cached asset if there is one.
+    ///
+    /// Ranges of cached assets are read from the cache once it's revalidated.
+    fn fetch(&self, path: &Path, range: Option<Range<u64>>) -> Result<Vec<u8>, AssetReaderError> {
+        let url = self.url(path);
+        let cached = self.load_cached(&url);
+        let slice = |bytes: Vec<u8>| match &range {
+            Some(range) => bytes
+                .get(range.start as usize..(range.end as usize).min(bytes.len()))
+                .map(<[u8]>::to_vec)
+                .ok_or(AssetReaderError::HttpError(416)),
+            None => Ok(bytes),
+        };
+
+        let mut request = self.agent.get(&url);
+        match &cached {
+            Some(cached) => {
+                let validators = &cached.validators;
+                if let Some(etag) = &validators.etag {
+                    request = request.header("If-None-Match", etag);
+                }
+                if let Some(last_modified) = &validators.last_modified {
+                    request = request.header("If-Modified-Since", last_modified);
+                }
+            }
+            None => {
+                if let Some(range) = &range {
+                    request = request.header(
+                        "Range",
+                        &format!("bytes={}-{}", range.start, range.end.saturating_sub(1)),
+                    );
+                }
+            }
+        }
+
+        let mut response = match request.call() {
+            Ok(response) => response,
+            Err(error) => {
+                return match cached {
+                    Some(cached) => {
+                        warn!("Failed to revalidate {url}, using the cached asset: {error}");
+                        slice(cached.bytes)
+                    }
+                    None => Err(std::io::Error::other(error).into()),
+                };
+            }
+        };
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+        let validators = CacheValidators {
+            etag: header("ETag"),
+            last_modified: header("Last-Modified"),
+        };
+        match response.status().as_u16() {
+            200 => {
+                let bytes = response
+                    .body_mut()
+                    .with_config()
+                    .limit(u64::MAX)
+                    .read_to_vec()
+                    .map_err(std::io::Error::other)?;
+                self.store_cached(&url, &validators, &bytes);
+                slice(bytes)
+            }
+            206 => Ok(response
+                .body_mut()
+                .with_config()
+                .limit(u64::MAX)
+                .read_to_vec()
+                .map_err(std::io::Error::other)?),
+            304 => match cached {
+                Some(cached) => slice(cached.bytes),
+                None => Err(AssetReaderError::HttpError(304)),
+            },
+            404 => Err(AssetReaderError::NotFound(path.to_owned())),
+            status => Err(AssetReaderError::HttpError(status)),
+        }
+    }
+
+    fn cache_paths(&self, url: &str) -> Option<(PathBuf, PathBuf)> {
+        let directory = self.cache.as_ref()?;
+        let key = blake3::hash(url.as_bytes()).to_hex();
+        Some((
+            directory.join(key.as_str()),
+            directory.join(format!("{key}.ron")),
+        ))
+    }
+
+    fn load_cached(&self, url: &str) -> Option<CachedAsset> {
+        let (bytes_path, validators_path) = self.cache_paths(url)?;
+        let validators = ron::de::from_bytes(&std::fs::read(validators_path).ok()?).ok()?;
+        let bytes = std::fs::read(bytes_path).ok()?;
+        Some(CachedAsset { validators, bytes })
+    }
+
+    fn store_cached(&self, url: &str, validators: &CacheValidators, bytes: &[u8]) {
+        let Some((bytes_path, validators_path)) = self.cache_paths(url) else {
+            return;
+        };
+        if validators.etag.is_none() && validators.last_modified.is_none() {
+            return;
+        }
+        let result = (|| {
+            std::fs::create_dir_all(bytes_path.parent().unwrap())?;
+            // Remove the validators first, so that an interrupted write never pairs them with other bytes.
+            let _ = std::fs::remove_file(&validators_path);
+            std::fs::write(&bytes_path, bytes)?;
+            let validators = ron::ser::to_string(validators).map_err(std::io::Error::other)?;
+            std::fs::write(&validators_path, validators)
+        })();
+        if let Err(error) = result {
+            warn!("Failed to cache {url} in {}: {error}", bytes_path.display());
+        }
+    }
+}
+
+impl AssetReader for HttpAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        let bytes = self.fetch_in_background(path.to_owned(), None).await?;
+        Ok(VecReader::new(bytes))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        let bytes = self.fetch_in_background(get_meta_path(path), None).await?;
+        Ok(VecReader::new(bytes))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        _path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        // HTTP servers don't list directories in a standard way.
+        let stream: Box<PathStream> = Box::new(futures_lite::stream::empty());
+        Ok(stream)
+    }
+
+    async fn is_directory<'a>(&'a self, _path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+    use bevy_tasks::block_on;
+    use parking_lot::Mutex;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    /// A stand-in for a remote server, which serves one asset with an `ETag` and supports
+    /// conditional and range requests.
+    struct TestServer {
+        url: String,
+        asset: Arc<Mutex<(&'static str, &'static str)>>,
+        statuses: Arc<Mutex<Vec<u16>>>,
+        thread: std::thread::JoinHandle<()>,
+    }
+
+    impl TestServer {
+        fn start(requests: usize) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/content", listener.local_addr().unwrap());
+            let asset = Arc::new(Mutex::new(("\"v1\"", "first version")));
+            let statuses = Arc::new(Mutex::new(Vec::new()));
+            let thread = std::thread::spawn({
+                let asset = asset.clone();
+                let statuses = statuses.clone();
+                move || {
+                    for stream in listener.incoming().take(requests) {
+                        let mut stream = stream.unwrap();
+                        let mut lines = BufReader::new(&stream).lines().map(Result::unwrap);
+                        let target = lines.next().unwrap().split(' ').nth(1).unwrap().to_string();
+                        let mut headers = Vec::new();
+                        for line in lines.by_ref().take_while(|line| !line.is_empty()) {
+                            let (name, value) = line.split_once(": ").unwrap();
+                            headers.push((name.to_ascii_lowercase(), value.to_string()));
+                        }
+                        let header = |name: &str| {
+                            headers
+                                .iter()
+                                .find(|(header, _)| header == name)
+                                .map(|(_, value)| value.as_str())
+                        };
+
+                        let (etag, body) = *asset.lock();
+                        let range = header("range")
+                            .filter(|_| header("if-range").is_none_or(|tag| tag == etag))
+                            .map(|range| {
+                                let (start, end) =
+                                    range.trim_start_matches("bytes=").split_once('-').unwrap();
+                                start.parse::<usize>().unwrap()..end.parse::<usize>().unwrap() + 1
+                            });
+                        let (status, body) = if target != "/content/level.txt" {
+                            (404, "")
+                        } else if header("if-none-match") == Some(etag) {
+                            (304, "")
+                        } else if let Some(range) = range {
+                            (206, &body[range])
+                        } else {
+                            (200, body)
+                        };
+                        statuses.lock().push(status);
+                        write!(
+                            stream,
+                            "HTTP/1.1 {status} Status\r\nETag: {etag}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                        .unwrap();
+                    }
+                }
+            });
+            Self {
+                url,
+                asset,
+                statuses,
+                thread,
+            }
+        }
+    }
+
+    fn read(reader: &HttpAssetReader, path: &str) -> Result<Vec<u8>, AssetReaderError> {
+        block_on(async {
+            let mut bytes = Vec::new();
+            reader
+                .read(Path::new(path))
+                .await?
+                .read_to_end(&mut bytes)
+                .await?;
+            Ok(bytes)
+        })
+    }
+
+    #[test]
+    fn http_reader_revalidates_cache() {
+        let cache = std::env::temp_dir().join(format!("bevy_http_cache_{}", std::process::id()));
+        let server = TestServer::start(7);
+        let reader = HttpAssetReader::new(&server.url).with_cache(&cache);
+
+        assert_eq!(read(&reader, "level.txt").unwrap(), b"first version");
+        assert_eq!(read(&reader, "level.txt").unwrap(), b"first version");
+        let range = block_on(reader.read_range(Path::new("level.txt"), 6..13)).unwrap();
+        assert_eq!(range, b"version");
+
+        *server.asset.lock() = ("\"v2\"", "second version");
+        assert_eq!(read(&reader, "level.txt").unwrap(), b"second version");
+        let range = block_on(reader.read_range(Path::new("level.txt"), 0..6)).unwrap();
+        assert_eq!(range, b"second");
+        assert_eq!(
+            read(&reader, "missing.txt"),
+            Err(AssetReaderError::NotFound(PathBuf::from("missing.txt")))
+        );
+
+        // Without a cache, only the range is downloaded.
+        let uncached = HttpAssetReader::new(&server.url);
+        let range = block_on(uncached.read_range(Path::new("level.txt"), 7..14)).unwrap();
+        assert_eq!(range, b"version");
+        assert_eq!(
+            *server.statuses.lock(),
+            vec![200, 304, 304, 200, 304, 404, 206]
+        );
+
+        // Once the server is unreachable, the cached asset is used.
+        server.thread.join().unwrap();
+        assert_eq!(read(&reader, "level.txt").unwrap(), b"second version");
+
+        std::fs::remove_dir_all(cache).unwrap();
+    }
+}
@@ -10,7 +10,11 @@ pub mod android;
 pub mod embedded;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod file;
+#[cfg(all(feature = "http_source", not(target_arch = "wasm32")))]
+pub mod http;
 pub mod memory;
+#[cfg(feature = "asset_pack")]
+pub mod pack;
 pub mod processor_gated;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
@@ -0,0 +1,759 @@
+//! Serves an [`AssetSource`](crate::io::AssetSource) from asset packs: single files that bundle many assets
+//! and their meta files behind an index.
+//!
+//! Loading a few large packs is faster than loading thousands of loose files, and a game can be updated
+//! by shipping a small overlay pack that only contains the assets that changed:
+//!
+//! - [`AssetPackWriter`] writes packs. Entries can be compressed with zstd, which requires the `zstd_rust` or
+//!   `zstd_c` feature. [`AssetProcessor::pack_processed_assets`](crate::processor::AssetProcessor::pack_processed_assets)
+//!   collects the output of the asset processor into a pack.
+//! - [`AssetPack`] reads the index of a pack. On platforms with a filesystem, [`AssetPack::open`] memory-maps
+//!   the pack file, so uncompressed entries are read without copying them.
+//! - [`AssetPackReader`] is an [`AssetReader`] over a base pack and any number of overlay packs, whose entries
+//!   shadow the entries of the packs before them. [`AssetPackWriter::retain_changes`] turns a pack into an overlay
+//!   of another one, which also hides the assets that were removed.
+//!
+//! Packs of processed assets should be registered as the processed reader of their source,
+//! with [`AssetSourceBuilder::with_processed_reader`](crate::io::AssetSourceBuilder::with_processed_reader).
+//!
+//! ```no_run
+//! # use bevy_app::App;
+//! # use bevy_asset::{prelude::*, io::{AssetSource, AssetSourceId, pack::{AssetPack, AssetPackReader}}};
+//! let reader = AssetPackReader::new(AssetPack::open("assets.pack").unwrap())
+//!     .with_overlay(AssetPack::open("patch_1.pack").unwrap());
+//! App::new().register_asset_source(
+//!     AssetSourceId::Default,
+//!     AssetSource::build().with_reader(move || Box::new(reader.clone())),
+//! );
+//! ```
+
+use crate::io::{
+    get_meta_path, AssetReader, AssetReaderError, ErasedAssetReader, MissingAssetSourceError,
+    MissingProcessedAssetReaderError, PathStream, Reader, SliceReader, VecReader,
+};
+use alloc::{borrow::Cow, boxed::Box, string::String, sync::Arc, vec::Vec};
+use bevy_platform::collections::HashMap;
+use futures_lite::StreamExt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The bytes that every asset pack starts with.
+const MAGIC: &[u8; 8] = b"BEVYPACK";
+
+/// The version of the asset pack format written by [`AssetPackWriter`].
+const VERSION: u32 = 1;
+
+/// The length of an entry of the index, without its path: the path length, the entry kind, the offset and the
+/// stored length.
+const MIN_INDEX_ENTRY_LEN: usize = size_of::<u32>() + 1 + 2 * size_of::<u64>();
+
+/// How an entry of an asset pack is stored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PackCompression {
+    /// The entry is stored as is, and can be read without copying it.
+    #[default]
+    None,
+    /// The entry is compressed with zstd. This requires the `zstd_rust` or `zstd_c` feature.
+    Zstd,
+}
+
+/// An error that occurs while reading or writing an asset pack.
+#[derive(Error, Debug)]
+pub enum AssetPackError {
+    /// Encountered an I/O error while reading or writing an asset pack.
+    #[error("Encountered an I/O error while reading or writing an asset pack: {0}")]
+    Io(#[from] std::io::Error),
+    /// The asset pack is not a valid asset pack.
+    #[error("Invalid asset pack: {0}")]
+    Invalid(&'static str),
+    /// The asset pack was written by a newer version of the asset pack format.
+    #[error("Unsupported asset pack version {0}, expected version {VERSION}")]
+    UnsupportedVersion(u32),
+    /// An entry is compressed with zstd, but neither the `zstd_rust` nor the `zstd_c` feature is enabled.
+    #[error("The asset pack entry {} is compressed with zstd, which requires the `zstd_rust` or `zstd_c` feature", _0.display())]
+    ZstdUnsupported(PathBuf),
+    /// Encountered an error while reading the assets to pack.
+    #[error(transparent)]
+    AssetReaderError(#[from] AssetReaderError),
+    /// The asset source to pack doesn't exist.
+    #[error(transparent)]
+    MissingAssetSource(#[from] MissingAssetSourceError),
+    /// The asset source to pack isn't processed.
+    #[error(transparent)]
+    MissingProcessedAssetReader(#[from] MissingProcessedAssetReaderError),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EntryKind {
+    Stored(PackCompression),
+    /// Hides the entry with the same path in the packs below an overlay.
+    Removed,
+}
+
+#[derive(Clone, Debug)]
+struct PackEntry {
+    kind: EntryKind,
+    offset: usize,
+    stored_len: usize,
+}
+
+enum PackData {
+    Owned(Vec<u8>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Mapped(memmap2::Mmap),
+}
+
+impl PackData {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            PackData::Owned(bytes) => bytes,
+            #[cfg(not(target_arch = "wasm32"))]
+            PackData::Mapped(map) => map,
+        }
+    }
+}
+
+/// The index and data of an asset pack, written by [`AssetPackWriter`].
+///
+/// Use an [`AssetPackReader`] to load assets from packs.
+///
+/// [`AssetPack`] can be cloned. It is backed by an [`Arc`] so clones will share the data.
+#[derive(Clone)]
+pub struct AssetPack {
+    data: Arc<PackData>,
+    entries: Arc<HashMap<PathBuf, PackEntry>>,
+}
+
+impl AssetPack {
+    /// Memory-maps the asset pack at `path`, which is relative to the working directory.
+    ///
+    /// The pack file must not be modified while it's open, or the assets read from it will be corrupted.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[expect(unsafe_code, reason = "Memory-mapping a file is unsafe.")]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AssetPackError> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: Asset packs are read-only game data, which is documented to not be modified while it's open.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::new(PackData::Mapped(map))
+    }
+
+    /// Reads an asset pack from the bytes written by [`AssetPackWriter`].
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, AssetPackError> {
+        Self::new(PackData::Owned(bytes))
+    }
+
+    fn new(data: PackData) -> Result<Self, AssetPackError> {
+        let bytes = data.bytes();
+        let mut cursor = Cursor { bytes, position: 0 };
+        if cursor.take(MAGIC.len())? != MAGIC {
+            return Err(AssetPackError::Invalid("not an asset pack"));
+        }
+        let version = cursor.u32()?;
+        if version != VERSION {
+            return Err(AssetPackError::UnsupportedVersion(version));
+        }
+        let entry_count = cursor.u32()? as usize;
+        // Don't trust the count to reserve memory until the index is known to be large enough to hold it.
+        if entry_count > cursor.remaining() / MIN_INDEX_ENTRY_LEN {
+            return Err(AssetPackError::Invalid("unexpected end of index"));
+        }
+        let mut entries = HashMap::with_capacity(entry_count);
+        for _ in 0..entry_count {
+            let path_len = cursor.u32()? as usize;
+            let path = core::str::from_utf8(cursor.take(path_len)?)
+                .map_err(|_| AssetPackError::Invalid("entry path is not UTF-8"))?;
+            let kind = match cursor.u8()? {
+                0 => EntryKind::Stored(PackCompression::None),
+                1 => EntryKind::Stored(PackCompression::Zstd),
+                2 => EntryKind::Removed,
+                _ => return Err(AssetPackError::Invalid("unknown entry kind")),
+            };
+            let offset = cursor.usize()?;
+            let stored_len = cursor.usize()?;
+            if offset
+                .checked_add(stored_len)
+                .is_none_or(|end| end > bytes.len())
+            {
+                return Err(AssetPackError::Invalid("entry is out of bounds"));
+            }
+            entries.insert(
+                PathBuf::from(path),
+                PackEntry {
+                    kind,
+                    offset,
+                    stored_len,
+                },
+            );
+        }
+        Ok(Self {
+            data: Arc::new(data),
+            entries: Arc::new(entries),
+        })
+    }
+
+    /// Returns the number of files in this pack, including meta files, and excluding removed files.
+    pub fn len(&self) -> usize {
+        self.paths().count()
+    }
+
+    /// Returns `true` if this pack contains no files.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the paths of the files in this pack, including meta files, in no particular order.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.kind != EntryKind::Removed)
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Returns the paths that this pack removes from the packs below it when used as an overlay.
+    pub fn removed_paths(&self) -> impl Iterator<Item = &Path> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.kind == EntryKind::Removed)
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Returns the decompressed contents of the file at `path`, or `None` if this pack doesn't contain it.
+    pub fn get(&self, path: &Path) -> Result<Option<Cow<'_, [u8]>>, AssetPackError> {
+        match self.entries.get(path) {
+            Some(entry) => self.read_entry(path, entry).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn read_entry(&self, path: &Path, entry: &PackEntry) -> Result<Cow<'_, [u8]>, AssetPackError> {
+        let stored = &self.data.bytes()[entry.offset..entry.offset + entry.stored_len];
+        match entry.kind {
+            EntryKind::Stored(PackCompression::None) => Ok(Cow::Borrowed(stored)),
+            EntryKind::Stored(PackCompression::Zstd) => decompress(path, stored).map(Cow::Owned),
+            EntryKind::Removed => Err(AssetReaderError::NotFound(path.to_path_buf()).into()),
+        }
+    }
+}
+
+/// An [`AssetReader`] that reads assets from a base [`AssetPack`] and overlay packs that shadow its entries.
+///
+/// Compressed entries are decompressed on a blocking thread, except on wasm, where decompressing blocks the
+/// task that reads the asset.
+///
+/// [`AssetPackReader`] can be cloned. Its packs are backed by an [`Arc`] so clones will share them.
+#[derive(Clone)]
+pub struct AssetPackReader {
+    packs: Vec<AssetPack>,
+    /// The index in `packs` of the pack that provides each file.
+    files: Arc<HashMap<PathBuf, usize>>,
+    /// The files and directories in each directory, excluding meta files.
+    directories: Arc<HashMap<PathBuf, Vec<PathBuf>>>,
+}
+
+impl AssetPackReader {
+    /// Creates an [`AssetPackReader`] that reads the assets of `base`.
+    pub fn new(base: AssetPack) -> Self {
+        let mut reader = Self {
+            packs: Vec::new(),
+            files: Default::default(),
+            directories: Default::default(),
+        };
+        reader.packs.push(base);
+        reader.build_index();
+        reader
+    }
+
+    /// Adds an overlay pack, whose files replace the files with the same path in the packs added before it.
+    ///
+    /// Files that the overlay removes, see [`AssetPackWriter::remove`], are no longer found.
+    pub fn with_overlay(mut self, overlay: AssetPack) -> Self {
+        self.packs.push(overlay);
+        self.build_index();
+        self
+    }
+
+    /// Returns the packs of this reader, starting with the base pack.
+    pub fn packs(&self) -> &[AssetPack] {
+        &self.packs
+    }
+
+    fn build_index(&mut self) {
+        let mut files = HashMap::default();
+        for (index, pack) in self.packs.iter().enumerate() {
+            for (path, entry) in pack.entries.iter() {
+                match entry.kind {
+                    EntryKind::Stored(_) => files.insert(path.clone(), index),
+                    EntryKind::Removed => files.remove(path),
+                };
+            }
+        }
+
+        let mut directories = HashMap::<PathBuf, Vec<PathBuf>>::default();
+        directories.insert(PathBuf::new(), Vec::new());
+        for path in files.keys().filter(|path| !is_meta_path(path)) {
+            let mut child = path.as_path();
+            while let Some(parent) = child.parent() {
+                let children = directories.entry(parent.to_path_buf()).or_default();
+                let is_new_directory = children.is_empty();
+                children.push(child.to_path_buf());
+                if !is_new_directory {
+                    break;
+                }
+                child = parent;
+            }
+        }
+        for children in directories.values_mut() {
+            children.sort();
+        }
+
+        self.files = Arc::new(files);
+        self.directories = Arc::new(directories);
+    }
+
+    async fn read_file(&self, path: &Path) -> Result<Box<dyn Reader + '_>, AssetReaderError> {
+        let not_found = || AssetReaderError::NotFound(path.to_path_buf());
+        let pack = &self.packs[*self.files.get(path).ok_or_else(not_found)?];
+        let entry = pack.entries.get(path).ok_or_else(not_found)?;
+        let bytes = match entry.kind {
+            // Decompressing a large entry can take a while, so it runs on a blocking thread instead of stalling
+            // the executor. On wasm, where there are no threads to hand it to, it blocks the executor.
+            #[cfg(all(
+                any(feature = "zstd_rust", feature = "zstd_c"),
+                not(target_arch = "wasm32")
+            ))]
+            EntryKind::Stored(PackCompression::Zstd) => {
+                let (pack, path, entry) = (pack.clone(), path.to_path_buf(), entry.clone());
+                blocking::unblock(move || {
+                    pack.read_entry(&path, &entry)
+                        .map(|bytes| Cow::Owned(bytes.into_owned()))
+                })
+                .await
+            }
+            _ => pack.read_entry(path, entry),
+        };
+        let reader: Box<dyn Reader> = match bytes {
+            Ok(Cow::Borrowed(bytes)) => Box::new(SliceReader::new(bytes)),
+            Ok(Cow::Owned(bytes)) => Box::new(VecReader::new(bytes)),
+            Err(error) => {
+                return Err(AssetReaderError::Io(Arc::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    error,
+                ))));
+            }
+        };
+        Ok(reader)
+    }
+}
+
+impl AssetReader for AssetPackReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.read_file(path).await
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.read_file(&get_meta_path(path)).await
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let children = self
+            .directories
+            .get(path)
+            .ok_or_else(|| AssetReaderError::NotFound(path.to_path_buf()))?
+            .clone();
+        let stream: Box<PathStream> = Box::new(futures_lite::stream::iter(children));
+        Ok(stream)
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(self.directories.contains_key(path))
+    }
+}
+
+/// Writes an asset pack, which can then be read with [`AssetPack`].
+///
+/// ```
+/// # use std::path::Path;
+/// # use bevy_asset::io::pack::{AssetPack, AssetPackWriter, PackCompression};
+/// let mut writer = AssetPackWriter::default();
+/// writer.insert("levels/intro.ron", b"(enemies: 3)".to_vec(), PackCompression::None);
+/// let pack = AssetPack::from_bytes(writer.to_bytes().unwrap()).unwrap();
+///
+/// let intro = pack.get(Path::new("levels/intro.ron")).unwrap().unwrap();
+/// assert_eq!(&*intro, b"(enemies: 3)");
+/// ```
+#[derive(Default)]
+pub struct AssetPackWriter {
+    entries: HashMap<PathBuf, Option<(Vec<u8>, PackCompression)>>,
+}
+
+impl AssetPackWriter {
+    /// Adds the file at `path` with the given contents, replacing any previous contents.
+    ///
+    /// Meta files are added like any other file, at the path returned by appending `.meta` to the asset path.
+    pub fn insert(
+        &mut self,
+        path: impl Into<PathBuf>,
+        bytes: Vec<u8>,
+        compression: PackCompression,
+    ) -> &mut Self {
+        self.entries.insert(path.into(), Some((bytes, compression)));
+        self
+    }
+
+    /// Marks the file at `path` as removed, so that it's hidden from the packs below this one when it's
+    /// used as an overlay.
+    pub fn remove(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.entries.insert(path.into(), None);
+        self
+    }
+
+    /// Returns the number of files added to the pack, including removed files.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no files were added to the pack.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds every asset of `reader` and its meta file, starting at the `root` directory.
+    pub async fn insert_from_reader(
+        &mut self,
+        reader: &dyn ErasedAssetReader,
+        root: &Path,
+        compression: PackCompression,
+    ) -> Result<&mut Self, AssetPackError> {
+        let mut directories = Vec::from([root.to_path_buf()]);
+        while let Some(directory) = directories.pop() {
+            let mut children = reader.read_directory(&directory).await?;
+            while let Some(path) = children.next().await {
+                if reader.is_directory(&path).await? {
+                    directories.push(path);
+                    continue;
+                }
+                let mut bytes = Vec::new();
+                reader.read(&path).await?.read_to_end(&mut bytes).await?;
+                match reader.read_meta_bytes(&path).await {
+                    Ok(meta) => {
+                        self.insert(get_meta_path(&path), meta, compression);
+                    }
+                    Err(AssetReaderError::NotFound(_)) => {}
+                    Err(error) => return Err(error.into()),
+                }
+                self.insert(path, bytes, compression);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Turns this pack into an overlay of `base`, by keeping only the files that differ from `base`
+    /// and removing the files of `base` that this pack doesn't contain.
+    pub fn retain_changes(&mut self, base: &AssetPack) -> Result<&mut Self, AssetPackError> {
+        for (path, entry) in base.entries.iter() {
+            if entry.kind == EntryKind::Removed {
+                continue;
+            }
+            match self.entries.get(path) {
+                Some(Some((bytes, _))) => {
+                    if base.read_entry(path, entry)?.as_ref() == bytes.as_slice() {
+                        self.entries.remove(path);
+                    }
+                }
+                Some(None) => {}
+                None => {
+                    self.entries.insert(path.clone(), None);
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Writes the pack to `writer`, compressing the files that were added with [`PackCompression::Zstd`].
+    ///
+    /// Files that don't get smaller when compressed are stored uncompressed.
+    pub fn write(&self, writer: &mut impl std::io::Write) -> Result<(), AssetPackError> {
+        let mut paths = self.entries.keys().collect::<Vec<_>>();
+        paths.sort();
+
+        let mut stored = Vec::with_capacity(paths.len());
+        let mut index_len = MAGIC.len() + 2 * size_of::<u32>();
+        for path in &paths {
+            let path_string = path_to_string(path)?;
+            index_len += MIN_INDEX_ENTRY_LEN + path_string.len();
+            let data = match &self.entries[*path] {
+                Some((bytes, PackCompression::Zstd)) => {
+                    let compressed = compress(path, bytes)?;
+                    if compressed.len() < bytes.len() {
+                        Some((Cow::Owned(compressed), PackCompression::Zstd))
+                    } else {
+                        Some((Cow::Borrowed(bytes.as_slice()), PackCompression::None))
+                    }
+                }
+                Some((bytes, PackCompression::None)) => {
+                    Some((Cow::Borrowed(bytes.as_slice()), PackCompression::None))
+                }
+                None => None,
+            };
+            stored.push((path_string, data));
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(stored.len() as u32).to_le_bytes())?;
+        let mut offset = index_len as u64;
+        for (path, data) in &stored {
+            writer.write_all(&(path.len() as u32).to_le_bytes())?;
+            writer.write_all(path.as_bytes())?;
+            let (kind, len) = match data {
+                Some((bytes, PackCompression::None)) => (0u8, bytes.len() as u64),
+                Some((bytes, PackCompression::Zstd)) => (1, bytes.len() as u64),
+                None => (2, 0),
+            };
+            writer.write_all(&[kind])?;
+            writer.write_all(&offset.to_le_bytes())?;
+            writer.write_all(&len.to_le_bytes())?;
+            offset += len;
+        }
+        for (bytes, _) in stored.iter().filter_map(|(_, data)| data.as_ref()) {
+            writer.write_all(bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the pack to a [`Vec<u8>`], see [`AssetPackWriter::write`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, AssetPackError> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AssetPackError> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + len)
+            .ok_or(AssetPackError::Invalid("unexpected end of index"))?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, AssetPackError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, AssetPackError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, AssetPackError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Reads a `u64` that has to fit in a `usize`, like an offset into the pack.
+    fn usize(&mut self) -> Result<usize, AssetPackError> {
+        usize::try_from(self.u64()?).map_err(|_| AssetPackError::Invalid("entry is out of bounds"))
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.position)
+    }
+}
+
+/// Returns `path` with `/` separators, so that packs are the same on every platform.
+fn path_to_string(path: &Path) -> Result<String, AssetPackError> {
+    let mut string = String::new();
+    for component in path.components() {
+        let component = component
+            .as_os_str()
+            .to_str()
+            .ok_or(AssetPackError::Invalid("entry path is not UTF-8"))?;
+        if !string.is_empty() {
+            string.push('/');
+        }
+        string.push_str(component);
+    }
+    Ok(string)
+}
+
+fn is_meta_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("meta"))
+}
+
+#[cfg_attr(
+    not(any(feature = "zstd_rust", feature = "zstd_c")),
+    expect(unused_variables, reason = "zstd is not available.")
+)]
+#[cfg_attr(
+    any(feature = "zstd_rust", feature = "zstd_c"),
+    expect(
+        unused_variables,
+        reason = "The path is only used when zstd is not available."
+    )
+)]
+fn compress(path: &Path, bytes: &[u8]) -> Result<Vec<u8>, AssetPackError> {
+    #[cfg(feature = "zstd_c")]
+    return Ok(zstd::encode_all(bytes, 0)?);
+    #[cfg(all(feature = "zstd_rust", not(feature = "zstd_c")))]
+    return Ok(ruzstd::encoding::compress_to_vec(
+        bytes,
+        ruzstd::encoding::CompressionLevel::Fastest,
+    ));
+    #[cfg(not(any(feature = "zstd_rust", feature = "zstd_c")))]
+    Err(AssetPackError::ZstdUnsupported(path.to_path_buf()))
+}
+
+#[cfg_attr(
+    not(any(feature = "zstd_rust", feature = "zstd_c")),
+    expect(unused_variables, reason = "zstd is not available.")
+)]
+#[cfg_attr(
+    any(feature = "zstd_rust", feature = "zstd_c"),
+    expect(
+        unused_variables,
+        reason = "The path is only used when zstd is not available."
+    )
+)]
+fn decompress(path: &Path, bytes: &[u8]) -> Result<Vec<u8>, AssetPackError> {
+    #[cfg(feature = "zstd_c")]
+    return Ok(zstd::decode_all(bytes)?);
+    #[cfg(all(feature = "zstd_rust", not(feature = "zstd_c")))]
+    return {
+        use std::io::Read;
+        let mut decoder = ruzstd::decoding::StreamingDecoder::new(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    };
+    #[cfg(not(any(feature = "zstd_rust", feature = "zstd_c")))]
+    Err(AssetPackError::ZstdUnsupported(path.to_path_buf()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::memory::{Dir, MemoryAssetReader};
+    use bevy_tasks::block_on;
+
+    fn read(reader: &AssetPackReader, path: &str) -> Result<Vec<u8>, AssetReaderError> {
+        block_on(async {
+            let mut bytes = Vec::new();
+            AssetReader::read(reader, Path::new(path))
+                .await?
+                .read_to_end(&mut bytes)
+                .await?;
+            Ok(bytes)
+        })
+    }
+
+    fn children(reader: &AssetPackReader, path: &str) -> Vec<PathBuf> {
+        block_on(async {
+            AssetReader::read_directory(reader, Path::new(path))
+                .await
+                .unwrap()
+                .collect()
+                .await
+        })
+    }
+
+    #[test]
+    fn pack_reader_with_overlay() {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("a.txt"), "a");
+        dir.insert_meta_text(Path::new("a.txt"), "a meta");
+        dir.insert_asset_text(Path::new("x/y/b.txt"), "b");
+        dir.insert_asset_text(Path::new("x/c.txt"), "c");
+        let source = MemoryAssetReader { root: dir.clone() };
+
+        let mut writer = AssetPackWriter::default();
+        block_on(writer.insert_from_reader(&source, Path::new(""), PackCompression::None)).unwrap();
+        assert_eq!(writer.len(), 4);
+        let base = AssetPack::from_bytes(writer.to_bytes().unwrap()).unwrap();
+        let reader = AssetPackReader::new(base.clone());
+
+        assert_eq!(read(&reader, "x/y/b.txt").unwrap(), b"b");
+        let meta = block_on(AssetReader::read_meta_bytes(&reader, Path::new("a.txt"))).unwrap();
+        assert_eq!(meta, b"a meta");
+        assert_eq!(
+            children(&reader, ""),
+            [PathBuf::from("a.txt"), PathBuf::from("x")]
+        );
+        assert_eq!(
+            children(&reader, "x"),
+            [PathBuf::from("x/c.txt"), PathBuf::from("x/y")]
+        );
+        assert!(block_on(AssetReader::is_directory(&reader, Path::new("x/y"))).unwrap());
+        assert!(!block_on(AssetReader::is_directory(&reader, Path::new("a.txt"))).unwrap());
+
+        dir.insert_asset_text(Path::new("a.txt"), "a2");
+        dir.remove_asset(Path::new("x/c.txt"));
+        let mut writer = AssetPackWriter::default();
+        block_on(writer.insert_from_reader(&source, Path::new(""), PackCompression::None)).unwrap();
+        writer.retain_changes(&base).unwrap();
+        let overlay = AssetPack::from_bytes(writer.to_bytes().unwrap()).unwrap();
+        assert_eq!(overlay.len(), 1);
+        assert_eq!(
+            overlay.removed_paths().collect::<Vec<_>>(),
+            [Path::new("x/c.txt")]
+        );
+
+        let reader = reader.with_overlay(overlay);
+        assert_eq!(read(&reader, "a.txt").unwrap(), b"a2");
+        assert_eq!(read(&reader, "x/y/b.txt").unwrap(), b"b");
+        assert_eq!(
+            read(&reader, "x/c.txt"),
+            Err(AssetReaderError::NotFound(PathBuf::from("x/c.txt")))
+        );
+        assert_eq!(children(&reader, "x"), [PathBuf::from("x/y")]);
+    }
+
+    #[test]
+    fn invalid_pack() {
+        assert!(matches!(
+            AssetPack::from_bytes(b"not a pack".to_vec()),
+            Err(AssetPackError::Invalid(_))
+        ));
+        let mut bytes = AssetPackWriter::default().to_bytes().unwrap();
+        bytes[MAGIC.len()] = 2;
+        assert!(matches!(
+            AssetPack::from_bytes(bytes),
+            Err(AssetPackError::UnsupportedVersion(2))
+        ));
+
+        // An entry count that the index can't hold is rejected before reserving memory for it.
+        let mut bytes = AssetPackWriter::default().to_bytes().unwrap();
+        let count = MAGIC.len() + size_of::<u32>();
+        bytes[count..count + size_of::<u32>()].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            AssetPack::from_bytes(bytes),
+            Err(AssetPackError::Invalid(_))
+        ));
+    }
+
+    #[cfg(any(feature = "zstd_rust", feature = "zstd_c"))]
+    #[test]
+    fn compressed_entries() {
+        let text = "compressible ".repeat(100);
+        let mut writer = AssetPackWriter::default();
+        writer
+            .insert("text.txt", text.clone().into_bytes(), PackCompression::Zstd)
+            .insert("tiny.txt", b"x".to_vec(), PackCompression::Zstd);
+        let bytes = writer.to_bytes().unwrap();
+        assert!(bytes.len() < text.len());
+
+        let reader = AssetPackReader::new(AssetPack::from_bytes(bytes).unwrap());
+        assert_eq!(read(&reader, "text.txt").unwrap(), text.as_bytes());
+        assert_eq!(read(&reader, "tiny.txt").unwrap(), b"x");
+    }
+}
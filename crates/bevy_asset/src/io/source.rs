@@ -302,6 +302,12 @@ impl AssetSourceBuilder {
             default
         }
     }
+
+    /// Returns a builder for a remote source, whose assets are read over HTTP or HTTPS by `reader`.
+    #[cfg(all(feature = "http_source", not(target_arch = "wasm32")))]
+    pub fn http(reader: crate::io::http::HttpAssetReader) -> Self {
+        Self::default().with_reader(move || Box::new(reader.clone()))
+    }
 }
 
 /// A [`Resource`] that hold (repeatable) functions capable of producing new [`AssetReader`](crate::io::AssetReader) and [`AssetWriter`](crate::io::AssetWriter) instances
@@ -174,6 +174,7 @@ pub mod prelude {
 
 mod asset_changed;
 mod assets;
+mod budget;
 mod direct_access_ext;
 mod event;
 mod folder;
@@ -188,6 +189,7 @@ mod server;
 
 pub use assets::*;
 pub use bevy_asset_macros::Asset;
+pub use budget::AssetBudget;
 pub use direct_access_ext::DirectAssetAccessExt;
 pub use event::*;
 pub use folder::*;
@@ -256,6 +258,13 @@ pub struct AssetPlugin {
     /// Approved folders are [`AssetPlugin::file_path`] and the folder of each
     /// [`AssetSource`](io::AssetSource). Subfolders within these folders are also valid.
     pub unapproved_path_mode: UnapprovedPathMode,
+    /// How many loads can be in flight at once. Further loads are queued and started in order of [`LoadPriority`].
+    /// Defaults to `usize::MAX`, which starts every load right away, so priorities only matter once this is lowered.
+    ///
+    /// Dependencies loaded with [`LoadContext::load`] are queued like any other load, so a loader must not wait
+    /// for them to finish, or it could hold the last slot they need. Immediate loads, made with
+    /// [`LoadContext::loader`], run as part of the load that makes them and never wait in the queue.
+    pub max_concurrent_loads: usize,
 }
 
 /// Determines how to react to attempts to load assets not inside the approved folders.
@@ -332,6 +341,7 @@ impl Default for AssetPlugin {
             watch_for_changes_override: None,
             meta_check: AssetMetaCheck::default(),
             unapproved_path_mode: UnapprovedPathMode::default(),
+            max_concurrent_loads: Self::DEFAULT_MAX_CONCURRENT_LOADS,
         }
     }
 }
@@ -341,6 +351,7 @@ impl AssetPlugin {
     /// NOTE: this is in the Default sub-folder to make this forward compatible with "import profiles"
     /// and to allow us to put the "processor transaction log" at `imported_assets/log`
     const DEFAULT_PROCESSED_FILE_PATH: &'static str = "imported_assets/Default";
+    pub(crate) const DEFAULT_MAX_CONCURRENT_LOADS: usize = usize::MAX;
 }
 
 impl Plugin for AssetPlugin {
@@ -408,6 +419,9 @@ impl Plugin for AssetPlugin {
                     }
                 }
             }
+            app.world()
+                .resource::<AssetServer>()
+                .set_max_concurrent_loads(self.max_concurrent_loads);
         }
         app.insert_resource(embedded)
             .init_asset::<LoadedFolder>()
@@ -611,9 +625,14 @@ impl AssetApp for App {
             .register_type::<Handle<A>>()
             .add_systems(
                 PostUpdate,
-                Assets::<A>::asset_events
-                    .run_if(Assets::<A>::asset_events_condition)
-                    .in_set(AssetEventSystems),
+                (
+                    Assets::<A>::evict_assets
+                        .run_if(Assets::<A>::has_budget)
+                        .before(AssetEventSystems),
+                    Assets::<A>::asset_events
+                        .run_if(Assets::<A>::asset_events_condition)
+                        .in_set(AssetEventSystems),
+                ),
             )
             .add_systems(
                 PreUpdate,
@@ -675,8 +694,9 @@ mod tests {
             AssetReader, AssetReaderError, AssetSource, AssetSourceId, Reader,
         },
         loader::{AssetLoader, LoadContext},
-        Asset, AssetApp, AssetEvent, AssetId, AssetLoadError, AssetLoadFailedEvent, AssetPath,
-        AssetPlugin, AssetServer, Assets, InvalidGenerationError, LoadState, UnapprovedPathMode,
+        Asset, AssetApp, AssetBudget, AssetEvent, AssetId, AssetLoadError, AssetLoadFailedEvent,
+        AssetPath, AssetPlugin, AssetServer, Assets, InvalidGenerationError, LoadPriority,
+        LoadState, UnapprovedPathMode,
     };
     use alloc::{
         boxed::Box,
@@ -1556,6 +1576,327 @@ mod tests {
         assert_eq!(events, expected_events);
     }
 
+    #[test]
+    fn evict_over_budget_and_reload() {
+        let dir = Dir::default();
+        let a_path = "a.cool.ron";
+        let b_path = "b.cool.ron";
+        dir.insert_asset_text(Path::new(a_path), &SIMPLE_TEXT.replace("\"dep\"", "\"a\""));
+        dir.insert_asset_text(Path::new(b_path), &SIMPLE_TEXT.replace("\"dep\"", "\"b\""));
+
+        let (mut app, gate_opener) = test_app(dir);
+        app.init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .init_resource::<StoredEvents>()
+            .register_asset_loader(CoolTextLoader)
+            .add_systems(Update, store_asset_events);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        gate_opener.open(a_path);
+        gate_opener.open(b_path);
+        let a = asset_server.load::<CoolText>(a_path);
+        let b = asset_server.load::<CoolText>(b_path);
+        run_app_until(&mut app, |world| {
+            (get(world, a.id()).is_some() && get(world, b.id()).is_some()).then_some(())
+        });
+
+        // Only one of the two texts fits in the budget, but assets aren't evictable by default.
+        app.world_mut()
+            .resource_mut::<Assets<CoolText>>()
+            .set_budget(AssetBudget::new(1, |text: &CoolText| text.text.len()));
+        for _ in 0..3 {
+            app.update();
+        }
+        let texts = app.world().resource::<Assets<CoolText>>();
+        assert!(!texts.is_evicted(&a));
+        assert!(!texts.is_evicted(&b));
+
+        // Once they're evictable, the one that went unused is evicted, even though its handle is still held.
+        app.world_mut()
+            .resource_mut::<Assets<CoolText>>()
+            .set_budget(
+                AssetBudget::new(1, |text: &CoolText| text.text.len()).evictable_by_default(),
+            );
+        for _ in 0..3 {
+            assert!(get(app.world(), a.id()).is_some());
+            app.update();
+        }
+
+        let texts = app.world().resource::<Assets<CoolText>>();
+        assert!(!texts.is_evicted(&a));
+        assert!(texts.is_evicted(&b));
+        assert!(asset_server.load_state(&b).is_evicted());
+        let events = core::mem::take(&mut app.world_mut().resource_mut::<StoredEvents>().0);
+        assert!(events.contains(&AssetEvent::Evicted { id: b.id() }));
+        assert!(!events.contains(&AssetEvent::Evicted { id: a.id() }));
+
+        // Accessing the evicted text reloads it.
+        assert!(get(app.world(), b.id()).is_none());
+        gate_opener.open(b_path);
+        run_app_until(&mut app, |world| {
+            get(world, a.id())?;
+            get(world, b.id()).map(|text| assert_eq!(text.text, "b"))
+        });
+
+        // Now that the other text went unused the longest, it's evicted instead.
+        for _ in 0..2 {
+            assert!(get(app.world(), b.id()).is_some());
+            app.update();
+        }
+        assert!(app.world().resource::<Assets<CoolText>>().is_evicted(&a));
+        let events = core::mem::take(&mut app.world_mut().resource_mut::<StoredEvents>().0);
+        assert!(events.contains(&AssetEvent::Added { id: b.id() }));
+    }
+
+    #[test]
+    fn queued_loads_start_by_priority() {
+        let dir = Dir::default();
+        let paths = ["a.cool.ron", "b.cool.ron", "c.cool.ron", "d.cool.ron"];
+        for path in paths {
+            dir.insert_asset_text(Path::new(path), SIMPLE_TEXT);
+        }
+        let [a_path, b_path, c_path, d_path] = paths;
+
+        let (mut app, gate_opener) = test_app(dir);
+        app.init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .init_resource::<StoredEvents>()
+            .register_asset_loader(CoolTextLoader)
+            .add_systems(Update, store_asset_events);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        asset_server.set_max_concurrent_loads(1);
+        let a = asset_server.load::<CoolText>(a_path);
+        // Dropping every handle of a queued load cancels it. Its gate is never opened, so it would
+        // block the other loads if it started.
+        drop(asset_server.load::<CoolText>(d_path));
+        let b = asset_server.load::<CoolText>(b_path);
+        let c = asset_server.load_with_priority::<CoolText>(c_path, LoadPriority::LOW);
+        assert!(asset_server.set_load_priority(&c, LoadPriority::HIGH));
+        assert!(!asset_server.set_load_priority(AssetId::<CoolText>::default(), LoadPriority::HIGH));
+        app.update();
+
+        gate_opener.open(b_path);
+        gate_opener.open(c_path);
+        gate_opener.open(a_path);
+        run_app_until(&mut app, |world| get(world, b.id()).map(|_| ()));
+        app.update();
+
+        let events = core::mem::take(&mut app.world_mut().resource_mut::<StoredEvents>().0);
+        let added = events
+            .iter()
+            .filter_map(|event| match event {
+                AssetEvent::Added { id } => Some(*id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(added, [a.id(), c.id(), b.id()]);
+    }
+
+    #[test]
+    fn dropping_handles_cancels_in_flight_load() {
+        let dir = Dir::default();
+        let paths = ["a.cool.ron", "b.cool.ron"];
+        for path in paths {
+            dir.insert_asset_text(Path::new(path), SIMPLE_TEXT);
+        }
+        let [a_path, b_path] = paths;
+
+        let (mut app, gate_opener) = test_app(dir);
+        app.init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .register_asset_loader(CoolTextLoader);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        asset_server.set_max_concurrent_loads(1);
+        // The gate of `a` is never opened, so its load stays in flight and keeps `b` queued.
+        let a = asset_server.load::<CoolText>(a_path);
+        let b = asset_server.load::<CoolText>(b_path);
+        gate_opener.open(b_path);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert!(get(app.world(), b.id()).is_none());
+
+        let a_id = a.id();
+        drop(a);
+        run_app_until(&mut app, |world| get(world, b.id()).map(|_| ()));
+        assert!(matches!(
+            asset_server.load_state(a_id),
+            LoadState::NotLoaded
+        ));
+        assert!(get(app.world(), a_id).is_none());
+    }
+
+    #[test]
+    fn immediate_loads_do_not_wait_for_the_queue() {
+        let dir = Dir::default();
+        let a_path = "a.cool.ron";
+        let a_ron = r#"
+(
+    text: "a",
+    dependencies: ["b.cool.ron"],
+    embedded_dependencies: ["c.cool.ron"],
+    sub_texts: [],
+)"#;
+        let paths = [a_path, "b.cool.ron", "c.cool.ron"];
+        dir.insert_asset_text(Path::new(a_path), a_ron);
+        for path in &paths[1..] {
+            dir.insert_asset_text(Path::new(path), SIMPLE_TEXT);
+        }
+
+        let (mut app, gate_opener) = test_app(dir);
+        app.init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .register_asset_loader(CoolTextLoader);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        asset_server.set_max_concurrent_loads(1);
+        for path in paths {
+            gate_opener.open(path);
+        }
+        // The loader of `a` awaits its immediate load of `c` while it holds the only slot, and its
+        // dependency on `b` is queued until it's done.
+        let a = asset_server.load::<CoolText>(a_path);
+        run_app_until(&mut app, |_| {
+            asset_server.is_loaded_with_dependencies(&a).then_some(())
+        });
+        let a = get(app.world(), a.id()).unwrap();
+        assert_eq!(a.embedded, "dep");
+    }
+
+    #[test]
+    fn dependency_loads_inherit_priority() {
+        let dir = Dir::default();
+        let a_path = "a.cool.ron";
+        let a_ron = r#"
+(
+    text: "a",
+    dependencies: ["dep.cool.ron"],
+    embedded_dependencies: [],
+    sub_texts: [],
+)"#;
+        let b_path = "b.cool.ron";
+        let dep_path = "dep.cool.ron";
+        dir.insert_asset_text(Path::new(a_path), a_ron);
+        dir.insert_asset_text(Path::new(b_path), SIMPLE_TEXT);
+        dir.insert_asset_text(Path::new(dep_path), SIMPLE_TEXT);
+
+        let (mut app, gate_opener) = test_app(dir);
+        app.init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .init_resource::<StoredEvents>()
+            .register_asset_loader(CoolTextLoader)
+            .add_systems(Update, store_asset_events);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        asset_server.set_max_concurrent_loads(1);
+        let a = asset_server.load::<CoolText>(a_path);
+        let b = asset_server.load::<CoolText>(b_path);
+        app.update();
+        // `a` is already in flight, so this changes the priority of the dependency it loads, which then starts
+        // before `b` even though `b` was queued first.
+        assert!(asset_server.set_load_priority(&a, LoadPriority::HIGH));
+
+        gate_opener.open(a_path);
+        gate_opener.open(b_path);
+        gate_opener.open(dep_path);
+        run_app_until(&mut app, |world| get(world, b.id()).map(|_| ()));
+        app.update();
+
+        let dep = asset_server.get_handle_untyped(dep_path).unwrap().id();
+        let events = core::mem::take(&mut app.world_mut().resource_mut::<StoredEvents>().0);
+        let added = events
+            .iter()
+            .filter_map(|event| match event {
+                AssetEvent::Added { id } => Some(id.untyped()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(added, [a.id().untyped(), dep, b.id().untyped()]);
+    }
+
+    #[test]
+    fn dependency_graph() {
+        let dir = Dir::default();
+        let a_path = "a.cool.ron";
+        let a_ron = r#"
+(
+    text: "a",
+    dependencies: ["b.cool.ron"],
+    embedded_dependencies: ["c.cool.ron"],
+    sub_texts: ["hello"],
+)"#;
+        let b_path = "b.cool.ron";
+        let c_path = "c.cool.ron";
+        dir.insert_asset_text(Path::new(a_path), a_ron);
+        dir.insert_asset_text(Path::new(b_path), SIMPLE_TEXT);
+        dir.insert_asset_text(Path::new(c_path), SIMPLE_TEXT);
+
+        let (mut app, gate_opener) = test_app(dir);
+        app.init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .register_asset_loader(CoolTextLoader);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        // Loader dependencies are only tracked when watching for changes.
+        asset_server.data.infos.write().watching_for_changes = true;
+        gate_opener.open(a_path);
+        gate_opener.open(b_path);
+        gate_opener.open(c_path);
+        let a = asset_server.load::<CoolText>(a_path);
+        run_app_until(&mut app, |_| {
+            asset_server.is_loaded_with_dependencies(&a).then_some(())
+        });
+
+        let graph = asset_server.dependency_graph();
+        let b = asset_server.get_handle_untyped(b_path).unwrap().id();
+        let hello = graph.get_by_path("a.cool.ron#hello").next().unwrap().id;
+
+        let a_node = graph.get(&a).unwrap();
+        assert_eq!(a_node.path, Some(AssetPath::from(a_path)));
+        assert_eq!(
+            a_node.asset_type_name,
+            Some(core::any::type_name::<CoolText>())
+        );
+        assert_eq!(
+            a_node.loader,
+            Some(core::any::type_name::<CoolTextLoader>())
+        );
+        assert_eq!(a_node.processor, None);
+        assert!(a_node.dependencies.contains(&b));
+        assert_eq!(a_node.labeled_assets, [hello]);
+        assert_eq!(a_node.loader_dependencies, [AssetPath::from(c_path)]);
+
+        let b_node = graph.get(b).unwrap();
+        assert_eq!(b_node.dependents, [a.id().untyped()]);
+        assert_eq!(graph.recursive_dependents(b), [a.id().untyped()]);
+        assert!(graph.recursive_dependencies(&a).contains(&b));
+
+        let hello_node = graph.get(hello).unwrap();
+        assert_eq!(hello_node.parent, Some(a.id().untyped()));
+        assert_eq!(
+            hello_node.loader,
+            Some(core::any::type_name::<CoolTextLoader>())
+        );
+
+        // Changing the file read by `a`'s loader reloads `a` and its labeled assets, but not `b`.
+        let mut reloaded = graph.reloaded_by(c_path);
+        reloaded.sort_unstable();
+        let mut expected = vec![a.id().untyped(), hello];
+        expected.sort_unstable();
+        assert_eq!(reloaded, expected);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph assets {"));
+        assert!(dot.contains("a.cool.ron#hello"));
+        assert!(dot.contains("[style=dashed]"));
+        let json = graph.to_json();
+        assert!(json.starts_with("{\"nodes\":["));
+        assert!(json.contains("\"path\":\"a.cool.ron\""));
+        assert!(json.contains("\"loader_dependencies\":[\"c.cool.ron\"]"));
+    }
+
     #[test]
     fn load_folder() {
         let dir = Dir::default();
@@ -310,6 +310,7 @@ impl NestedLoader<'_, '_, StaticTyped, Deferred> {
                 self.meta_transform,
                 (),
                 true,
+                Some(self.load_context.asset_path()),
             )
         } else {
             self.load_context
@@ -339,6 +340,7 @@ impl NestedLoader<'_, '_, DynamicTyped, Deferred> {
                     self.typing.asset_type_id,
                     self.meta_transform,
                     (),
+                    Some(self.load_context.asset_path()),
                 )
         } else {
             self.load_context
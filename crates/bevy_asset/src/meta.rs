@@ -83,6 +83,9 @@ pub struct ProcessedInfo {
     pub full_hash: AssetHash,
     /// Information about the "process dependencies" used to process this asset.
     pub process_dependencies: Vec<ProcessDependencyInfo>,
+    /// The type name of the [`Process`] implementation that processed this asset, if it wasn't copied as is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processor: Option<String>,
 }
 
 /// Information about a dependency used to process an asset. This is used to determine whether an asset's "process dependency"
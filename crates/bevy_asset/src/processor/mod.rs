@@ -67,6 +67,8 @@ use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tracing::{debug, error, trace, warn};
 
+#[cfg(feature = "asset_pack")]
+use crate::io::pack::{AssetPackError, AssetPackWriter, PackCompression};
 #[cfg(feature = "trace")]
 use {
     alloc::string::ToString,
@@ -168,6 +170,24 @@ impl AssetProcessor {
         &self.data.sources
     }
 
+    /// Waits until processing has finished, then adds every processed asset of the `source` and its meta file
+    /// to `pack`, see [`AssetPackWriter::insert_from_reader`].
+    ///
+    /// The pack can then be turned into an overlay of a previous release with [`AssetPackWriter::retain_changes`].
+    #[cfg(feature = "asset_pack")]
+    pub async fn pack_processed_assets<'a>(
+        &self,
+        source: impl Into<AssetSourceId<'a>>,
+        pack: &mut AssetPackWriter,
+        compression: PackCompression,
+    ) -> Result<(), AssetPackError> {
+        self.data.wait_until_finished().await;
+        let reader = self.get_source(source)?.processed_reader()?;
+        pack.insert_from_reader(reader, Path::new(""), compression)
+            .await?;
+        Ok(())
+    }
+
     /// Logs an unrecoverable error. On the next run of the processor, all assets will be regenerated. This should only be used as a last resort.
     /// Every call to this should be considered with scrutiny and ideally replaced with something more granular.
     async fn log_unrecoverable(&self) {
@@ -886,6 +906,9 @@ impl AssetProcessor {
             hash: new_hash,
             full_hash: new_hash,
             process_dependencies: Vec::new(),
+            processor: processor
+                .as_ref()
+                .map(|processor| processor.type_name().into()),
         };
 
         {
@@ -1349,6 +1372,7 @@ impl ProcessorAssetInfos {
                         hash: AssetHash::default(),
                         full_hash: AssetHash::default(),
                         process_dependencies: vec![],
+                        processor: None,
                     });
                     self.add_dependent(dependency.path(), asset_path.to_owned());
                 }
@@ -221,6 +221,8 @@ pub trait ErasedProcessor: Send + Sync {
     fn deserialize_meta(&self, meta: &[u8]) -> Result<Box<dyn AssetMetaDyn>, DeserializeMetaError>;
     /// Returns the default type-erased [`AssetMeta`] for the underlying [`Process`] impl.
     fn default_meta(&self) -> Box<dyn AssetMetaDyn>;
+    /// Returns the type name of the underlying [`Process`] impl.
+    fn type_name(&self) -> &'static str;
 }
 
 impl<P: Process> ErasedProcessor for P {
@@ -255,6 +257,10 @@ impl<P: Process> ErasedProcessor for P {
             settings: P::Settings::default(),
         }))
     }
+
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<P>()
+    }
 }
 
 /// Provides scoped data access to the [`AssetProcessor`].
@@ -0,0 +1,435 @@
+use crate::{
+    AssetPath, DependencyLoadState, LoadState, RecursiveDependencyLoadState, UntypedAssetId,
+};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use bevy_platform::collections::{HashMap, HashSet};
+use core::fmt::Write;
+
+/// A snapshot of the assets tracked by the [`AssetServer`](crate::AssetServer) and the dependencies between them,
+/// returned by [`AssetServer::dependency_graph`](crate::AssetServer::dependency_graph).
+///
+/// The graph answers questions like "why is this asset loaded?" ([`AssetDependencyGraph::recursive_dependents`])
+/// and "what reloads if this file changes?" ([`AssetDependencyGraph::reloaded_by`]). It can be exported with
+/// [`AssetDependencyGraph::to_dot`] and [`AssetDependencyGraph::to_json`].
+///
+/// The snapshot doesn't change once it's taken. Call [`AssetServer::dependency_graph`](crate::AssetServer::dependency_graph)
+/// again to see the current state.
+#[derive(Clone, Debug, Default)]
+pub struct AssetDependencyGraph {
+    nodes: BTreeMap<UntypedAssetId, AssetGraphNode>,
+}
+
+/// An asset in an [`AssetDependencyGraph`].
+#[derive(Clone, Debug)]
+pub struct AssetGraphNode {
+    /// The id of the asset.
+    pub id: UntypedAssetId,
+    /// The path of the asset, if it was loaded from one.
+    pub path: Option<AssetPath<'static>>,
+    /// The type name of the asset. This is `None` until the asset has loaded.
+    pub asset_type_name: Option<&'static str>,
+    /// The load state of the asset.
+    pub load_state: LoadState,
+    /// The load state of the asset's direct dependencies.
+    pub dependency_load_state: DependencyLoadState,
+    /// The load state of the asset's recursive dependencies.
+    pub recursive_dependency_load_state: RecursiveDependencyLoadState,
+    /// The assets this asset depends on directly, as of its last load.
+    pub dependencies: Vec<UntypedAssetId>,
+    /// The assets that depend directly on this asset.
+    pub dependents: Vec<UntypedAssetId>,
+    /// The labeled sub-assets of this asset, such as `scene.gltf#Mesh0` for `scene.gltf`.
+    pub labeled_assets: Vec<UntypedAssetId>,
+    /// The asset this labeled sub-asset belongs to.
+    pub parent: Option<UntypedAssetId>,
+    /// The type name of the [`AssetLoader`](crate::AssetLoader) that loaded this asset, if it was loaded from a path.
+    pub loader: Option<&'static str>,
+    /// The type name of the [`Process`](crate::processor::Process) implementation that produced the processed
+    /// asset this was loaded from, if any.
+    pub processor: Option<Box<str>>,
+    /// The paths the asset's loader read while loading it, in addition to the asset's own path. A change to any
+    /// of them reloads this asset.
+    ///
+    /// This is only tracked while the [`AssetServer`](crate::AssetServer) is watching for changes.
+    pub loader_dependencies: Vec<AssetPath<'static>>,
+}
+
+impl AssetDependencyGraph {
+    /// Creates a graph from `nodes`, filling in [`AssetGraphNode::dependents`] and
+    /// [`AssetGraphNode::labeled_assets`].
+    pub(crate) fn new(nodes: impl IntoIterator<Item = AssetGraphNode>) -> Self {
+        let mut nodes: BTreeMap<_, _> = nodes.into_iter().map(|node| (node.id, node)).collect();
+        let mut dependents = Vec::new();
+        let mut labeled_assets = Vec::new();
+        for node in nodes.values() {
+            for dependency in &node.dependencies {
+                dependents.push((*dependency, node.id));
+            }
+            if let Some(parent) = node.parent {
+                labeled_assets.push((parent, node.id));
+            }
+        }
+        for (dependency, dependent) in dependents {
+            if let Some(node) = nodes.get_mut(&dependency) {
+                node.dependents.push(dependent);
+            }
+        }
+        for (parent, labeled_asset) in labeled_assets {
+            if let Some(node) = nodes.get_mut(&parent) {
+                node.labeled_assets.push(labeled_asset);
+            }
+        }
+        Self { nodes }
+    }
+
+    /// Returns the node of the asset with the given `id`, if the asset is tracked by the server.
+    pub fn get(&self, id: impl Into<UntypedAssetId>) -> Option<&AssetGraphNode> {
+        self.nodes.get(&id.into())
+    }
+
+    /// Iterates over the nodes of every tracked asset, ordered by id.
+    pub fn iter(&self) -> impl Iterator<Item = &AssetGraphNode> {
+        self.nodes.values()
+    }
+
+    /// Returns the number of assets in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the graph has no assets.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the nodes of the assets loaded from `path`. This includes the asset's labeled sub-assets if `path`
+    /// has no label.
+    pub fn get_by_path<'a>(
+        &self,
+        path: impl Into<AssetPath<'a>>,
+    ) -> impl Iterator<Item = &AssetGraphNode> {
+        let path = path.into().into_owned();
+        self.nodes.values().filter(move |node| {
+            node.path.as_ref().is_some_and(|node_path| {
+                if path.label().is_some() {
+                    *node_path == path
+                } else {
+                    node_path.without_label() == path
+                }
+            })
+        })
+    }
+
+    /// Returns every asset the asset with the given `id` depends on, directly or through other dependencies.
+    pub fn recursive_dependencies(&self, id: impl Into<UntypedAssetId>) -> Vec<UntypedAssetId> {
+        self.walk(id.into(), |node| &node.dependencies)
+    }
+
+    /// Returns every asset that depends on the asset with the given `id`, directly or through other dependents.
+    /// These are the assets keeping the asset loaded, other than handles held by the app.
+    pub fn recursive_dependents(&self, id: impl Into<UntypedAssetId>) -> Vec<UntypedAssetId> {
+        self.walk(id.into(), |node| &node.dependents)
+    }
+
+    /// Returns the assets that are reloaded when the file at `path` changes: the assets loaded from `path`,
+    /// and the assets whose loaders read it, transitively.
+    ///
+    /// Loader dependencies are only tracked while the [`AssetServer`](crate::AssetServer) is watching for changes,
+    /// which is also the only time assets are reloaded on change.
+    pub fn reloaded_by<'a>(&self, path: impl Into<AssetPath<'a>>) -> Vec<UntypedAssetId> {
+        let path = path.into().without_label().into_owned();
+        let mut changed = <HashSet<_>>::default();
+        let mut stack = vec![path];
+        while let Some(path) = stack.pop() {
+            if !changed.insert(path.clone()) {
+                continue;
+            }
+            for node in self.nodes.values() {
+                if node.loader_dependencies.contains(&path)
+                    && let Some(node_path) = &node.path
+                {
+                    stack.push(node_path.without_label().into_owned());
+                }
+            }
+        }
+        self.nodes
+            .values()
+            .filter(|node| {
+                node.path
+                    .as_ref()
+                    .is_some_and(|path| changed.contains(&path.without_label()))
+            })
+            .map(|node| node.id)
+            .collect()
+    }
+
+    fn walk(
+        &self,
+        id: UntypedAssetId,
+        edges: impl Fn(&AssetGraphNode) -> &Vec<UntypedAssetId>,
+    ) -> Vec<UntypedAssetId> {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            for next in edges(node) {
+                if visited.insert(*next) {
+                    stack.push(*next);
+                }
+            }
+        }
+        visited.remove(&id);
+        visited.into_iter().collect()
+    }
+
+    /// Exports the graph in the Graphviz DOT format. Dependencies are drawn as solid edges from the asset to its
+    /// dependency, and labeled sub-assets as dashed edges from the asset they belong to.
+    pub fn to_dot(&self) -> String {
+        let indices = self.indices();
+        let mut dot = String::from("digraph assets {\n");
+        for (index, node) in self.nodes.values().enumerate() {
+            let mut label = match &node.path {
+                Some(path) => path.to_string(),
+                None => String::from("<unnamed>"),
+            };
+            if let Some(asset_type_name) = node.asset_type_name {
+                let _ = write!(label, "\n{asset_type_name}");
+            }
+            let _ = write!(label, "\n{}", load_state_name(&node.load_state));
+            if let Some(loader) = node.loader {
+                let _ = write!(label, "\nloader: {loader}");
+            }
+            if let Some(processor) = &node.processor {
+                let _ = write!(label, "\nprocessor: {processor}");
+            }
+            let _ = writeln!(dot, "    n{index} [label={}];", dot_string(&label));
+        }
+        for (index, node) in self.nodes.values().enumerate() {
+            for dependency in &node.dependencies {
+                if let Some(dependency) = indices.get(dependency) {
+                    let _ = writeln!(dot, "    n{index} -> n{dependency};");
+                }
+            }
+            for labeled_asset in &node.labeled_assets {
+                if let Some(labeled_asset) = indices.get(labeled_asset) {
+                    let _ = writeln!(dot, "    n{index} -> n{labeled_asset} [style=dashed];");
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the graph as JSON: an object with a `nodes` array. Nodes refer to each other by their index in
+    /// that array.
+    pub fn to_json(&self) -> String {
+        let indices = self.indices();
+        let index_list = |ids: &[UntypedAssetId]| {
+            let indices: Vec<String> = ids
+                .iter()
+                .filter_map(|id| indices.get(id))
+                .map(ToString::to_string)
+                .collect();
+            format!("[{}]", indices.join(","))
+        };
+        let optional = |value: Option<String>| match value {
+            Some(value) => json_string(&value),
+            None => String::from("null"),
+        };
+        let nodes: Vec<String> = self
+            .nodes
+            .values()
+            .enumerate()
+            .map(|(index, node)| {
+                let loader_dependencies: Vec<String> = node
+                    .loader_dependencies
+                    .iter()
+                    .map(|path| json_string(&path.to_string()))
+                    .collect();
+                format!(
+                    "{{\"index\":{index},\"path\":{},\"asset_type_name\":{},\"load_state\":{},\"dependency_load_state\":{},\"recursive_dependency_load_state\":{},\"loader\":{},\"processor\":{},\"dependencies\":{},\"dependents\":{},\"labeled_assets\":{},\"parent\":{},\"loader_dependencies\":[{}]}}",
+                    optional(node.path.as_ref().map(ToString::to_string)),
+                    optional(node.asset_type_name.map(String::from)),
+                    json_string(load_state_name(&node.load_state)),
+                    json_string(dependency_load_state_name(&node.dependency_load_state)),
+                    json_string(recursive_dependency_load_state_name(
+                        &node.recursive_dependency_load_state
+                    )),
+                    optional(node.loader.map(String::from)),
+                    optional(node.processor.as_deref().map(String::from)),
+                    index_list(&node.dependencies),
+                    index_list(&node.dependents),
+                    index_list(&node.labeled_assets),
+                    node.parent
+                        .and_then(|parent| indices.get(&parent))
+                        .map_or_else(|| String::from("null"), ToString::to_string),
+                    loader_dependencies.join(","),
+                )
+            })
+            .collect();
+        format!("{{\"nodes\":[{}]}}", nodes.join(","))
+    }
+
+    fn indices(&self) -> HashMap<UntypedAssetId, usize> {
+        self.nodes
+            .keys()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect()
+    }
+}
+
+/// Quotes `value` as a DOT string. DOT strings only escape quotes, but labels also treat backslashes as the start
+/// of an escape sequence, like `\n` for a line break.
+fn dot_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes and escapes `value` as a JSON string.
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn load_state_name(state: &LoadState) -> &'static str {
+    match state {
+        LoadState::NotLoaded => "NotLoaded",
+        LoadState::Loading => "Loading",
+        LoadState::Loaded => "Loaded",
+        LoadState::Failed(_) => "Failed",
+        LoadState::Evicted => "Evicted",
+    }
+}
+
+fn dependency_load_state_name(state: &DependencyLoadState) -> &'static str {
+    match state {
+        DependencyLoadState::NotLoaded => "NotLoaded",
+        DependencyLoadState::Loading => "Loading",
+        DependencyLoadState::Loaded => "Loaded",
+        DependencyLoadState::Failed(_) => "Failed",
+    }
+}
+
+fn recursive_dependency_load_state_name(state: &RecursiveDependencyLoadState) -> &'static str {
+    match state {
+        RecursiveDependencyLoadState::NotLoaded => "NotLoaded",
+        RecursiveDependencyLoadState::Loading => "Loading",
+        RecursiveDependencyLoadState::Loaded => "Loaded",
+        RecursiveDependencyLoadState::Failed(_) => "Failed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use crate::{AssetId, LoadedUntypedAsset};
+
+    use super::*;
+
+    /// Reads the string literal that `input` starts with, undoing the escapes of the format. `escape` maps the
+    /// character after a backslash to the character it stands for.
+    fn unquote(input: &str, mut escape: impl FnMut(char, &mut core::str::Chars) -> char) -> String {
+        let mut chars = input.strip_prefix('"').unwrap().chars();
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return value,
+                '\\' => {
+                    let c = chars.next().unwrap();
+                    value.push(escape(c, &mut chars));
+                }
+                c => value.push(c),
+            }
+        }
+        panic!("unterminated string literal");
+    }
+
+    fn unquote_dot(input: &str) -> String {
+        unquote(input, |c, _| match c {
+            '"' | '\\' => c,
+            'n' => '\n',
+            c => panic!("`\\{c}` is not a DOT escape"),
+        })
+    }
+
+    fn unquote_json(input: &str) -> String {
+        unquote(input, |c, chars| match c {
+            '"' | '\\' | '/' => c,
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let code: String = chars.take(4).collect();
+                char::from_u32(u32::from_str_radix(&code, 16).unwrap()).unwrap()
+            }
+            c => panic!("`\\{c}` is not a JSON escape"),
+        })
+    }
+
+    #[test]
+    fn paths_round_trip_through_exports() {
+        let path = "say \"hi\"\\to\\u0041\t\u{1}file.txt";
+        let graph = AssetDependencyGraph::new([AssetGraphNode {
+            id: AssetId::<LoadedUntypedAsset>::default().untyped(),
+            path: Some(AssetPath::from(path)),
+            asset_type_name: None,
+            load_state: LoadState::Loaded,
+            dependency_load_state: DependencyLoadState::Loaded,
+            recursive_dependency_load_state: RecursiveDependencyLoadState::Loaded,
+            dependencies: Vec::new(),
+            dependents: Vec::new(),
+            labeled_assets: Vec::new(),
+            parent: None,
+            loader: None,
+            processor: None,
+            loader_dependencies: vec![AssetPath::from(path)],
+        }]);
+
+        let dot = graph.to_dot();
+        let (_, label) = dot.split_once("[label=").unwrap();
+        let label = unquote_dot(label);
+        assert_eq!(label.lines().next(), Some(path));
+
+        let json = graph.to_json();
+        let (_, json_path) = json.split_once("\"path\":").unwrap();
+        assert_eq!(unquote_json(json_path), path);
+        let (_, loader_dependency) = json.split_once("\"loader_dependencies\":[").unwrap();
+        assert_eq!(unquote_json(loader_dependency), path);
+    }
+}
@@ -1,3 +1,4 @@
+use super::{queue::CancelLoad, AssetDependencyGraph, AssetGraphNode};
 use crate::{
     meta::{AssetHash, MetaTransform},
     Asset, AssetHandleProvider, AssetLoadError, AssetPath, DependencyLoadState, ErasedLoadedAsset,
@@ -7,6 +8,7 @@ use crate::{
 use alloc::{
     borrow::ToOwned,
     boxed::Box,
+    string::ToString,
     sync::{Arc, Weak},
     vec::Vec,
 };
@@ -33,6 +35,15 @@ pub(crate) struct AssetInfo {
     failed_rec_dependencies: HashSet<UntypedAssetId>,
     dependents_waiting_on_load: HashSet<UntypedAssetId>,
     dependents_waiting_on_recursive_dep_load: HashSet<UntypedAssetId>,
+    /// The direct dependencies of this asset as of its last load, including the ones that have loaded.
+    dependencies: HashSet<UntypedAssetId>,
+    /// The type name of this asset. This is set once the asset has loaded.
+    asset_type_name: Option<&'static str>,
+    /// The type name of the [`AssetLoader`](crate::AssetLoader) that loaded this asset, if it was loaded from a path.
+    loader: Option<&'static str>,
+    /// The type name of the [`Process`](crate::processor::Process) implementation that produced the processed
+    /// asset this was loaded from, if any.
+    processor: Option<Box<str>>,
     /// The asset paths required to load this asset. Hashes will only be set for processed assets.
     /// This is set using the value from [`LoadedAsset`].
     /// This will only be populated if [`AssetInfos::watching_for_changes`] is set to `true` to
@@ -45,6 +56,8 @@ pub(crate) struct AssetInfo {
     handle_drops_to_skip: usize,
     /// List of tasks waiting for this asset to complete loading
     pub(crate) waiting_tasks: Vec<Waker>,
+    /// Cancels the load of this asset when this info is removed, because every handle to it was dropped.
+    pub(crate) cancel_load: Option<CancelLoad>,
 }
 
 impl AssetInfo {
@@ -62,8 +75,13 @@ impl AssetInfo {
             loader_dependencies: HashMap::default(),
             dependents_waiting_on_load: HashSet::default(),
             dependents_waiting_on_recursive_dep_load: HashSet::default(),
+            dependencies: HashSet::default(),
+            asset_type_name: None,
+            loader: None,
+            processor: None,
             handle_drops_to_skip: 0,
             waiting_tasks: Vec::new(),
+            cancel_load: None,
         }
     }
 }
@@ -223,7 +241,10 @@ impl AssetInfos {
                 let mut should_load = false;
                 if loading_mode == HandleLoadingMode::Force
                     || (loading_mode == HandleLoadingMode::Request
-                        && matches!(info.load_state, LoadState::NotLoaded | LoadState::Failed(_)))
+                        && matches!(
+                            info.load_state,
+                            LoadState::NotLoaded | LoadState::Failed(_) | LoadState::Evicted
+                        ))
                 {
                     info.load_state = LoadState::Loading;
                     info.dep_load_state = DependencyLoadState::Loading;
@@ -350,6 +371,58 @@ impl AssetInfos {
             .any(|info| info.weak_handle.strong_count() > 0)
     }
 
+    /// Records the loader and processor that produced `loaded_asset` and its labeled assets.
+    pub(crate) fn set_loaded_with(
+        &mut self,
+        id: UntypedAssetId,
+        loaded_asset: &ErasedLoadedAsset,
+        loader: &'static str,
+        processor: Option<&str>,
+    ) {
+        if let Some(info) = self.get_mut(id) {
+            info.loader = Some(loader);
+            info.processor = processor.map(Into::into);
+        }
+        for labeled_asset in loaded_asset.labeled_assets.values() {
+            self.set_loaded_with(
+                labeled_asset.handle.id(),
+                &labeled_asset.asset,
+                loader,
+                processor,
+            );
+        }
+    }
+
+    /// Builds a snapshot of the tracked assets and their dependencies.
+    pub(crate) fn dependency_graph(&self) -> AssetDependencyGraph {
+        AssetDependencyGraph::new(self.infos.iter().map(|(id, info)| {
+            let parent = info.path.as_ref().and_then(|path| {
+                path.label()?;
+                self.get_path_ids(&path.without_label()).next()
+            });
+            let mut dependencies: Vec<_> = info.dependencies.iter().copied().collect();
+            dependencies.sort_unstable();
+            let mut loader_dependencies: Vec<_> =
+                info.loader_dependencies.keys().cloned().collect();
+            loader_dependencies.sort_by_cached_key(ToString::to_string);
+            AssetGraphNode {
+                id: *id,
+                path: info.path.clone(),
+                asset_type_name: info.asset_type_name,
+                load_state: info.load_state.clone(),
+                dependency_load_state: info.dep_load_state.clone(),
+                recursive_dependency_load_state: info.rec_dep_load_state.clone(),
+                dependencies,
+                dependents: Vec::new(),
+                labeled_assets: Vec::new(),
+                parent,
+                loader: info.loader,
+                processor: info.processor.clone(),
+                loader_dependencies,
+            }
+        }))
+    }
+
     /// Returns `true` if the asset at this path should be reloaded
     pub(crate) fn should_reload(&self, path: &AssetPath) -> bool {
         if self.is_path_alive(path) {
@@ -389,7 +462,9 @@ impl AssetInfos {
             return;
         }
 
+        let asset_type_name = loaded_asset.asset_type_name();
         loaded_asset.value.insert(loaded_asset_id, world);
+        let dependencies = loaded_asset.dependencies.clone();
         let mut loading_deps = loaded_asset.dependencies;
         let mut failed_deps = <HashSet<_>>::default();
         let mut dep_error = None;
@@ -424,8 +499,9 @@ impl AssetInfos {
                         dep_info.dependents_waiting_on_load.insert(loaded_asset_id);
                         true
                     }
-                    LoadState::Loaded => {
-                        // If dependency is loaded, reduce our count by one
+                    LoadState::Loaded | LoadState::Evicted => {
+                        // If dependency is loaded, reduce our count by one. Evicted dependencies
+                        // were loaded and are reloaded on demand.
                         false
                     }
                     LoadState::Failed(ref error) => {
@@ -493,6 +569,8 @@ impl AssetInfos {
             info.load_state = LoadState::Loaded;
             info.dep_load_state = dep_load_state;
             info.rec_dep_load_state = rec_dep_load_state.clone();
+            info.dependencies = dependencies;
+            info.asset_type_name = Some(asset_type_name);
             if watching_for_changes {
                 info.loader_dependencies = loaded_asset.loader_dependencies;
             }
@@ -1,5 +1,7 @@
+mod graph;
 mod info;
 mod loaders;
+mod queue;
 
 use crate::{
     folder::LoadedFolder,
@@ -14,15 +16,15 @@ use crate::{
         MetaTransform, Settings,
     },
     path::AssetPath,
-    Asset, AssetEvent, AssetHandleProvider, AssetId, AssetLoadFailedEvent, AssetMetaCheck, Assets,
-    DeserializeMetaError, ErasedLoadedAsset, Handle, LoadedUntypedAsset, UnapprovedPathMode,
-    UntypedAssetId, UntypedAssetLoadFailedEvent, UntypedHandle,
+    Asset, AssetEvent, AssetHandleProvider, AssetId, AssetLoadFailedEvent, AssetMetaCheck,
+    AssetPlugin, Assets, DeserializeMetaError, ErasedLoadedAsset, Handle, LoadedUntypedAsset,
+    UnapprovedPathMode, UntypedAssetId, UntypedAssetLoadFailedEvent, UntypedHandle,
 };
 use alloc::{borrow::ToOwned, boxed::Box, vec, vec::Vec};
 use alloc::{
     format,
     string::{String, ToString},
-    sync::Arc,
+    sync::{Arc, Weak},
 };
 use atomicow::CowArc;
 use bevy_ecs::prelude::*;
@@ -32,9 +34,12 @@ use core::{any::TypeId, future::Future, panic::AssertUnwindSafe, task::Poll};
 use crossbeam_channel::{Receiver, Sender};
 use either::Either;
 use futures_lite::{FutureExt, StreamExt};
+pub use graph::{AssetDependencyGraph, AssetGraphNode};
 use info::*;
 use loaders::*;
 use parking_lot::{RwLock, RwLockWriteGuard};
+pub use queue::LoadPriority;
+use queue::{cancel_signal, LoadQueue};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tracing::{error, info};
@@ -69,6 +74,7 @@ pub(crate) struct AssetServerData {
     mode: AssetServerMode,
     meta_check: AssetMetaCheck,
     unapproved_path_mode: UnapprovedPathMode,
+    load_queue: LoadQueue,
 }
 
 /// The "asset mode" the server is currently in.
@@ -139,6 +145,7 @@ impl AssetServer {
                 loaders,
                 infos: RwLock::new(infos),
                 unapproved_path_mode,
+                load_queue: LoadQueue::new(AssetPlugin::DEFAULT_MAX_CONCURRENT_LOADS),
             }),
         }
     }
@@ -151,6 +158,19 @@ impl AssetServer {
         self.data.sources.get(source.into())
     }
 
+    /// Returns how many loads can be in flight at once before further loads are queued by [`LoadPriority`].
+    pub fn max_concurrent_loads(&self) -> usize {
+        self.data.load_queue.max_concurrent_loads()
+    }
+
+    /// Sets how many loads can be in flight at once before further loads are queued by [`LoadPriority`].
+    /// This is configured by [`AssetPlugin::max_concurrent_loads`].
+    pub fn set_max_concurrent_loads(&self, max_concurrent_loads: usize) {
+        self.data
+            .load_queue
+            .set_max_concurrent_loads(max_concurrent_loads);
+    }
+
     /// Returns true if the [`AssetServer`] watches for changes.
     pub fn watching_for_changes(&self) -> bool {
         self.data.infos.read().watching_for_changes
@@ -322,7 +342,35 @@ impl AssetServer {
     /// The asset load will fail and an error will be printed to the logs if the asset stored at `path` is not of type `A`.
     #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
     pub fn load<'a, A: Asset>(&self, path: impl Into<AssetPath<'a>>) -> Handle<A> {
-        self.load_with_meta_transform(path, None, (), false)
+        self.load_with_meta_transform(path, None, (), false, None)
+    }
+
+    /// Begins loading an [`Asset`] of type `A` stored at `path` with the given [`LoadPriority`].
+    ///
+    /// Once [`AssetPlugin::max_concurrent_loads`] loads are in flight, further loads are queued and the ones with the
+    /// highest priority start first. If the asset is already being loaded, its priority is changed instead. To change
+    /// the priority of loads started some other way, like [`AssetServer::load_with_settings`], use
+    /// [`AssetServer::set_load_priority`].
+    ///
+    /// Dependencies loaded by the asset's [`AssetLoader`] are queued with the same priority.
+    #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
+    pub fn load_with_priority<'a, A: Asset>(
+        &self,
+        path: impl Into<AssetPath<'a>>,
+        priority: LoadPriority,
+    ) -> Handle<A> {
+        let handle = self.load(path);
+        self.set_load_priority(&handle, priority);
+        handle
+    }
+
+    /// Changes the [`LoadPriority`] of the load of the asset with the given `id`, so that it starts sooner or later
+    /// than it would have. If the load has already started, this changes the priority of the dependency loads it
+    /// starts instead. Returns `false` if the asset isn't being loaded.
+    ///
+    /// A load is cancelled when every strong [`Handle`] to its asset is dropped, whether it's queued or in flight.
+    pub fn set_load_priority(&self, id: impl Into<UntypedAssetId>, priority: LoadPriority) -> bool {
+        self.data.load_queue.set_priority(id.into(), priority)
     }
 
     /// Same as [`load`](AssetServer::load), but you can load assets from unaproved paths
@@ -331,7 +379,7 @@ impl AssetServer {
     ///
     /// See [`UnapprovedPathMode`] and [`AssetPath::is_unapproved`]
     pub fn load_override<'a, A: Asset>(&self, path: impl Into<AssetPath<'a>>) -> Handle<A> {
-        self.load_with_meta_transform(path, None, (), true)
+        self.load_with_meta_transform(path, None, (), true, None)
     }
 
     /// Begins loading an [`Asset`] of type `A` stored at `path` while holding a guard item.
@@ -355,7 +403,7 @@ impl AssetServer {
         path: impl Into<AssetPath<'a>>,
         guard: G,
     ) -> Handle<A> {
-        self.load_with_meta_transform(path, None, guard, false)
+        self.load_with_meta_transform(path, None, guard, false, None)
     }
 
     /// Same as [`load`](AssetServer::load_acquire), but you can load assets from unaproved paths
@@ -368,7 +416,7 @@ impl AssetServer {
         path: impl Into<AssetPath<'a>>,
         guard: G,
     ) -> Handle<A> {
-        self.load_with_meta_transform(path, None, guard, true)
+        self.load_with_meta_transform(path, None, guard, true, None)
     }
 
     /// Begins loading an [`Asset`] of type `A` stored at `path`. The given `settings` function will override the asset's
@@ -385,6 +433,7 @@ impl AssetServer {
             Some(loader_settings_meta_transform(settings)),
             (),
             false,
+            None,
         )
     }
 
@@ -403,6 +452,7 @@ impl AssetServer {
             Some(loader_settings_meta_transform(settings)),
             (),
             true,
+            None,
         )
     }
 
@@ -427,6 +477,7 @@ impl AssetServer {
             Some(loader_settings_meta_transform(settings)),
             guard,
             false,
+            None,
         )
     }
 
@@ -451,6 +502,7 @@ impl AssetServer {
             Some(loader_settings_meta_transform(settings)),
             guard,
             true,
+            None,
         )
     }
 
@@ -460,6 +512,7 @@ impl AssetServer {
         meta_transform: Option<MetaTransform>,
         guard: G,
         override_unapproved: bool,
+        parent: Option<&AssetPath<'static>>,
     ) -> Handle<A> {
         let path = path.into().into_owned();
 
@@ -481,7 +534,7 @@ impl AssetServer {
         );
 
         if should_load {
-            self.spawn_load_task(handle.clone().untyped(), path, infos, guard);
+            self.spawn_load_task(handle.clone().untyped(), path, parent, infos, guard);
         }
 
         handle
@@ -493,6 +546,7 @@ impl AssetServer {
         type_id: TypeId,
         meta_transform: Option<MetaTransform>,
         guard: G,
+        parent: Option<&AssetPath<'static>>,
     ) -> UntypedHandle {
         let path = path.into().into_owned();
         let mut infos = self.data.infos.write();
@@ -505,7 +559,7 @@ impl AssetServer {
         );
 
         if should_load {
-            self.spawn_load_task(handle.clone(), path, infos, guard);
+            self.spawn_load_task(handle.clone(), path, parent, infos, guard);
         }
 
         handle
@@ -515,30 +569,51 @@ impl AssetServer {
         &self,
         handle: UntypedHandle,
         path: AssetPath<'static>,
-        infos: RwLockWriteGuard<AssetInfos>,
+        parent: Option<&AssetPath<'static>>,
+        mut infos: RwLockWriteGuard<AssetInfos>,
         guard: G,
     ) {
+        // Dropping every handle to the asset removes its info, which cancels the load.
+        let (cancel_load, load_cancelled) = cancel_signal();
+        if let Some(info) = infos.get_mut(handle.id()) {
+            info.cancel_load = Some(cancel_load);
+        }
+
         // drop the lock on `AssetInfos` before spawning a task that may block on it in single-threaded
         #[cfg(any(target_arch = "wasm32", not(feature = "multi_threaded")))]
         drop(infos);
 
-        let owned_handle = handle.clone();
+        // Only hold a weak reference to the handle, so that it can be dropped while the asset loads.
+        let queued_handle = match &handle {
+            UntypedHandle::Strong(handle) => Either::Left(Arc::downgrade(handle)),
+            handle => Either::Right(handle.clone()),
+        };
+        let queued = self.data.load_queue.enqueue(handle.id(), &path, parent);
         let server = self.clone();
         let task = IoTaskPool::get().spawn(async move {
-            if let Err(err) = server
-                .load_internal(Some(owned_handle), path, false, None)
-                .await
-            {
-                error!("{}", err);
-            }
+            let load = async {
+                let _permit = queued.await;
+                let owned_handle = match queued_handle {
+                    Either::Left(handle) => match handle.upgrade() {
+                        Some(handle) => UntypedHandle::Strong(handle),
+                        None => return,
+                    },
+                    Either::Right(handle) => handle,
+                };
+                if let Err(err) = server
+                    .load_internal(Some(owned_handle), path, false, None)
+                    .await
+                {
+                    error!("{}", err);
+                }
+            };
+            // Stops the load and releases its permit if it's cancelled.
+            load.or(load_cancelled).await;
             drop(guard);
         });
 
         #[cfg(not(any(target_arch = "wasm32", not(feature = "multi_threaded"))))]
-        {
-            let mut infos = infos;
-            infos.pending_tasks.insert(handle.id(), task);
-        }
+        infos.pending_tasks.insert(handle.id(), task);
 
         #[cfg(any(target_arch = "wasm32", not(feature = "multi_threaded")))]
         task.detach();
@@ -658,7 +733,14 @@ impl AssetServer {
         force: bool,
         meta_transform: Option<MetaTransform>,
     ) -> Result<Option<UntypedHandle>, AssetLoadError> {
+        let input_handle_id = input_handle.as_ref().map(UntypedHandle::id);
         let input_handle_type_id = input_handle.as_ref().map(UntypedHandle::type_id);
+        // Only hold a weak reference to the input handle while awaiting, so that the load can be
+        // cancelled if every other handle is dropped.
+        let input_handle = input_handle.map(|handle| match handle {
+            UntypedHandle::Strong(handle) => Arc::downgrade(&handle),
+            UntypedHandle::Uuid { .. } => Weak::new(),
+        });
 
         let path = path.into_owned();
         let path_clone = path.clone();
@@ -668,26 +750,28 @@ impl AssetServer {
             .inspect_err(|e| {
                 // if there was an input handle, a "load" operation has already started, so we must produce a "failure" event, if
                 // we cannot find the meta and loader
-                if let Some(handle) = &input_handle {
+                if let Some(id) = input_handle_id {
                     self.send_asset_event(InternalAssetEvent::Failed {
-                        id: handle.id(),
+                        id,
                         path: path.clone_owned(),
                         error: e.clone(),
                     });
                 }
             })?;
 
-        if let Some(meta_transform) = input_handle.as_ref().and_then(|h| h.meta_transform()) {
+        if let Some(handle) = input_handle.as_ref().and_then(Weak::upgrade)
+            && let Some(meta_transform) = &handle.meta_transform
+        {
             (*meta_transform)(&mut *meta);
         }
 
         let asset_id; // The asset ID of the asset we are trying to load.
         let fetched_handle; // The handle if one was looked up/created.
         let should_load; // Whether we need to load the asset.
-        if let Some(input_handle) = input_handle {
-            asset_id = Some(input_handle.id());
-            // In this case, we intentionally drop the input handle so we can cancel loading the
-            // asset if the handle gets dropped (externally) before it finishes loading.
+        if let Some(input_handle_id) = input_handle_id {
+            asset_id = Some(input_handle_id);
+            // In this case, we intentionally don't hold the input handle so we can cancel loading
+            // the asset if the handle gets dropped (externally) before it finishes loading.
             fetched_handle = None;
             // The handle was passed in, so the "should_load" check was already done.
             should_load = true;
@@ -799,6 +883,16 @@ impl AssetServer {
                     fetched_handle
                 };
 
+                let processor = meta
+                    .processed_info()
+                    .as_ref()
+                    .and_then(|info| info.processor.as_deref());
+                self.data.infos.write().set_loaded_with(
+                    base_asset_id,
+                    &loaded_asset,
+                    loader.type_name(),
+                    processor,
+                );
                 self.send_loaded_asset(base_asset_id, loaded_asset);
                 Ok(final_handle)
             }
@@ -1185,6 +1279,15 @@ impl AssetServer {
         )
     }
 
+    /// Returns a snapshot of every asset tracked by this server: its load states, its dependencies and dependents,
+    /// its labeled sub-assets, and the loader and processor that produced it.
+    ///
+    /// This is meant for debugging and tooling. Building the graph locks the server's asset tracking and visits
+    /// every tracked asset, so avoid calling it every frame.
+    pub fn dependency_graph(&self) -> AssetDependencyGraph {
+        self.data.infos.read().dependency_graph()
+    }
+
     /// Returns an active handle for the given path, if the asset at the given path has already started loading,
     /// or is still "alive".
     pub fn get_handle<'a, A: Asset>(&self, path: impl Into<AssetPath<'a>>) -> Option<Handle<A>> {
@@ -1540,7 +1643,9 @@ impl AssetServer {
         match (&info.load_state, &info.rec_dep_load_state) {
             (LoadState::Loaded, RecursiveDependencyLoadState::Loaded) => Poll::Ready(Ok(())),
             // Return an error immediately if the asset is not in the process of loading
-            (LoadState::NotLoaded, _) => Poll::Ready(Err(WaitForAssetError::NotLoaded)),
+            (LoadState::NotLoaded | LoadState::Evicted, _) => {
+                Poll::Ready(Err(WaitForAssetError::NotLoaded))
+            }
             // If the asset is loading, leave our waker behind
             (LoadState::Loading, _)
             | (_, RecursiveDependencyLoadState::Loading)
@@ -1802,6 +1907,10 @@ pub enum LoadState {
     /// referenced by [`Arc`] clones in all related [`DependencyLoadState`]s
     /// and [`RecursiveDependencyLoadState`]s in the asset's dependency tree.
     Failed(Arc<AssetLoadError>),
+
+    /// The asset was loaded, but has since been unloaded to stay within its [`AssetBudget`](crate::AssetBudget).
+    /// It will be reloaded the next time it is accessed or loaded.
+    Evicted,
 }
 
 impl LoadState {
@@ -1819,6 +1928,11 @@ impl LoadState {
     pub fn is_failed(&self) -> bool {
         matches!(self, Self::Failed(_))
     }
+
+    /// Returns `true` if this instance is [`LoadState::Evicted`]
+    pub fn is_evicted(&self) -> bool {
+        matches!(self, Self::Evicted)
+    }
 }
 
 /// The load state of an asset's dependencies.
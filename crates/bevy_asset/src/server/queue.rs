@@ -0,0 +1,314 @@
+use crate::{AssetPath, UntypedAssetId};
+use alloc::{collections::BTreeSet, sync::Arc, vec::Vec};
+use bevy_platform::collections::{HashMap, HashSet};
+use core::{
+    cmp::Reverse,
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use parking_lot::Mutex;
+
+/// The priority of an asset load, set with [`AssetServer::load_with_priority`](crate::AssetServer::load_with_priority)
+/// or [`AssetServer::set_load_priority`](crate::AssetServer::set_load_priority).
+///
+/// Once [`AssetPlugin::max_concurrent_loads`](crate::AssetPlugin::max_concurrent_loads) loads are in flight,
+/// further loads are queued and started in order of priority, highest first. Loads with the same priority start
+/// in the order they were requested.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoadPriority(pub i32);
+
+impl LoadPriority {
+    /// A priority for loads that can wait until everything else is loaded.
+    pub const LOW: Self = Self(-100);
+    /// The priority of loads started with [`AssetServer::load`](crate::AssetServer::load).
+    pub const NORMAL: Self = Self(0);
+    /// A priority for loads that are needed as soon as possible.
+    pub const HIGH: Self = Self(100);
+}
+
+/// Limits how many loads run at once, starting queued loads in order of [`LoadPriority`].
+#[derive(Clone)]
+pub(crate) struct LoadQueue {
+    state: Arc<Mutex<LoadQueueState>>,
+}
+
+struct LoadQueueState {
+    max_concurrent_loads: usize,
+    running: usize,
+    next_ticket: u64,
+    /// Queued loads, highest priority first, then in the order they were queued.
+    order: BTreeSet<(Reverse<LoadPriority>, u64)>,
+    /// Loads that are queued or in flight.
+    loads: HashMap<u64, QueuedLoad>,
+    /// The tickets of the loads of each asset.
+    by_id: HashMap<UntypedAssetId, Vec<u64>>,
+    /// The tickets of the loads of each path, without its label.
+    by_path: HashMap<AssetPath<'static>, Vec<u64>>,
+    /// The tickets of the loads started by the loader of each path.
+    by_parent: HashMap<AssetPath<'static>, Vec<u64>>,
+}
+
+fn index<K: Eq + Hash>(index: &mut HashMap<K, Vec<u64>>, key: K, ticket: u64) {
+    index.entry(key).or_default().push(ticket);
+}
+
+fn unindex<K: Eq + Hash>(index: &mut HashMap<K, Vec<u64>>, key: &K, ticket: u64) {
+    if let Some(tickets) = index.get_mut(key) {
+        tickets.retain(|indexed| *indexed != ticket);
+        if tickets.is_empty() {
+            index.remove(key);
+        }
+    }
+}
+
+struct QueuedLoad {
+    id: UntypedAssetId,
+    /// The path of the asset being loaded, without its label.
+    path: AssetPath<'static>,
+    /// The path of the asset whose loader started this load, if any.
+    parent: Option<AssetPath<'static>>,
+    priority: LoadPriority,
+    started: bool,
+    waker: Option<Waker>,
+}
+
+impl LoadQueueState {
+    /// Wakes the queued load that should start next, if there's room for it.
+    fn wake_next(&mut self) {
+        if self.running >= self.max_concurrent_loads {
+            return;
+        }
+        if let Some((_, ticket)) = self.order.first()
+            && let Some(waker) = self
+                .loads
+                .get_mut(ticket)
+                .and_then(|queued| queued.waker.take())
+        {
+            waker.wake();
+        }
+    }
+
+    fn remove(&mut self, ticket: u64) {
+        let Some(queued) = self.loads.remove(&ticket) else {
+            return;
+        };
+        if !queued.started {
+            self.order.remove(&(Reverse(queued.priority), ticket));
+        }
+        unindex(&mut self.by_id, &queued.id, ticket);
+        unindex(&mut self.by_path, &queued.path, ticket);
+        if let Some(parent) = &queued.parent {
+            unindex(&mut self.by_parent, parent, ticket);
+        }
+    }
+}
+
+impl LoadQueue {
+    pub(crate) fn new(max_concurrent_loads: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(LoadQueueState {
+                max_concurrent_loads: max_concurrent_loads.max(1),
+                running: 0,
+                next_ticket: 0,
+                order: BTreeSet::new(),
+                loads: HashMap::default(),
+                by_id: HashMap::default(),
+                by_path: HashMap::default(),
+                by_parent: HashMap::default(),
+            })),
+        }
+    }
+
+    pub(crate) fn max_concurrent_loads(&self) -> usize {
+        self.state.lock().max_concurrent_loads
+    }
+
+    pub(crate) fn set_max_concurrent_loads(&self, max_concurrent_loads: usize) {
+        let mut state = self.state.lock();
+        state.max_concurrent_loads = max_concurrent_loads.max(1);
+        state.wake_next();
+    }
+
+    /// Queues a load of the asset with the given `id`. The returned future resolves to a [`LoadPermit`] once the
+    /// load can start. Dropping the future removes the load from the queue.
+    ///
+    /// If the load was started by the loader of the asset at `parent`, it inherits that load's priority, and keeps
+    /// following it when it's changed with [`LoadQueue::set_priority`].
+    pub(crate) fn enqueue(
+        &self,
+        id: UntypedAssetId,
+        path: &AssetPath<'static>,
+        parent: Option<&AssetPath<'static>>,
+    ) -> QueuedLoadFuture {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        let priority = parent
+            .and_then(|parent| state.by_path.get(parent)?.first())
+            .and_then(|ticket| state.loads.get(ticket))
+            .map_or(LoadPriority::NORMAL, |load| load.priority);
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.order.insert((Reverse(priority), ticket));
+        let path = path.without_label().into_owned();
+        index(&mut state.by_id, id, ticket);
+        index(&mut state.by_path, path.clone(), ticket);
+        if let Some(parent) = parent {
+            index(&mut state.by_parent, parent.clone(), ticket);
+        }
+        state.loads.insert(
+            ticket,
+            QueuedLoad {
+                id,
+                path,
+                parent: parent.cloned(),
+                priority,
+                started: false,
+                waker: None,
+            },
+        );
+        QueuedLoadFuture {
+            queue: self.clone(),
+            ticket: Some(ticket),
+        }
+    }
+
+    /// Changes the priority of the queued or in-flight loads of the asset with the given `id`, along with the loads
+    /// they started. Returns `false` if the asset isn't being loaded.
+    pub(crate) fn set_priority(&self, id: UntypedAssetId, priority: LoadPriority) -> bool {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        let Some(mut tickets) = state.by_id.get(&id).cloned() else {
+            return false;
+        };
+        let mut visited = HashSet::new();
+        while let Some(ticket) = tickets.pop() {
+            if !visited.insert(ticket) {
+                continue;
+            }
+            let Some(load) = state.loads.get_mut(&ticket) else {
+                continue;
+            };
+            if !load.started {
+                state.order.remove(&(Reverse(load.priority), ticket));
+                state.order.insert((Reverse(priority), ticket));
+            }
+            load.priority = priority;
+            if let Some(children) = state.by_parent.get(&load.path) {
+                tickets.extend(children);
+            }
+        }
+        state.wake_next();
+        true
+    }
+}
+
+/// A load waiting in a [`LoadQueue`].
+pub(crate) struct QueuedLoadFuture {
+    queue: LoadQueue,
+    /// `None` once the load has started.
+    ticket: Option<u64>,
+}
+
+impl Future for QueuedLoadFuture {
+    type Output = LoadPermit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Some(ticket) = self.ticket else {
+            panic!("`QueuedLoadFuture` polled after completion");
+        };
+        let mut state = self.queue.state.lock();
+        let is_next = state.order.first().is_some_and(|(_, next)| *next == ticket);
+        if is_next && state.running < state.max_concurrent_loads {
+            state.order.pop_first();
+            if let Some(queued) = state.loads.get_mut(&ticket) {
+                queued.started = true;
+            }
+            state.running += 1;
+            // There may be room for more than one load.
+            state.wake_next();
+            drop(state);
+            self.ticket = None;
+            return Poll::Ready(LoadPermit {
+                queue: self.queue.clone(),
+                ticket,
+            });
+        }
+        if let Some(queued) = state.loads.get_mut(&ticket) {
+            queued.waker = Some(cx.waker().clone());
+        }
+        // The load that's next may not have been polled yet.
+        state.wake_next();
+        Poll::Pending
+    }
+}
+
+impl Drop for QueuedLoadFuture {
+    fn drop(&mut self) {
+        if let Some(ticket) = self.ticket {
+            let mut state = self.queue.state.lock();
+            state.remove(ticket);
+            state.wake_next();
+        }
+    }
+}
+
+/// Allows a load to run. The next queued load starts when this is dropped.
+pub(crate) struct LoadPermit {
+    queue: LoadQueue,
+    ticket: u64,
+}
+
+impl Drop for LoadPermit {
+    fn drop(&mut self) {
+        let mut state = self.queue.state.lock();
+        state.remove(self.ticket);
+        state.running -= 1;
+        state.wake_next();
+    }
+}
+
+/// Creates a signal that cancels a load. The returned [`LoadCancelled`] future resolves once the [`CancelLoad`] is
+/// dropped.
+pub(crate) fn cancel_signal() -> (CancelLoad, LoadCancelled) {
+    let state = Arc::new(Mutex::new(CancelState::default()));
+    (CancelLoad(state.clone()), LoadCancelled(state))
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: bool,
+    waker: Option<Waker>,
+}
+
+/// Cancels a load when dropped. This is stored in the [`AssetInfo`](super::info::AssetInfo) of the loading asset,
+/// which is removed once every strong handle to the asset is dropped.
+#[derive(Debug)]
+pub(crate) struct CancelLoad(Arc<Mutex<CancelState>>);
+
+impl Drop for CancelLoad {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.cancelled = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Resolves once the matching [`CancelLoad`] is dropped.
+pub(crate) struct LoadCancelled(Arc<Mutex<CancelState>>);
+
+impl Future for LoadCancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.lock();
+        if state.cancelled {
+            return Poll::Ready(());
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,110 @@
+use bevy_app::prelude::*;
+use bevy_ecs::{schedule::IntoScheduleConfigs, world::World};
+use bevy_time::common_conditions::on_real_timer;
+use core::time::Duration;
+
+use crate::{
+    Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic, DEFAULT_MAX_HISTORY_LENGTH,
+};
+
+/// Adds diagnostics for the heap memory used by the entities and components of the [`World`],
+/// computed with [`World::memory_report`].
+///
+/// Since the report walks every table, archetype and component of the world, the measurements are only taken once
+/// every [`wait_duration`](Self::wait_duration), rather than every frame.
+///
+/// Call [`World::memory_report`] directly for a breakdown per component type, table and archetype.
+///
+/// # See also
+///
+/// [`LogDiagnosticsPlugin`](crate::LogDiagnosticsPlugin) to output diagnostics to the console.
+pub struct WorldMemoryDiagnosticsPlugin {
+    /// The total number of values to keep.
+    pub max_history_length: usize,
+    /// Time to wait between taking measurements and taking them again.
+    ///
+    /// Defaults to one second.
+    pub wait_duration: Duration,
+}
+
+impl Default for WorldMemoryDiagnosticsPlugin {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_HISTORY_LENGTH)
+    }
+}
+
+impl WorldMemoryDiagnosticsPlugin {
+    /// Creates a new `WorldMemoryDiagnosticsPlugin` with the specified `max_history_length`.
+    pub fn new(max_history_length: usize) -> Self {
+        Self {
+            max_history_length,
+            wait_duration: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Plugin for WorldMemoryDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        for (path, suffix) in [
+            (Self::ALLOCATED_BYTES, "B"),
+            (Self::USED_BYTES, "B"),
+            (Self::TABLE_SLACK_BYTES, "B"),
+            (Self::ARCHETYPE_COUNT, ""),
+            (Self::SMALL_ARCHETYPE_COUNT, ""),
+        ] {
+            app.register_diagnostic(
+                Diagnostic::new(path)
+                    .with_suffix(suffix)
+                    .with_max_history_length(self.max_history_length),
+            );
+        }
+        app.add_systems(
+            Update,
+            Self::diagnostic_system.run_if(on_real_timer(self.wait_duration)),
+        );
+    }
+}
+
+impl WorldMemoryDiagnosticsPlugin {
+    /// Bytes allocated for entities, components, archetypes and bundles.
+    pub const ALLOCATED_BYTES: DiagnosticPath = DiagnosticPath::const_new("world_memory/allocated");
+
+    /// Bytes of the allocations that hold values.
+    pub const USED_BYTES: DiagnosticPath = DiagnosticPath::const_new("world_memory/used");
+
+    /// Bytes allocated by tables but unused, summed over all tables.
+    pub const TABLE_SLACK_BYTES: DiagnosticPath =
+        DiagnosticPath::const_new("world_memory/table_slack");
+
+    /// Number of archetypes.
+    pub const ARCHETYPE_COUNT: DiagnosticPath =
+        DiagnosticPath::const_new("world_memory/archetypes");
+
+    /// Number of archetypes with at most [`Self::SMALL_ARCHETYPE_MAX_ENTITIES`] entities.
+    pub const SMALL_ARCHETYPE_COUNT: DiagnosticPath =
+        DiagnosticPath::const_new("world_memory/small_archetypes");
+
+    /// The maximum number of entities of an archetype counted by [`Self::SMALL_ARCHETYPE_COUNT`].
+    pub const SMALL_ARCHETYPE_MAX_ENTITIES: usize = 4;
+
+    /// Updates the world memory measurements.
+    pub fn diagnostic_system(mut diagnostics: Diagnostics, world: &World) {
+        let report = world.memory_report();
+        let total = report.total();
+        diagnostics.add_measurement(&Self::ALLOCATED_BYTES, || total.allocated as f64);
+        diagnostics.add_measurement(&Self::USED_BYTES, || total.used as f64);
+        diagnostics.add_measurement(&Self::TABLE_SLACK_BYTES, || {
+            report
+                .tables
+                .iter()
+                .map(|table| table.memory.slack())
+                .sum::<usize>() as f64
+        });
+        diagnostics.add_measurement(&Self::ARCHETYPE_COUNT, || report.archetypes.len() as f64);
+        diagnostics.add_measurement(&Self::SMALL_ARCHETYPE_COUNT, || {
+            report
+                .small_archetypes(Self::SMALL_ARCHETYPE_MAX_ENTITIES)
+                .count() as f64
+        });
+    }
+}
@@ -158,6 +158,25 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
         .on_remove
         .map(|path| path.to_token_stream(&bevy_ecs_path));
 
+    let relationship_trait = if attrs
+        .relationship
+        .as_ref()
+        .is_some_and(|relationship| relationship.many_to_many)
+    {
+        quote!(#bevy_ecs_path::relationship::ManyToManyRelationship)
+    } else {
+        quote!(#bevy_ecs_path::relationship::Relationship)
+    };
+    let relationship_target_trait = if attrs
+        .relationship_target
+        .as_ref()
+        .is_some_and(|relationship_target| relationship_target.many_to_many)
+    {
+        quote!(#bevy_ecs_path::relationship::ManyToManyRelationshipTarget)
+    } else {
+        quote!(#bevy_ecs_path::relationship::RelationshipTarget)
+    };
+
     let on_insert_path = if relationship.is_some() {
         if attrs.on_insert.is_some() {
             return syn::Error::new(
@@ -168,7 +187,7 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
             .into();
         }
 
-        Some(quote!(<Self as #bevy_ecs_path::relationship::Relationship>::on_insert))
+        Some(quote!(<Self as #relationship_trait>::on_insert))
     } else {
         attrs
             .on_insert
@@ -185,7 +204,7 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
             .into();
         }
 
-        Some(quote!(<Self as #bevy_ecs_path::relationship::Relationship>::on_replace))
+        Some(quote!(<Self as #relationship_trait>::on_replace))
     } else if attrs.relationship_target.is_some() {
         if attrs.on_replace.is_some() {
             return syn::Error::new(
@@ -196,7 +215,7 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
             .into();
         }
 
-        Some(quote!(<Self as #bevy_ecs_path::relationship::RelationshipTarget>::on_replace))
+        Some(quote!(<Self as #relationship_target_trait>::on_replace))
     } else {
         attrs
             .on_replace
@@ -205,6 +224,7 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
 
     let on_despawn_path = if attrs
         .relationship_target
+        .as_ref()
         .is_some_and(|target| target.linked_spawn)
     {
         if attrs.on_despawn.is_some() {
@@ -216,7 +236,7 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
             .into();
         }
 
-        Some(quote!(<Self as #bevy_ecs_path::relationship::RelationshipTarget>::on_despawn))
+        Some(quote!(<Self as #relationship_target_trait>::on_despawn))
     } else {
         attrs
             .on_despawn
@@ -269,7 +289,23 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
         .then_some(quote! { #bevy_ecs_path::component::Immutable })
         .unwrap_or(quote! { #bevy_ecs_path::component::Mutable });
 
-    let clone_behavior = if relationship_target.is_some() || relationship.is_some() {
+    let many_to_many = attrs
+        .relationship
+        .as_ref()
+        .is_some_and(|relationship| relationship.many_to_many)
+        || attrs
+            .relationship_target
+            .as_ref()
+            .is_some_and(|relationship_target| relationship_target.many_to_many);
+    let clone_behavior = if many_to_many {
+        quote!(
+            use #bevy_ecs_path::relationship::{
+                RelationshipCloneBehaviorBase, ManyToManyCloneBehaviorViaClone, ManyToManyCloneBehaviorViaReflect,
+                ManyToManyTargetCloneBehaviorViaClone, ManyToManyTargetCloneBehaviorViaReflect
+                };
+            (&&&&&&&#bevy_ecs_path::relationship::RelationshipCloneBehaviorSpecialization::<Self>::default()).default_clone_behavior()
+        )
+    } else if relationship_target.is_some() || relationship.is_some() {
         quote!(
             use #bevy_ecs_path::relationship::{
                 RelationshipCloneBehaviorBase, RelationshipCloneBehaviorViaClone, RelationshipCloneBehaviorViaReflect,
@@ -560,11 +596,14 @@ struct Require {
 
 struct Relationship {
     relationship_target: Type,
+    many_to_many: bool,
+    fragmenting: bool,
 }
 
 struct RelationshipTarget {
     relationship: Type,
     linked_spawn: bool,
+    many_to_many: bool,
 }
 
 // values for `storage` attribute
@@ -756,14 +795,41 @@ mod kw {
     syn::custom_keyword!(relationship_target);
     syn::custom_keyword!(relationship);
     syn::custom_keyword!(linked_spawn);
+    syn::custom_keyword!(many_to_many);
+    syn::custom_keyword!(fragmenting);
 }
 
 impl Parse for Relationship {
     fn parse(input: syn::parse::ParseStream) -> Result<Self> {
-        input.parse::<kw::relationship_target>()?;
-        input.parse::<Token![=]>()?;
+        let mut relationship_target: Option<Type> = None;
+        let mut many_to_many: bool = false;
+        let mut fragmenting: bool = false;
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::many_to_many) {
+                input.parse::<kw::many_to_many>()?;
+                many_to_many = true;
+            } else if lookahead.peek(kw::fragmenting) {
+                input.parse::<kw::fragmenting>()?;
+                fragmenting = true;
+            } else if lookahead.peek(kw::relationship_target) {
+                input.parse::<kw::relationship_target>()?;
+                input.parse::<Token![=]>()?;
+                relationship_target = Some(input.parse()?);
+            } else {
+                return Err(lookahead.error());
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
         Ok(Relationship {
-            relationship_target: input.parse::<Type>()?,
+            relationship_target: relationship_target.ok_or_else(|| {
+                syn::Error::new(input.span(), "Missing `relationship_target = X` attribute")
+            })?,
+            many_to_many,
+            fragmenting,
         })
     }
 }
@@ -772,12 +838,16 @@ impl Parse for RelationshipTarget {
     fn parse(input: syn::parse::ParseStream) -> Result<Self> {
         let mut relationship: Option<Type> = None;
         let mut linked_spawn: bool = false;
+        let mut many_to_many: bool = false;
 
         while !input.is_empty() {
             let lookahead = input.lookahead1();
             if lookahead.peek(kw::linked_spawn) {
                 input.parse::<kw::linked_spawn>()?;
                 linked_spawn = true;
+            } else if lookahead.peek(kw::many_to_many) {
+                input.parse::<kw::many_to_many>()?;
+                many_to_many = true;
             } else if lookahead.peek(kw::relationship) {
                 input.parse::<kw::relationship>()?;
                 input.parse::<Token![=]>()?;
@@ -794,6 +864,7 @@ impl Parse for RelationshipTarget {
                 syn::Error::new(input.span(), "Missing `relationship = X` attribute")
             })?,
             linked_spawn,
+            many_to_many,
         })
     }
 }
@@ -828,10 +899,49 @@ fn derive_relationship(
     let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
 
     let relationship_target = &relationship.relationship_target;
+    let fragmenting = relationship.fragmenting.then(|| {
+        quote! {
+            const FRAGMENTING: bool = true;
+        }
+    });
+
+    if relationship.many_to_many {
+        let collection = &field.ty;
+        let related_entities =
+            related_entities(ast, bevy_ecs_path, &quote!(ManyToManyRelationship));
+        return Ok(Some(quote! {
+            impl #impl_generics #bevy_ecs_path::relationship::ManyToManyRelationship for #struct_name #type_generics #where_clause {
+                type RelationshipTarget = #relationship_target;
+                type Collection = #collection;
+                #fragmenting
+
+                #[inline]
+                fn collection(&self) -> &Self::Collection {
+                    &self.#relationship_member
+                }
+
+                #[inline]
+                fn collection_mut_risky(&mut self) -> &mut Self::Collection {
+                    &mut self.#relationship_member
+                }
+
+                #[inline]
+                fn from_collection_risky(collection: Self::Collection) -> Self {
+                    Self {
+                        #(#members: core::default::Default::default(),)*
+                        #relationship_member: collection
+                    }
+                }
+            }
+
+            #related_entities
+        }));
+    }
 
     Ok(Some(quote! {
         impl #impl_generics #bevy_ecs_path::relationship::Relationship for #struct_name #type_generics #where_clause {
             type RelationshipTarget = #relationship_target;
+            #fragmenting
 
             #[inline(always)]
             fn get(&self) -> #bevy_ecs_path::entity::Entity {
@@ -890,8 +1000,16 @@ fn derive_relationship_target(
     let struct_name = &ast.ident;
     let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
     let linked_spawn = relationship_target.linked_spawn;
+    let relationship_target_trait = if relationship_target.many_to_many {
+        quote!(ManyToManyRelationshipTarget)
+    } else {
+        quote!(RelationshipTarget)
+    };
+    let related_entities = relationship_target
+        .many_to_many
+        .then(|| related_entities(ast, bevy_ecs_path, &relationship_target_trait));
     Ok(Some(quote! {
-        impl #impl_generics #bevy_ecs_path::relationship::RelationshipTarget for #struct_name #type_generics #where_clause {
+        impl #impl_generics #bevy_ecs_path::relationship::#relationship_target_trait for #struct_name #type_generics #where_clause {
             const LINKED_SPAWN: bool = #linked_spawn;
             type Relationship = #relationship;
             type Collection = #collection;
@@ -914,9 +1032,36 @@ fn derive_relationship_target(
                 }
             }
         }
+
+        #related_entities
     }))
 }
 
+/// Implements `RelatedEntities` for one side of a many-to-many relationship, by forwarding
+/// to the collection of the given relationship trait.
+fn related_entities(
+    ast: &DeriveInput,
+    bevy_ecs_path: &Path,
+    relationship_trait: &TokenStream2,
+) -> TokenStream2 {
+    let struct_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #bevy_ecs_path::relationship::RelatedEntities for #struct_name #type_generics #where_clause {
+            type RelatedIter<'a> = <<Self as #bevy_ecs_path::relationship::#relationship_trait>::Collection as #bevy_ecs_path::relationship::RelationshipSourceCollection>::SourceIter<'a>
+            where
+                Self: 'a;
+
+            #[inline]
+            fn iter_related(&self) -> Self::RelatedIter<'_> {
+                #bevy_ecs_path::relationship::RelationshipSourceCollection::iter(
+                    <Self as #bevy_ecs_path::relationship::#relationship_trait>::collection(self),
+                )
+            }
+        }
+    }
+}
+
 /// Returns the field with the `#[relationship]` attribute, the only field if unnamed,
 /// or the only field in a [`Fields::Named`] with one field, otherwise `Err`.
 fn relationship_field<'a>(
@@ -25,7 +25,7 @@ use crate::{
     entity::{Entity, EntityLocation},
     event::Event,
     observer::Observers,
-    storage::{ImmutableSparseSet, SparseArray, SparseSet, TableId, TableRow},
+    storage::{ImmutableSparseSet, MemoryUsage, SparseArray, SparseSet, TableId, TableRow},
 };
 use alloc::{boxed::Box, vec::Vec};
 use bevy_platform::collections::{hash_map::Entry, HashMap};
@@ -215,6 +215,12 @@ pub struct Edges {
 }
 
 impl Edges {
+    fn memory_usage(&self) -> MemoryUsage {
+        self.insert_bundle.memory_usage()
+            + self.remove_bundle.memory_usage()
+            + self.take_bundle.memory_usage()
+    }
+
     /// Checks the cache for the target archetype when inserting a bundle into the
     /// source archetype.
     ///
@@ -640,6 +646,37 @@ impl Archetype {
         self.entities.is_empty()
     }
 
+    /// Sorts the entities of this archetype by their row in the table, so that iterating the archetype follows
+    /// the order of the table. Returns `false` if they were already sorted.
+    ///
+    /// The caller must update the archetype rows of the entity locations.
+    pub(crate) fn sort_entities_by_table_row(&mut self) -> bool {
+        if self
+            .entities
+            .is_sorted_by_key(|entity| entity.table_row.index())
+        {
+            return false;
+        }
+        self.entities
+            .sort_unstable_by_key(|entity| entity.table_row.index());
+        true
+    }
+
+    /// Shrinks the capacity of the entity list of this archetype to its length.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.entities.shrink_to_fit();
+    }
+
+    /// Returns the heap memory of the metadata of this archetype: its entity list, component set and
+    /// cached bundle transitions. The components themselves are stored in [`Table`]s and sparse sets.
+    ///
+    /// [`Table`]: crate::storage::Table
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of_vec(&self.entities)
+            + self.components.memory_usage()
+            + self.edges.memory_usage()
+    }
+
     /// Checks if the archetype contains a specific component. This runs in `O(1)` time.
     #[inline]
     pub fn contains(&self, component_id: ComponentId) -> bool {
@@ -769,6 +806,8 @@ pub struct Archetypes {
     by_components: HashMap<ArchetypeComponents, ArchetypeId>,
     /// find all the archetypes that contain a component
     pub(crate) by_component: ComponentIndex,
+    /// The number of times empty archetypes were removed, which changes the ids of archetypes and tables
+    compactions: u32,
 }
 
 /// Metadata about how a component is stored in an [`Archetype`].
@@ -788,6 +827,7 @@ impl Archetypes {
             archetypes: Vec::new(),
             by_components: Default::default(),
             by_component: Default::default(),
+            compactions: 0,
         };
         // SAFETY: Empty archetype has no components
         unsafe {
@@ -806,7 +846,10 @@ impl Archetypes {
     ///
     /// This can be used with the `Index` [`Archetypes`] implementation to
     /// iterate over newly introduced [`Archetype`]s since the last time this
-    /// function was called.
+    /// function was called, as long as [`World::compact`] didn't renumber the
+    /// archetypes in between, see [`Archetypes::compactions`].
+    ///
+    /// [`World::compact`]: crate::world::World::compact
     #[inline]
     pub fn generation(&self) -> ArchetypeGeneration {
         let id = ArchetypeId::new(self.archetypes.len());
@@ -823,6 +866,99 @@ impl Archetypes {
         self.archetypes.len()
     }
 
+    /// Returns the number of times [`World::compact`] removed empty archetypes.
+    ///
+    /// Removing archetypes changes the [`ArchetypeId`]s of the remaining archetypes and the [`TableId`]s of their
+    /// tables, so ids and [`ArchetypeGeneration`]s obtained while this returned a different value must not be used
+    /// anymore.
+    ///
+    /// [`World::compact`]: crate::world::World::compact
+    #[inline]
+    pub fn compactions(&self) -> u32 {
+        self.compactions
+    }
+
+    /// Removes every empty [`Archetype`] other than the empty archetype, and renumbers the remaining ones.
+    ///
+    /// Returns true if any archetype was removed. The cached archetype [`Edges`] are cleared, since they may refer
+    /// to removed archetypes. The entity locations and table ids are left to the caller to update.
+    pub(crate) fn remove_empty(&mut self) -> bool {
+        if !self
+            .archetypes
+            .iter()
+            .any(|archetype| archetype.id != ArchetypeId::EMPTY && archetype.is_empty())
+        {
+            return false;
+        }
+
+        let mut next_id = 0;
+        let new_ids = self
+            .archetypes
+            .iter()
+            .map(|archetype| {
+                (archetype.id == ArchetypeId::EMPTY || !archetype.is_empty()).then(|| {
+                    next_id += 1;
+                    ArchetypeId::new(next_id - 1)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        self.archetypes
+            .retain(|archetype| new_ids[archetype.id.index()].is_some());
+        for archetype in &mut self.archetypes {
+            archetype.id = new_ids[archetype.id.index()].unwrap();
+            archetype.edges = Edges::default();
+        }
+        self.by_components
+            .retain(|_, id| match new_ids[id.index()] {
+                Some(new_id) => {
+                    *id = new_id;
+                    true
+                }
+                None => false,
+            });
+        for archetypes in self.by_component.values_mut() {
+            *archetypes = archetypes
+                .drain()
+                .filter_map(|(id, record)| Some((new_ids[id.index()]?, record)))
+                .collect();
+        }
+        self.by_component
+            .retain(|_, archetypes| !archetypes.is_empty());
+        self.compactions += 1;
+        true
+    }
+
+    /// Changes the [`TableId`]s of all [`Archetype`]s to the new ids of their tables, indexed by the old ids.
+    pub(crate) fn remap_table_ids(&mut self, new_table_ids: &[Option<TableId>]) {
+        for archetype in &mut self.archetypes {
+            archetype.table_id = new_table_ids[archetype.table_id.as_usize()]
+                .expect("tables used by an archetype must not be removed");
+        }
+    }
+
+    /// Shrinks the capacity of the entity lists of all [`Archetype`]s and of the lookups between archetypes
+    /// and components.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for archetype in &mut self.archetypes {
+            archetype.shrink_to_fit();
+        }
+        self.by_components.shrink_to_fit();
+        self.by_component.shrink_to_fit();
+        for archetypes in self.by_component.values_mut() {
+            archetypes.shrink_to_fit();
+        }
+    }
+
+    /// Returns the heap memory of all [`Archetype`]s and of the lookups between archetypes and components.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of_vec(&self.archetypes)
+            + self.archetypes.iter().map(Archetype::memory_usage).sum()
+            + MemoryUsage::of_map(&self.by_components)
+            + MemoryUsage::of_map(&self.by_component)
+            + self.by_component.values().map(MemoryUsage::of_map).sum()
+    }
+
     /// Fetches an immutable reference to the archetype without any components.
     ///
     /// Shorthand for `archetypes.get(ArchetypeId::EMPTY).unwrap()`
@@ -18,7 +18,7 @@ use crate::{
     },
     entity::Entity,
     query::DebugCheckedUnwrap as _,
-    storage::{SparseSetIndex, SparseSets, Storages, Table, TableRow},
+    storage::{MemoryUsage, SparseSetIndex, SparseSets, Storages, Table, TableRow},
 };
 
 /// For a specific [`World`], this stores a unique value identifying a type of a registered [`Bundle`].
@@ -395,6 +395,26 @@ impl Bundles {
         self.len() == 0
     }
 
+    /// Returns the heap memory of all registered bundles and of the caches used to look them up.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let infos = self
+            .bundle_infos
+            .iter()
+            .map(|info| {
+                MemoryUsage::of_slice(&info.contributed_component_ids)
+                    + MemoryUsage::of_slice(&info.required_component_constructors)
+            })
+            .sum::<MemoryUsage>();
+        MemoryUsage::of_vec(&self.bundle_infos)
+            + infos
+            + MemoryUsage::of_map(&self.bundle_ids)
+            + MemoryUsage::of_map(&self.contributed_bundle_ids)
+            + MemoryUsage::of_map(&self.dynamic_bundle_ids)
+            + MemoryUsage::of_map(&self.dynamic_bundle_storages)
+            + MemoryUsage::of_map(&self.dynamic_component_bundle_ids)
+            + MemoryUsage::of_map(&self.dynamic_component_storages)
+    }
+
     /// Iterate over [`BundleInfo`].
     pub fn iter(&self) -> impl Iterator<Item = &BundleInfo> {
         self.bundle_infos.iter()
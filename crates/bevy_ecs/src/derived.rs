@@ -0,0 +1,320 @@
+//! Provides derived components, whose value is computed from other components of the same entity.
+//!
+//! A derived component is registered with [`World::register_derived_component`], along with the
+//! components it is derived from and a function that computes it from them. The derived component is then:
+//! - inserted, updated or removed right after one of its inputs is inserted, replaced or removed, by observers.
+//! - updated when one of its inputs is mutated, by a system in the [`DeriveComponents`] schedule that uses
+//!   [`Changed`] filters. With `bevy_app`, this schedule runs after `PreUpdate`, `Update` and `PostUpdate`.
+//!
+//! Derived components that are inputs of other derived components are updated first, so derived components
+//! don't need any manual ordering. Entities disabled with [`Disabled`] are skipped, and updated when they
+//! are enabled again.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_ecs::derived::DeriveComponents;
+//! #[derive(Component)]
+//! struct Position(f32);
+//!
+//! #[derive(Component)]
+//! struct Radius(f32);
+//!
+//! #[derive(Component, PartialEq, Debug)]
+//! struct Bounds { min: f32, max: f32 }
+//!
+//! fn bounds(position: &Position, radius: &Radius) -> Bounds {
+//!     Bounds { min: position.0 - radius.0, max: position.0 + radius.0 }
+//! }
+//!
+//! let mut world = World::new();
+//! world.register_derived_component::<Bounds, (Position, Radius)>(bounds);
+//!
+//! let ball = world.spawn((Position(0.0), Radius(1.0))).id();
+//! assert_eq!(world.get::<Bounds>(ball), Some(&Bounds { min: -1.0, max: 1.0 }));
+//!
+//! world.get_mut::<Position>(ball).unwrap().0 = 5.0;
+//! world.run_schedule(DeriveComponents);
+//! assert_eq!(world.get::<Bounds>(ball), Some(&Bounds { min: 4.0, max: 6.0 }));
+//!
+//! world.entity_mut(ball).remove::<Radius>();
+//! assert!(world.get::<Bounds>(ball).is_none());
+//! ```
+//!
+//! [`Changed`]: crate::query::Changed
+
+use alloc::sync::Arc;
+use core::any::TypeId;
+
+use variadics_please::all_tuples;
+
+use crate::{
+    change_detection::DetectChangesMut,
+    component::{Component, Mutable},
+    entity::Entity,
+    entity_disabling::{DefaultQueryFilters, Disabled},
+    lifecycle::{Insert, Remove},
+    observer::On,
+    prelude::{IntoScheduleConfigs, SystemSet},
+    query::{Changed, Or, QueryFilter, QueryItem, ReadOnlyQueryData},
+    schedule::{ScheduleConfigs, ScheduleLabel, Schedules},
+    system::{Commands, Query, ScheduleSystem},
+    world::{EntityRef, World},
+};
+
+/// The schedule that updates derived components whose inputs were mutated.
+///
+/// With `bevy_app`, this schedule runs after `PreUpdate`, `Update` and `PostUpdate`.
+/// See the [module-level documentation](self) for more details.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct DeriveComponents;
+
+/// The system set of the system in [`DeriveComponents`] that updates the derived component with the given [`TypeId`].
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+struct DerivedComponentSystems(TypeId);
+
+impl DerivedComponentSystems {
+    fn of<C: Component>() -> Self {
+        Self(TypeId::of::<C>())
+    }
+}
+
+/// The components that a derived component is computed from, as a tuple of [`Component`]s.
+pub trait DerivedInputs: Send + Sync + 'static {
+    /// The [`QueryData`](crate::query::QueryData) that reads the inputs.
+    type Data: ReadOnlyQueryData;
+    /// A [`QueryFilter`] that matches entities where one of the inputs changed.
+    type Changed: QueryFilter;
+    /// References to the inputs.
+    type Refs<'a>;
+
+    /// Converts the query item of [`Self::Data`] to references to the inputs.
+    fn from_item<'w, 's>(item: QueryItem<'w, 's, Self::Data>) -> Self::Refs<'w>;
+
+    /// Returns the inputs of an entity, or `None` if it doesn't have all of them.
+    fn get<'a>(entity: &EntityRef<'a>) -> Option<Self::Refs<'a>>;
+
+    /// Adds the observers that update `Out` when one of the inputs is inserted, replaced or removed.
+    fn add_observers<Out: DerivedComponent, F: DeriveFn<Out, Self>>(
+        world: &mut World,
+        derive: &Arc<F>,
+    ) where
+        Self: Sized;
+
+    /// Orders the system that updates a derived component after the systems that update its inputs.
+    fn order(system: ScheduleConfigs<ScheduleSystem>) -> ScheduleConfigs<ScheduleSystem>;
+}
+
+/// A [`Component`] that can be derived from other components with [`World::register_derived_component`].
+///
+/// Derived components are compared to their previous value before being updated, so that their change
+/// detection only triggers when their value actually changes.
+pub trait DerivedComponent: Component<Mutability = Mutable> + PartialEq {}
+
+impl<C: Component<Mutability = Mutable> + PartialEq> DerivedComponent for C {}
+
+/// A function that computes the derived component `Out` from references to the components `Ins`.
+///
+/// This is implemented for functions and closures like `Fn(&A, &B) -> Out`.
+pub trait DeriveFn<Out, Ins: DerivedInputs>: Send + Sync + 'static {
+    /// Computes the derived component.
+    fn derive(&self, inputs: Ins::Refs<'_>) -> Out;
+}
+
+macro_rules! impl_derived_inputs {
+    ($(#[$meta:meta])* $(($I:ident, $i:ident)),*) => {
+        $(#[$meta])*
+        impl<$($I: Component),*> DerivedInputs for ($($I,)*) {
+            type Data = ($(&'static $I,)*);
+            type Changed = Or<($(Changed<$I>,)*)>;
+            type Refs<'a> = ($(&'a $I,)*);
+
+            fn from_item<'w, 's>(item: QueryItem<'w, 's, Self::Data>) -> Self::Refs<'w> {
+                item
+            }
+
+            fn get<'a>(entity: &EntityRef<'a>) -> Option<Self::Refs<'a>> {
+                Some(($(entity.get::<$I>()?,)*))
+            }
+
+            fn add_observers<Out: DerivedComponent, F: DeriveFn<Out, Self>>(
+                world: &mut World,
+                derive: &Arc<F>,
+            ) {
+                $(
+                    let on_insert = derive.clone();
+                    world.add_observer(move |trigger: On<Insert, $I>, mut commands: Commands| {
+                        queue_update::<Out, Self, F>(&mut commands, trigger.target(), &on_insert);
+                    });
+                    let on_remove = derive.clone();
+                    world.add_observer(move |trigger: On<Remove, $I>, mut commands: Commands| {
+                        queue_update::<Out, Self, F>(&mut commands, trigger.target(), &on_remove);
+                    });
+                )*
+            }
+
+            fn order(system: ScheduleConfigs<ScheduleSystem>) -> ScheduleConfigs<ScheduleSystem> {
+                system $(.after(DerivedComponentSystems::of::<$I>()))*
+            }
+        }
+
+        $(#[$meta])*
+        impl<Func, Out, $($I: Component),*> DeriveFn<Out, ($($I,)*)> for Func
+        where
+            Func: Fn($(&$I),*) -> Out + Send + Sync + 'static,
+        {
+            fn derive(&self, ($($i,)*): ($(&$I,)*)) -> Out {
+                self($($i),*)
+            }
+        }
+    };
+}
+
+all_tuples!(
+    #[doc(fake_variadic)]
+    impl_derived_inputs,
+    1,
+    8,
+    I,
+    i
+);
+
+impl World {
+    /// Registers `Out` as a component derived from the components `Ins`, computed with `derive`.
+    ///
+    /// `Out` is kept up to date with its inputs on every entity that has all of them, and removed from entities
+    /// that lose one of them. It is updated immediately when an input is inserted, replaced or removed, and when
+    /// the [`DeriveComponents`] schedule runs after an input was mutated.
+    /// See the [module-level documentation](crate::derived) for more details.
+    pub fn register_derived_component<Out: DerivedComponent, Ins: DerivedInputs>(
+        &mut self,
+        derive: impl DeriveFn<Out, Ins>,
+    ) -> &mut Self {
+        let derive = Arc::new(derive);
+        Ins::add_observers::<Out, _>(self, &derive);
+        let on_enable = derive.clone();
+        self.add_observer(
+            move |trigger: On<Remove, Disabled>, mut commands: Commands| {
+                queue_update::<Out, Ins, _>(&mut commands, trigger.target(), &on_enable);
+            },
+        );
+
+        let system = derived_component_system::<Out, Ins, _>(derive);
+        self.get_resource_or_init::<Schedules>().add_systems(
+            DeriveComponents,
+            Ins::order(system.in_set(DerivedComponentSystems::of::<Out>())),
+        );
+        self
+    }
+}
+
+fn derived_component_system<Out: DerivedComponent, Ins: DerivedInputs, F: DeriveFn<Out, Ins>>(
+    derive: Arc<F>,
+) -> impl FnMut(Commands, Query<(Entity, Ins::Data, Option<&mut Out>), Ins::Changed>) {
+    move |mut commands, mut query| {
+        for (entity, inputs, derived) in &mut query {
+            let value = derive.derive(Ins::from_item(inputs));
+            match derived {
+                Some(mut derived) => {
+                    derived.set_if_neq(value);
+                }
+                None => {
+                    commands.entity(entity).insert(value);
+                }
+            }
+        }
+    }
+}
+
+/// Updates `Out` on `entity` once the current structural change is applied.
+fn queue_update<Out: DerivedComponent, Ins: DerivedInputs, F: DeriveFn<Out, Ins>>(
+    commands: &mut Commands,
+    entity: Entity,
+    derive: &Arc<F>,
+) {
+    let derive = derive.clone();
+    commands.queue(move |world: &mut World| {
+        let Ok(entity_ref) = world.get_entity(entity) else {
+            return;
+        };
+        if let Some(filters) = world.get_resource::<DefaultQueryFilters>() {
+            if filters.disabling_ids().any(|id| entity_ref.contains_id(id)) {
+                return;
+            }
+        }
+        let value = Ins::get(&entity_ref).map(|inputs| derive.derive(inputs));
+        let mut entity = world.entity_mut(entity);
+        match value {
+            // Inserting, rather than mutating, triggers the observers of components derived from `Out`.
+            Some(value) if entity.get::<Out>() != Some(&value) => {
+                entity.insert(value);
+            }
+            Some(_) => {}
+            None => {
+                entity.remove::<Out>();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        component::Component, derived::DeriveComponents, entity_disabling::Disabled, world::World,
+    };
+
+    #[derive(Component)]
+    struct A(u32);
+
+    #[derive(Component)]
+    struct B(u32);
+
+    #[derive(Component, PartialEq, Debug)]
+    struct Sum(u32);
+
+    #[derive(Component, PartialEq, Debug)]
+    struct Double(u32);
+
+    #[test]
+    fn derived_components() {
+        let mut world = World::new();
+        // Registered before its input, to check that derived inputs are updated first.
+        world.register_derived_component::<Double, (Sum,)>(|sum: &Sum| Double(sum.0 * 2));
+        world.register_derived_component::<Sum, (A, B)>(|a: &A, b: &B| Sum(a.0 + b.0));
+
+        let entity = world.spawn((A(1), B(2))).id();
+        let incomplete = world.spawn(A(1)).id();
+        assert_eq!(world.get::<Sum>(entity), Some(&Sum(3)));
+        assert_eq!(world.get::<Double>(entity), Some(&Double(6)));
+        assert!(world.get::<Sum>(incomplete).is_none());
+
+        world.get_mut::<A>(entity).unwrap().0 = 10;
+        world.run_schedule(DeriveComponents);
+        assert_eq!(world.get::<Sum>(entity), Some(&Sum(12)));
+        assert_eq!(world.get::<Double>(entity), Some(&Double(24)));
+
+        world.entity_mut(entity).insert(B(0));
+        assert_eq!(world.get::<Sum>(entity), Some(&Sum(10)));
+        assert_eq!(world.get::<Double>(entity), Some(&Double(20)));
+
+        world.entity_mut(entity).remove::<B>();
+        assert!(world.get::<Sum>(entity).is_none());
+        assert!(world.get::<Double>(entity).is_none());
+    }
+
+    #[test]
+    fn derived_components_skip_disabled_entities() {
+        let mut world = World::new();
+        world.register_derived_component::<Sum, (A, B)>(|a: &A, b: &B| Sum(a.0 + b.0));
+        let entity = world.spawn((A(1), B(2))).id();
+        world.run_schedule(DeriveComponents);
+
+        world.entity_mut(entity).insert(Disabled);
+        world.get_mut::<A>(entity).unwrap().0 = 10;
+        world.run_schedule(DeriveComponents);
+        world.entity_mut(entity).insert(B(5));
+        assert_eq!(world.get::<Sum>(entity), Some(&Sum(3)));
+
+        world.entity_mut(entity).remove::<Disabled>();
+        assert_eq!(world.get::<Sum>(entity), Some(&Sum(15)));
+    }
+}
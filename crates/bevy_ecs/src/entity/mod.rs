@@ -77,11 +77,12 @@ use crate::{
     archetype::{ArchetypeId, ArchetypeRow},
     change_detection::MaybeLocation,
     component::{CheckChangeTicks, Tick},
-    storage::{SparseSetIndex, TableId, TableRow},
+    storage::{MemoryUsage, SparseSetIndex, TableId, TableRow},
 };
 use alloc::vec::Vec;
 use bevy_platform::sync::atomic::Ordering;
 use core::{fmt, hash::Hash, mem, num::NonZero, panic::Location};
+use fixedbitset::FixedBitSet;
 use log::warn;
 
 #[cfg(feature = "serialize")]
@@ -921,6 +922,105 @@ impl Entities {
         }
     }
 
+    /// Copies the allocator state, so that the same entity IDs can be restored later
+    /// with [`Entities::restore_snapshot`].
+    ///
+    /// Must not be called while reserved entities are awaiting `flush()`.
+    pub(crate) fn snapshot(&mut self) -> EntitiesSnapshot {
+        self.verify_flushed();
+        let mut alive = FixedBitSet::with_capacity(self.meta.len());
+        for (index, meta) in self.meta.iter().enumerate() {
+            alive.set(index, meta.location.is_some());
+        }
+        EntitiesSnapshot {
+            generations: self.meta.iter().map(|meta| meta.generation).collect(),
+            alive,
+            pending: self.pending.clone(),
+        }
+    }
+
+    /// Restores the allocator state from an [`EntitiesSnapshot`], so that the same entity IDs
+    /// are allocated again.
+    ///
+    /// Entities that currently have a location are kept, whether or not they were alive in `snapshot`,
+    /// and their IDs are not handed out again. An entity that was alive in `snapshot` can't be restored
+    /// if a kept entity has taken its index since; it's skipped with a warning.
+    ///
+    /// Returns the entities that were alive in `snapshot` but don't currently have a location,
+    /// except for the ones in `skip`, which stay free: these are allocated, and must be given a
+    /// location by the caller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserved entities are awaiting `flush()`.
+    pub(crate) fn restore_snapshot(
+        &mut self,
+        snapshot: &EntitiesSnapshot,
+        skip: &EntityHashSet,
+    ) -> Vec<Entity> {
+        assert!(
+            !self.needs_flush(),
+            "flush() needs to be called before restoring an entities snapshot"
+        );
+        let snapshot_len = snapshot.generations.len();
+        // Keep the indices of kept entities allocated after the snapshot, and forget the free ones after them.
+        let len = self
+            .meta
+            .iter()
+            .rposition(|meta| meta.location.is_some())
+            .map_or(0, |index| index + 1)
+            .max(snapshot_len);
+        self.meta.resize(len, EntityMeta::EMPTY);
+
+        let mut unlocated = Vec::new();
+        let mut kept = FixedBitSet::with_capacity(len);
+        let mut free = Vec::new();
+        for (index, meta) in self.meta.iter_mut().enumerate() {
+            // SAFETY: the index is less than the meta length, which can not exceeded u32::MAX
+            let row = EntityRow::new(unsafe { NonMaxU32::new_unchecked(index as u32) });
+            let saved = snapshot.generations.get(index).copied();
+            if meta.location.is_some() {
+                kept.insert(index);
+                if snapshot.alive.contains(index) && saved != Some(meta.generation) {
+                    warn!(
+                        "{} can't be restored, {} has taken its place",
+                        Entity::from_raw_and_generation(row, saved.unwrap()),
+                        Entity::from_raw_and_generation(row, meta.generation),
+                    );
+                }
+                continue;
+            }
+            match saved {
+                Some(generation)
+                    if snapshot.alive.contains(index)
+                        && skip.contains(&Entity::from_raw_and_generation(row, generation)) =>
+                {
+                    // Freed since the snapshot, so it keeps its newer generation.
+                    free.push(row);
+                }
+                Some(generation) => {
+                    meta.generation = generation;
+                    if snapshot.alive.contains(index) {
+                        unlocated.push(Entity::from_raw_and_generation(row, generation));
+                    }
+                }
+                // Allocated after the snapshot and freed since, so it keeps its generation.
+                None => free.push(row),
+            }
+        }
+
+        // The free list is popped from the back, so the entries of the snapshot are reused first.
+        self.pending = free;
+        self.pending.extend(
+            snapshot
+                .pending
+                .iter()
+                .filter(|row| !kept.contains(row.index() as usize)),
+        );
+        *self.free_cursor.get_mut() = self.pending.len() as IdCursor;
+        unlocated
+    }
+
     /// Returns true if the [`Entities`] contains [`entity`](Entity).
     // This will return false for entities which have been freed, even if
     // not reallocated since the generation is incremented in `free`
@@ -1131,6 +1231,11 @@ impl Entities {
         self.len() == 0
     }
 
+    /// Returns the heap memory of the metadata of all entities, including despawned entities whose ids can be reused.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of_vec(&self.meta) + MemoryUsage::of_vec(&self.pending)
+    }
+
     /// Try to get the source code location from which this entity has last been
     /// spawned, despawned or flushed.
     ///
@@ -1278,6 +1383,22 @@ impl EntityMeta {
     };
 }
 
+/// A copy of the allocator state of [`Entities`], created by [`Entities::snapshot`].
+#[derive(Clone, Debug)]
+pub(crate) struct EntitiesSnapshot {
+    generations: Vec<EntityGeneration>,
+    alive: FixedBitSet,
+    pending: Vec<EntityRow>,
+}
+
+impl EntitiesSnapshot {
+    /// Returns true if `entity` was alive when the snapshot was taken.
+    pub(crate) fn contains(&self, entity: Entity) -> bool {
+        let index = entity.index() as usize;
+        self.alive.contains(index) && self.generations[index] == entity.generation()
+    }
+}
+
 /// A location of an entity in an archetype.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct EntityLocation {
@@ -0,0 +1,349 @@
+//! Provides value indexes for [immutable](crate::component::Immutable) components, allowing
+//! entities to be looked up by the value of a component without scanning a [`Query`].
+//!
+//! An index is opted into with [`World::register_component_index`], and is kept up to date
+//! by the `on_insert` and `on_replace` [hooks](crate::lifecycle::ComponentHooks) of the component.
+//! Since indexed components are immutable, these hooks observe every change to their values.
+//!
+//! Indexes are queried with the [`QueryByIndex`] system parameter, or read directly
+//! through the [`ComponentValueIndex`] resource.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_ecs::{index::QueryByIndex, system::RunSystemOnce};
+//! #[derive(Component, Clone, PartialEq, Eq, Hash)]
+//! #[component(immutable)]
+//! struct NetworkId(u64);
+//!
+//! #[derive(Component)]
+//! struct Health(u32);
+//!
+//! fn damage_player(mut players: QueryByIndex<NetworkId, &mut Health>) {
+//!     if let Ok(mut health) = players.single_mut(&NetworkId(42)) {
+//!         health.0 -= 1;
+//!     }
+//! }
+//!
+//! let mut world = World::new();
+//! world.register_component_index::<NetworkId>();
+//! world.spawn((NetworkId(42), Health(10)));
+//! world.run_system_once(damage_player).unwrap();
+//! ```
+
+use crate::{
+    component::{Component, Immutable},
+    entity::{hash_set::EntityHashSet, Entity},
+    lifecycle::HookContext,
+    query::{QueryData, QueryFilter, QueryItem, QuerySingleError, ROQueryItem, With},
+    resource::Resource,
+    system::{Query, Res, SystemParam},
+    world::{DeferredWorld, World},
+};
+use bevy_platform::collections::HashMap;
+use bevy_utils::prelude::DebugName;
+use core::hash::Hash;
+
+/// A [`Component`] that can be indexed by value with [`World::register_component_index`].
+///
+/// This is implemented for every [immutable](crate::component::Immutable) component that
+/// can be cloned, hashed and compared.
+pub trait IndexableComponent: Component<Mutability = Immutable> + Clone + Eq + Hash {}
+
+impl<C: Component<Mutability = Immutable> + Clone + Eq + Hash> IndexableComponent for C {}
+
+/// A [`Resource`] mapping each value of the component `C` to the entities that have it.
+///
+/// This is created by [`World::register_component_index`], and kept up to date by
+/// the hooks of `C`.
+#[derive(Resource)]
+pub struct ComponentValueIndex<C: IndexableComponent> {
+    entities: HashMap<C, EntityHashSet>,
+}
+
+impl<C: IndexableComponent> Default for ComponentValueIndex<C> {
+    fn default() -> Self {
+        Self {
+            entities: HashMap::default(),
+        }
+    }
+}
+
+impl<C: IndexableComponent> ComponentValueIndex<C> {
+    /// Returns the set of entities whose `C` component is equal to `value`, if there are any.
+    pub fn get(&self, value: &C) -> Option<&EntityHashSet> {
+        self.entities.get(value)
+    }
+
+    /// Returns an iterator over the entities whose `C` component is equal to `value`.
+    pub fn entities(&self, value: &C) -> impl Iterator<Item = Entity> + '_ {
+        self.get(value).into_iter().flatten().copied()
+    }
+
+    /// Returns `true` if any entity has a `C` component equal to `value`.
+    pub fn contains(&self, value: &C) -> bool {
+        self.entities.contains_key(value)
+    }
+
+    /// Returns an iterator over every distinct value of `C`, along with the entities that have it.
+    pub fn iter(&self) -> impl Iterator<Item = (&C, &EntityHashSet)> {
+        self.entities.iter()
+    }
+
+    /// Returns an iterator over every distinct value of `C`.
+    pub fn values(&self) -> impl Iterator<Item = &C> {
+        self.entities.keys()
+    }
+
+    /// Returns the number of distinct values of `C`.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if no entity has a `C` component.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn on_insert(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+        let Some(value) = world.get::<C>(entity).cloned() else {
+            return;
+        };
+        if let Some(mut index) = world.get_resource_mut::<Self>() {
+            index.entities.entry(value).or_default().insert(entity);
+        }
+    }
+
+    fn on_replace(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+        let Some(value) = world.get::<C>(entity).cloned() else {
+            return;
+        };
+        let Some(mut index) = world.get_resource_mut::<Self>() else {
+            return;
+        };
+        if let Some(entities) = index.entities.get_mut(&value) {
+            entities.remove(&entity);
+            if entities.is_empty() {
+                index.entities.remove(&value);
+            }
+        }
+    }
+}
+
+impl World {
+    /// Creates a [`ComponentValueIndex`] for the component `C`, allowing entities to be looked up
+    /// by the value of `C` with [`QueryByIndex`].
+    ///
+    /// The index is maintained by the `on_insert` and `on_replace` hooks of `C`.
+    /// Calling this again once the index exists does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` already defines its own `on_insert` or `on_replace` hook, or if the
+    /// hooks of `C` can't be modified because it has already been added to an entity.
+    pub fn register_component_index<C: IndexableComponent>(&mut self) -> &mut Self {
+        if self.contains_resource::<ComponentValueIndex<C>>() {
+            return self;
+        }
+        let hooks = self.register_component_hooks::<C>();
+        if hooks
+            .try_on_insert(ComponentValueIndex::<C>::on_insert)
+            .is_none()
+            || hooks
+                .try_on_replace(ComponentValueIndex::<C>::on_replace)
+                .is_none()
+        {
+            panic!(
+                "Component {} can't be indexed because it already has an `on_insert` or `on_replace` hook",
+                DebugName::type_name::<C>()
+            );
+        }
+        self.init_resource::<ComponentValueIndex<C>>();
+        self
+    }
+}
+
+/// A [`SystemParam`] that looks up the entities matching a [`Query`] by the value of their
+/// indexed component `C`.
+///
+/// `C` must have been indexed with [`World::register_component_index`].
+/// Only entities that match `D` and `F` are returned.
+#[derive(SystemParam)]
+pub struct QueryByIndex<
+    'w,
+    's,
+    C: IndexableComponent,
+    D: QueryData + 'static,
+    F: QueryFilter + 'static = (),
+> {
+    #[system_param(validation_message = "Component index not registered")]
+    index: Res<'w, ComponentValueIndex<C>>,
+    query: Query<'w, 's, D, (F, With<C>)>,
+}
+
+impl<'w, 's, C: IndexableComponent, D: QueryData, F: QueryFilter> QueryByIndex<'w, 's, C, D, F> {
+    /// Returns the underlying [`ComponentValueIndex`].
+    pub fn index(&self) -> &ComponentValueIndex<C> {
+        &self.index
+    }
+
+    /// Returns the underlying [`Query`], over every entity with a `C` component.
+    pub fn query(&self) -> &Query<'w, 's, D, (F, With<C>)> {
+        &self.query
+    }
+
+    /// Returns `true` if an entity matching the query has a `C` component equal to `value`.
+    pub fn contains(&self, value: &C) -> bool {
+        self.iter(value).next().is_some()
+    }
+
+    /// Returns an iterator over the read-only query items of the entities whose `C` component
+    /// is equal to `value`.
+    pub fn iter(&self, value: &C) -> impl Iterator<Item = ROQueryItem<'_, 's, D>> {
+        self.index
+            .get(value)
+            .map(|entities| self.query.iter_many_unique(entities))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns an iterator over the query items of the entities whose `C` component
+    /// is equal to `value`.
+    pub fn iter_mut(&mut self, value: &C) -> impl Iterator<Item = QueryItem<'_, 's, D>> {
+        self.index
+            .get(value)
+            .map(|entities| self.query.iter_many_unique_mut(entities))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns the read-only query item of the only entity whose `C` component is equal to `value`.
+    ///
+    /// If the number of matching entities is not exactly one, a [`QuerySingleError`] is returned instead.
+    pub fn single(&self, value: &C) -> Result<ROQueryItem<'_, 's, D>, QuerySingleError> {
+        Self::only(self.iter(value))
+    }
+
+    /// Returns the query item of the only entity whose `C` component is equal to `value`.
+    ///
+    /// If the number of matching entities is not exactly one, a [`QuerySingleError`] is returned instead.
+    pub fn single_mut(&mut self, value: &C) -> Result<QueryItem<'_, 's, D>, QuerySingleError> {
+        Self::only(self.iter_mut(value))
+    }
+
+    /// Returns an iterator over every distinct value of `C`, along with the read-only query items
+    /// of the entities that share it.
+    ///
+    /// Values without any entity matching the query are skipped.
+    pub fn iter_groups(
+        &self,
+    ) -> impl Iterator<Item = (&C, impl Iterator<Item = ROQueryItem<'_, 's, D>>)> {
+        self.index
+            .iter()
+            .map(|(value, entities)| (value, self.query.iter_many_unique(entities).peekable()))
+            .filter_map(|(value, mut items)| items.peek().is_some().then_some((value, items)))
+    }
+
+    fn only<T>(mut items: impl Iterator<Item = T>) -> Result<T, QuerySingleError> {
+        match (items.next(), items.next()) {
+            (Some(item), None) => Ok(item),
+            (None, _) => Err(QuerySingleError::NoEntities(DebugName::type_name::<Self>())),
+            (Some(_), _) => Err(QuerySingleError::MultipleEntities(DebugName::type_name::<
+                Self,
+            >())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entity_disabling::Disabled,
+        system::{RunSystemOnce, SystemState},
+    };
+    use alloc::vec::Vec;
+
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    #[component(immutable)]
+    struct Cell(i32, i32);
+
+    #[derive(Component, PartialEq, Debug)]
+    struct Value(u32);
+
+    #[test]
+    fn index_tracks_inserts_replacements_and_removals() {
+        let mut world = World::new();
+        world.register_component_index::<Cell>();
+
+        let a = world.spawn(Cell(0, 0)).id();
+        let b = world.spawn(Cell(0, 0)).id();
+        let c = world.spawn(Cell(1, 0)).id();
+
+        let index = world.resource::<ComponentValueIndex<Cell>>();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(&Cell(0, 0)).unwrap().len(), 2);
+        assert_eq!(index.entities(&Cell(1, 0)).collect::<Vec<_>>(), [c]);
+
+        world.entity_mut(a).insert(Cell(1, 0));
+        world.entity_mut(b).remove::<Cell>();
+        world.despawn(c);
+
+        let index = world.resource::<ComponentValueIndex<Cell>>();
+        assert!(!index.contains(&Cell(0, 0)));
+        assert_eq!(index.entities(&Cell(1, 0)).collect::<Vec<_>>(), [a]);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn query_by_index() {
+        let mut world = World::new();
+        world.register_component_index::<Cell>();
+        world.spawn((Cell(0, 0), Value(1)));
+        world.spawn((Cell(0, 0), Value(2)));
+        world.spawn((Cell(0, 0), Value(3), Disabled));
+        world.spawn((Cell(1, 0), Value(4)));
+        world.spawn(Cell(2, 0));
+
+        let mut state = SystemState::<QueryByIndex<Cell, &mut Value>>::new(&mut world);
+        let mut query = state.get_mut(&mut world);
+        let mut values: Vec<_> = query.iter(&Cell(0, 0)).map(|value| value.0).collect();
+        values.sort();
+        assert_eq!(values, [1, 2]);
+        assert!(query.single(&Cell(0, 0)).is_err());
+        assert!(query.single(&Cell(2, 0)).is_err());
+        assert!(!query.contains(&Cell(2, 0)));
+        assert!(!query.contains(&Cell(3, 0)));
+
+        query.single_mut(&Cell(1, 0)).unwrap().0 = 5;
+        for mut value in query.iter_mut(&Cell(0, 0)) {
+            value.0 *= 10;
+        }
+        assert_eq!(query.iter_groups().count(), 2);
+
+        let mut values: Vec<_> = world
+            .query::<&Value>()
+            .iter(&world)
+            .map(|value| value.0)
+            .collect();
+        values.sort();
+        assert_eq!(values, [5, 10, 20]);
+    }
+
+    #[test]
+    fn query_by_index_requires_registration() {
+        let mut world = World::new();
+        world.spawn(Cell(0, 0));
+        let result = world.run_system_once(|_: QueryByIndex<Cell, Entity>| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Components hooks cannot be modified if the component already exists in an archetype"
+    )]
+    fn index_requires_unused_component() {
+        let mut world = World::new();
+        world.spawn(Cell(0, 0));
+        world.register_component_index::<Cell>();
+    }
+}
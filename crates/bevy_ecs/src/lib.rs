@@ -34,11 +34,13 @@ pub mod batching;
 pub mod bundle;
 pub mod change_detection;
 pub mod component;
+pub mod derived;
 pub mod entity;
 pub mod entity_disabling;
 pub mod error;
 pub mod event;
 pub mod hierarchy;
+pub mod index;
 pub mod intern;
 pub mod label;
 pub mod lifecycle;
@@ -50,6 +52,7 @@ pub mod query;
 pub mod reflect;
 pub mod relationship;
 pub mod resource;
+pub mod rollback;
 pub mod schedule;
 pub mod spawn;
 pub mod storage;
@@ -117,7 +120,8 @@ pub mod prelude {
     #[doc(hidden)]
     #[cfg(feature = "bevy_reflect")]
     pub use crate::reflect::{
-        AppTypeRegistry, ReflectComponent, ReflectFromWorld, ReflectResource,
+        AppTypeRegistry, ReflectBufferedEvent, ReflectComponent, ReflectEntityEvent, ReflectEvent,
+        ReflectFromWorld, ReflectResource,
     };
 
     #[doc(hidden)]
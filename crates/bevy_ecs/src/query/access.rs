@@ -928,6 +928,9 @@ pub struct FilteredAccess {
     // An array of filter sets to express `With` or `Without` clauses in disjunctive normal form, for example: `Or<(With<A>, With<B>)>`.
     // Filters like `(With<A>, Or<(With<B>, Without<C>)>` are expanded into `Or<((With<A>, With<B>), (With<A>, Without<C>))>`.
     pub(crate) filter_sets: Vec<AccessFilters>,
+    // The part of `access` that applies to entities other than the filtered ones, like the targets of relationships.
+    // Conflicts on this access can't be ruled out by the `filter_sets`.
+    pub(crate) related_access: Access,
 }
 
 // This is needed since `#[derive(Clone)]` does not generate optimized `clone_from`.
@@ -937,6 +940,7 @@ impl Clone for FilteredAccess {
             access: self.access.clone(),
             required: self.required.clone(),
             filter_sets: self.filter_sets.clone(),
+            related_access: self.related_access.clone(),
         }
     }
 
@@ -944,6 +948,7 @@ impl Clone for FilteredAccess {
         self.access.clone_from(&source.access);
         self.required.clone_from(&source.required);
         self.filter_sets.clone_from(&source.filter_sets);
+        self.related_access.clone_from(&source.related_access);
     }
 }
 
@@ -1031,6 +1036,7 @@ impl FilteredAccess {
             access: Access::default(),
             required: FixedBitSet::default(),
             filter_sets: vec![AccessFilters::default()],
+            related_access: Access::default(),
         }
     }
 
@@ -1041,6 +1047,7 @@ impl FilteredAccess {
             access: Access::default(),
             required: FixedBitSet::default(),
             filter_sets: Vec::new(),
+            related_access: Access::default(),
         }
     }
 
@@ -1080,6 +1087,24 @@ impl FilteredAccess {
         self.access.add_resource_write(index);
     }
 
+    /// Returns a reference to the access to entities other than the ones matched by the filters,
+    /// like the targets of relationships.
+    ///
+    /// This is a subset of [`FilteredAccess::access`].
+    #[inline]
+    pub fn related_access(&self) -> &Access {
+        &self.related_access
+    }
+
+    /// Adds access to entities other than the ones matched by the filters, like the targets of relationships.
+    ///
+    /// Since the filters don't apply to these entities, this access can't be ruled out by them
+    /// when checking whether two [`FilteredAccess`] are compatible.
+    pub fn add_related_access(&mut self, access: &Access) {
+        self.access.extend(access);
+        self.related_access.extend(access);
+    }
+
     fn add_required(&mut self, index: ComponentId) {
         self.required.grow_and_insert(index.index());
     }
@@ -1116,6 +1141,7 @@ impl FilteredAccess {
     /// Adds all of the accesses from `other` to `self`.
     pub fn extend_access(&mut self, other: &FilteredAccess) {
         self.access.extend(&other.access);
+        self.related_access.extend(&other.related_access);
     }
 
     /// Returns `true` if this and `other` can be active at the same time.
@@ -1130,6 +1156,13 @@ impl FilteredAccess {
             return true;
         }
 
+        // Access to related entities isn't restricted by the filters either.
+        if !self.related_access.is_components_compatible(&other.access)
+            || !other.related_access.is_components_compatible(&self.access)
+        {
+            return false;
+        }
+
         // If the access instances are incompatible, we want to check that whether filters can
         // guarantee that queries are disjoint.
         // Since the `filter_sets` array represents a Disjunctive Normal Form formula ("ORs of ANDs"),
@@ -1163,6 +1196,7 @@ impl FilteredAccess {
     pub fn extend(&mut self, other: &FilteredAccess) {
         self.access.extend(&other.access);
         self.required.union_with(&other.required);
+        self.related_access.extend(&other.related_access);
 
         // We can avoid allocating a new array of bitsets if `other` contains just a single set of filters:
         // in this case we can short-circuit by performing an in-place union for each bitset.
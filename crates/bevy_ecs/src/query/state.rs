@@ -67,6 +67,9 @@ pub(super) union StorageId {
 pub struct QueryState<D: QueryData, F: QueryFilter = ()> {
     world_id: WorldId,
     pub(crate) archetype_generation: ArchetypeGeneration,
+    /// The [`Archetypes::compactions`](crate::archetype::Archetypes::compactions) of the world when the matched
+    /// archetypes and tables were last updated.
+    compactions: u32,
     /// Metadata about the [`Table`](crate::storage::Table)s matched by this query.
     pub(crate) matched_tables: FixedBitSet,
     /// Metadata about the [`Archetype`]s matched by this query.
@@ -234,6 +237,7 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
         Self {
             world_id: world.id(),
             archetype_generation: ArchetypeGeneration::initial(),
+            compactions: world.archetypes().compactions(),
             matched_storage_ids: Vec::new(),
             is_dense,
             fetch_state,
@@ -276,6 +280,7 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
         let mut state = Self {
             world_id: builder.world().id(),
             archetype_generation: ArchetypeGeneration::initial(),
+            compactions: builder.world().archetypes().compactions(),
             matched_storage_ids: Vec::new(),
             is_dense,
             fetch_state,
@@ -410,12 +415,17 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
     /// have unique access to the components they query.
     /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
     /// with a mismatched [`WorldId`] is unsound.
+    ///
+    /// # Panics
+    ///
+    /// If [`World::compact`] renumbered the archetypes and tables of `world` since this state was last updated.
     pub unsafe fn query_unchecked_manual_with_ticks<'w, 's>(
         &'s self,
         world: UnsafeWorldCell<'w>,
         last_run: Tick,
         this_run: Tick,
     ) -> Query<'w, 's, D, F> {
+        self.validate_compactions(world);
         // SAFETY:
         // - The caller ensured we have the correct access to the world.
         // - The caller ensured that the world matches.
@@ -504,6 +514,15 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
     /// If `world` does not match the one used to call `QueryState::new` for this instance.
     pub fn update_archetypes_unsafe_world_cell(&mut self, world: UnsafeWorldCell) {
         self.validate_world(world.id());
+        let compactions = world.archetypes().compactions();
+        if self.compactions != compactions {
+            // `World::compact` renumbered the archetypes and tables, so match them all again.
+            self.compactions = compactions;
+            self.archetype_generation = ArchetypeGeneration::initial();
+            self.matched_tables.clear();
+            self.matched_archetypes.clear();
+            self.matched_storage_ids.clear();
+        }
         if self.component_access.required.is_empty() {
             let archetypes = world.archetypes();
             let old_generation =
@@ -577,6 +596,25 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
         }
     }
 
+    /// # Panics
+    ///
+    /// If [`World::compact`] renumbered the archetypes and tables of `world` since this state was last updated,
+    /// since the matched ids this state caches no longer refer to the same archetypes and tables.
+    #[inline]
+    #[track_caller]
+    fn validate_compactions(&self, world: UnsafeWorldCell) {
+        #[inline(never)]
+        #[track_caller]
+        #[cold]
+        fn panic_compacted() -> ! {
+            panic!("The World was compacted since this QueryState was last updated. Call `QueryState::update_archetypes` before using the `manual` methods.");
+        }
+
+        if self.compactions != world.archetypes().compactions() {
+            panic_compacted();
+        }
+    }
+
     /// Update the current [`QueryState`] with information from the provided [`Archetype`]
     /// (if applicable, i.e. if the archetype has any intersecting [`ComponentId`] with the current [`QueryState`]).
     ///
@@ -679,6 +717,7 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
         QueryState {
             world_id: self.world_id,
             archetype_generation: self.archetype_generation,
+            compactions: self.compactions,
             matched_storage_ids: self.matched_storage_ids.clone(),
             is_dense: self.is_dense,
             fetch_state,
@@ -795,6 +834,10 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
             DebugName::type_name::<(NewD, NewF)>(), DebugName::type_name::<(D, F)>(), DebugName::type_name::<(OtherD, OtherF)>()
         );
 
+        if self.compactions != other.compactions {
+            panic!("Joining queries whose caches were updated before and after `World::compact` is not allowed.");
+        }
+
         if self.archetype_generation != other.archetype_generation {
             warn!("You have tried to join queries with different archetype_generations. This could lead to unpredictable results.");
         }
@@ -826,6 +869,7 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
         QueryState {
             world_id: self.world_id,
             archetype_generation: self.archetype_generation,
+            compactions: self.compactions,
             matched_storage_ids,
             is_dense,
             fetch_state: new_fetch_state,
@@ -0,0 +1,226 @@
+//! Definitions for [`Event`], [`EntityEvent`] and [`BufferedEvent`] reflection.
+//!
+//! These allow events to be dispatched by type path, for example by scripting or remote tooling,
+//! without knowing their concrete type at compile time.
+
+use crate::{
+    entity::Entity,
+    event::{BufferedEvent, EntityEvent, Event},
+    world::World,
+};
+use bevy_reflect::{FromReflect, FromType, PartialReflect, TypePath, TypeRegistry};
+
+use super::from_reflect_with_fallback;
+
+/// A struct used to trigger reflected [`Event`]s of a type.
+///
+/// A [`ReflectEvent`] for type `T` can be obtained via
+/// [`bevy_reflect::TypeRegistration::data`], once `T` has been registered with the
+/// `#[reflect(Event)]` attribute.
+#[derive(Clone)]
+pub struct ReflectEvent {
+    trigger: fn(&mut World, &dyn PartialReflect, &TypeRegistry),
+}
+
+impl ReflectEvent {
+    /// Triggers the reflected [`Event`] like [`World::trigger`], running any
+    /// [`Observer`](crate::observer::Observer)s watching for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reflected value can't be converted into this event type.
+    /// See [`from_reflect_with_fallback`] for the strategies that are attempted.
+    pub fn trigger(&self, world: &mut World, event: &dyn PartialReflect, registry: &TypeRegistry) {
+        (self.trigger)(world, event, registry);
+    }
+}
+
+impl<E: Event + FromReflect + TypePath> FromType<E> for ReflectEvent {
+    fn from_type() -> Self {
+        ReflectEvent {
+            trigger: |world, reflected_event, registry| {
+                let event = from_reflect_with_fallback::<E>(reflected_event, world, registry);
+                world.trigger(event);
+            },
+        }
+    }
+}
+
+/// A struct used to trigger reflected [`EntityEvent`]s of a type on entities.
+///
+/// A [`ReflectEntityEvent`] for type `T` can be obtained via
+/// [`bevy_reflect::TypeRegistration::data`], once `T` has been registered with the
+/// `#[reflect(EntityEvent)]` attribute.
+#[derive(Clone)]
+pub struct ReflectEntityEvent {
+    trigger_targets: fn(&mut World, &dyn PartialReflect, &[Entity], &TypeRegistry),
+}
+
+impl ReflectEntityEvent {
+    /// Triggers the reflected [`EntityEvent`] for the given `targets` like
+    /// [`World::trigger_targets`], running any [`Observer`](crate::observer::Observer)s
+    /// watching for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reflected value can't be converted into this event type.
+    /// See [`from_reflect_with_fallback`] for the strategies that are attempted.
+    pub fn trigger_targets(
+        &self,
+        world: &mut World,
+        event: &dyn PartialReflect,
+        targets: &[Entity],
+        registry: &TypeRegistry,
+    ) {
+        (self.trigger_targets)(world, event, targets, registry);
+    }
+}
+
+impl<E: EntityEvent + FromReflect + TypePath> FromType<E> for ReflectEntityEvent {
+    fn from_type() -> Self {
+        ReflectEntityEvent {
+            trigger_targets: |world, reflected_event, targets, registry| {
+                let event = from_reflect_with_fallback::<E>(reflected_event, world, registry);
+                world.trigger_targets(event, targets);
+            },
+        }
+    }
+}
+
+/// A struct used to write reflected [`BufferedEvent`]s of a type.
+///
+/// A [`ReflectBufferedEvent`] for type `T` can be obtained via
+/// [`bevy_reflect::TypeRegistration::data`], once `T` has been registered with the
+/// `#[reflect(BufferedEvent)]` attribute.
+#[derive(Clone)]
+pub struct ReflectBufferedEvent {
+    write: fn(&mut World, &dyn PartialReflect, &TypeRegistry) -> bool,
+}
+
+impl ReflectBufferedEvent {
+    /// Writes the reflected [`BufferedEvent`] to its [`Events`](crate::event::Events) resource
+    /// like [`World::write_event`].
+    ///
+    /// Returns `false` if the event could not be written, because the event type was never
+    /// added to the world.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reflected value can't be converted into this event type.
+    /// See [`from_reflect_with_fallback`] for the strategies that are attempted.
+    pub fn write(
+        &self,
+        world: &mut World,
+        event: &dyn PartialReflect,
+        registry: &TypeRegistry,
+    ) -> bool {
+        (self.write)(world, event, registry)
+    }
+}
+
+impl<E: BufferedEvent + FromReflect + TypePath> FromType<E> for ReflectBufferedEvent {
+    fn from_type() -> Self {
+        ReflectBufferedEvent {
+            write: |world, reflected_event, registry| {
+                let event = from_reflect_with_fallback::<E>(reflected_event, world, registry);
+                world.write_event(event).is_some()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entity::Entity,
+        event::{BufferedEvent, EntityEvent, Events},
+        observer::On,
+        reflect::{AppTypeRegistry, ReflectBufferedEvent, ReflectEntityEvent, ReflectEvent},
+        resource::Resource,
+        system::ResMut,
+        world::World,
+    };
+    use alloc::vec::Vec;
+    use bevy_reflect::{DynamicTupleStruct, Reflect};
+    use core::any::TypeId;
+
+    #[derive(EntityEvent, BufferedEvent, Reflect, Clone, Copy)]
+    #[reflect(Event, EntityEvent, BufferedEvent)]
+    struct Damage(u32);
+
+    #[derive(Resource, Default)]
+    struct Received(Vec<(Option<Entity>, u32)>);
+
+    fn setup() -> (World, AppTypeRegistry) {
+        let mut world = World::new();
+        world.init_resource::<Received>();
+        world.init_resource::<Events<Damage>>();
+        world.add_observer(|trigger: On<Damage>, mut received: ResMut<Received>| {
+            let target = Some(trigger.target()).filter(|&target| target != Entity::PLACEHOLDER);
+            received.0.push((target, trigger.event().0));
+        });
+
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Damage>();
+        (world, registry)
+    }
+
+    fn reflected_damage(amount: u32) -> DynamicTupleStruct {
+        let mut event = DynamicTupleStruct::default();
+        event.insert(amount);
+        event
+    }
+
+    #[test]
+    fn trigger_reflected_events() {
+        let (mut world, registry) = setup();
+        let registry = registry.read();
+        let target = world.spawn_empty().id();
+
+        let reflect_event = registry.get_type_data::<ReflectEvent>(TypeId::of::<Damage>());
+        reflect_event
+            .unwrap()
+            .trigger(&mut world, &reflected_damage(1), &registry);
+
+        let reflect_entity_event =
+            registry.get_type_data::<ReflectEntityEvent>(TypeId::of::<Damage>());
+        reflect_entity_event.unwrap().trigger_targets(
+            &mut world,
+            &reflected_damage(2),
+            &[target],
+            &registry,
+        );
+
+        assert_eq!(
+            world.resource::<Received>().0,
+            [(None, 1), (Some(target), 2)]
+        );
+    }
+
+    #[test]
+    fn write_reflected_buffered_event() {
+        let (mut world, registry) = setup();
+        let registry = registry.read();
+
+        let reflect_buffered_event =
+            registry.get_type_data::<ReflectBufferedEvent>(TypeId::of::<Damage>());
+        assert!(reflect_buffered_event
+            .unwrap()
+            .write(&mut world, &reflected_damage(3), &registry));
+
+        let events = world.resource::<Events<Damage>>();
+        let written: Vec<_> = events
+            .iter_current_update_events()
+            .map(|event| event.0)
+            .collect();
+        assert_eq!(written, [3]);
+        assert!(world.resource::<Received>().0.is_empty());
+
+        world.remove_resource::<Events<Damage>>();
+        assert!(!reflect_buffered_event.unwrap().write(
+            &mut world,
+            &reflected_damage(4),
+            &registry
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    component::{Component, ComponentCloneBehavior, ComponentDescriptor, ComponentId, StorageType},
+    entity::Entity,
+    resource::Resource,
+    world::World,
+};
+use alloc::format;
+use bevy_platform::collections::HashMap;
+use bevy_ptr::OwningPtr;
+use bevy_utils::prelude::DebugName;
+use core::{alloc::Layout, any::TypeId};
+
+/// The components that mark the sources of fragmenting relationships, one for each `(relationship, target)` pair.
+///
+/// A relationship derived with the `fragmenting` attribute, like `#[relationship(relationship_target = X, fragmenting)]`,
+/// inserts a zero-sized marker component on its source for each of its targets. Sources of different targets
+/// then live in different archetypes, so that a query can match the sources of a single target without checking
+/// every source. Use [`World::relationship_pair_id`] to get the marker component of a target, for example to
+/// build a query with [`QueryBuilder::with_id`](crate::query::QueryBuilder::with_id).
+///
+/// Each target gets its own [`ComponentId`], and component ids are never freed, so fragmenting relationships suit
+/// a bounded set of long-lived targets, like teams or factions, rather than targets that are spawned and
+/// despawned all the time.
+#[derive(Resource, Default, Debug)]
+pub struct RelationshipPairs {
+    ids: HashMap<(TypeId, Entity), ComponentId>,
+}
+
+impl RelationshipPairs {
+    /// Returns the marker component of the `R` relationship with `target`, if any entity related to `target` with
+    /// `R` since the relationship was registered.
+    pub fn get<R: Component>(&self, target: Entity) -> Option<ComponentId> {
+        self.ids.get(&(TypeId::of::<R>(), target)).copied()
+    }
+}
+
+impl World {
+    /// Returns the marker component that the sources of the fragmenting `R` relationship with `target` have.
+    /// See [`RelationshipPairs`] for more information.
+    pub fn relationship_pair_id<R: Component>(&self, target: Entity) -> Option<ComponentId> {
+        self.get_resource::<RelationshipPairs>()?.get::<R>(target)
+    }
+}
+
+/// Inserts the marker component of the `R` relationship with `target` on `source`, registering it if needed.
+pub(crate) fn insert_relationship_pair<R: Component>(
+    world: &mut World,
+    source: Entity,
+    target: Entity,
+) {
+    let key = (TypeId::of::<R>(), target);
+    let id = match world
+        .get_resource_or_init::<RelationshipPairs>()
+        .ids
+        .get(&key)
+    {
+        Some(id) => *id,
+        None => {
+            // SAFETY: The marker is zero-sized, so it needs no drop function.
+            let descriptor = unsafe {
+                ComponentDescriptor::new_with_layout(
+                    format!("{}({target})", DebugName::type_name::<R>()),
+                    StorageType::Table,
+                    Layout::new::<()>(),
+                    None,
+                    false,
+                    // The relationship hooks of the clone insert its markers.
+                    ComponentCloneBehavior::Ignore,
+                )
+            };
+            let id = world.register_component_with_descriptor(descriptor);
+            world
+                .resource_mut::<RelationshipPairs>()
+                .ids
+                .insert(key, id);
+            id
+        }
+    };
+    if let Ok(mut source) = world.get_entity_mut(source) {
+        OwningPtr::make((), |ptr| {
+            // SAFETY: `id` was registered with the layout of `()`.
+            unsafe {
+                source.insert_by_id(id, ptr);
+            }
+        });
+    }
+}
+
+/// Removes the marker component of the `R` relationship with `target` from `source`.
+pub(crate) fn remove_relationship_pair<R: Component>(
+    world: &mut World,
+    source: Entity,
+    target: Entity,
+) {
+    let Some(id) = world.relationship_pair_id::<R>(target) else {
+        return;
+    };
+    if let Ok(mut source) = world.get_entity_mut(source) {
+        source.remove_by_id(id);
+    }
+}
@@ -0,0 +1,570 @@
+use crate::{
+    component::{Component, ComponentCloneBehavior, Mutable},
+    entity::{ComponentCloneCtx, Entity},
+    error::CommandWithEntity,
+    lifecycle::HookContext,
+    relationship::{
+        insert_relationship_pair, remove_relationship_pair, RelatedEntities,
+        RelationshipCloneBehaviorSpecialization, RelationshipHookMode,
+        RelationshipSourceCollection,
+    },
+    world::{DeferredWorld, EntityWorldMut, World},
+};
+use alloc::{format, vec::Vec};
+use bevy_utils::prelude::DebugName;
+use log::warn;
+
+/// A [`Component`] on a "source" [`Entity`] that references any number of "target" entities, creating a
+/// many-to-many relationship between them. Every [`ManyToManyRelationship`] has a corresponding
+/// [`ManyToManyRelationshipTarget`] type (and vice-versa), which exists on the "target" entities of the relationship
+/// and contains the list of all "source" entities that relate to the given "target".
+///
+/// Like a [`Relationship`](super::Relationship), the [`ManyToManyRelationship`] component is the "source of truth"
+/// and the [`ManyToManyRelationshipTarget`] component reflects that source of truth, using component hooks to keep
+/// both sides in sync. The targets of a [`ManyToManyRelationship`] are stored in a [`RelationshipSourceCollection`],
+/// and can be changed with [`EntityWorldMut::add_many_targets`] and [`EntityWorldMut::remove_many_targets`],
+/// or from the target side with [`EntityWorldMut::add_many_related`] and [`EntityWorldMut::remove_many_related`].
+///
+/// ## Derive
+///
+/// [`ManyToManyRelationship`] and [`ManyToManyRelationshipTarget`] are derived like
+/// [`Relationship`](super::Relationship) and [`RelationshipTarget`](super::RelationshipTarget),
+/// with the additional `many_to_many` attribute on both sides:
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// #[derive(Component)]
+/// #[relationship(relationship_target = LikedBy, many_to_many)]
+/// pub struct Likes(Vec<Entity>);
+///
+/// #[derive(Component)]
+/// #[relationship_target(relationship = Likes, many_to_many)]
+/// pub struct LikedBy(Vec<Entity>);
+///
+/// let mut world = World::new();
+/// let a = world.spawn_empty().id();
+/// let b = world.spawn_empty().id();
+/// let c = world.spawn(Likes(vec![a, b])).id();
+/// let d = world.spawn_empty().add_many_targets::<Likes>(&[a]).id();
+///
+/// assert_eq!(world.entity(a).get::<LikedBy>().unwrap().0, [c, d]);
+/// assert_eq!(world.entity(b).get::<LikedBy>().unwrap().0, [c]);
+/// ```
+///
+/// As with [`RelationshipTarget`](super::RelationshipTarget), the `#[relationship_target(linked_spawn)]` attribute
+/// despawns the sources of a target when the target is despawned.
+///
+/// An entity relates to each of its targets at most once: duplicate targets are removed from the
+/// [`ManyToManyRelationship`] when it is inserted.
+///
+/// ## Fragmentation
+///
+/// By default, all the sources of a [`ManyToManyRelationship`] share the same archetype, whichever entities they
+/// target. With the `#[relationship(fragmenting)]` attribute, each source gets a marker component for each of its
+/// targets instead, like a fragmenting [`Relationship`](super::Relationship). See [`RelationshipPairs`](super::RelationshipPairs)
+/// for more information.
+pub trait ManyToManyRelationship: RelatedEntities + Sized {
+    /// The [`Component`] added to the "target" entities of this [`ManyToManyRelationship`], which contains the list
+    /// of all "source" entities that relate to the "target".
+    type RelationshipTarget: ManyToManyRelationshipTarget<Relationship = Self>;
+
+    /// If this is true, each source gets a marker component for each of its targets, which splits the sources into
+    /// one archetype per set of targets. See [`RelationshipPairs`](super::RelationshipPairs) for more information.
+    ///
+    /// This defaults to false when derived.
+    const FRAGMENTING: bool = false;
+
+    /// The collection type that stores the "target" entities of this [`ManyToManyRelationship`].
+    type Collection: RelationshipSourceCollection;
+
+    /// Returns a reference to the stored [`ManyToManyRelationship::Collection`].
+    fn collection(&self) -> &Self::Collection;
+
+    /// Returns a mutable reference to the stored [`ManyToManyRelationship::Collection`].
+    ///
+    /// # Warning
+    /// This should generally not be called by user code, as modifying the internal collection could invalidate the relationship.
+    /// The collection should not contain duplicates.
+    fn collection_mut_risky(&mut self) -> &mut Self::Collection;
+
+    /// Creates a new [`ManyToManyRelationship`] from the given [`ManyToManyRelationship::Collection`].
+    ///
+    /// # Warning
+    /// This should generally not be called by user code, as constructing the internal collection could invalidate the relationship.
+    /// The collection should not contain duplicates.
+    fn from_collection_risky(collection: Self::Collection) -> Self;
+
+    /// The `on_insert` component hook that maintains the [`ManyToManyRelationship`] / [`ManyToManyRelationshipTarget`] connection.
+    fn on_insert(
+        mut world: DeferredWorld,
+        HookContext {
+            entity,
+            caller,
+            relationship_hook_mode,
+            ..
+        }: HookContext,
+    ) {
+        let targets: Vec<Entity> = world.entity(entity).get::<Self>().unwrap().iter().collect();
+        if Self::FRAGMENTING {
+            // The markers track the relationship itself, so they're inserted even if the hooks are skipped.
+            let targets = targets.clone();
+            world.commands().queue(move |world: &mut World| {
+                for target in targets {
+                    insert_relationship_pair::<Self>(world, entity, target);
+                }
+            });
+        }
+        if !should_run_hook::<Self::RelationshipTarget>(relationship_hook_mode) {
+            return;
+        }
+        let mut invalid_targets = Vec::new();
+        for (index, target_entity) in targets.iter().enumerate() {
+            // An entity relates to each target at most once, so drop repeated targets.
+            if targets[..index].contains(&target_entity) {
+                invalid_targets.push(target_entity);
+                continue;
+            }
+
+            if target_entity == entity {
+                warn!(
+                    "{}The {} relationship on entity {entity:?} targets itself. The invalid target has been removed.",
+                    caller.map(|location|format!("{location}: ")).unwrap_or_default(),
+                    DebugName::type_name::<Self>(),
+                );
+                invalid_targets.push(target_entity);
+                continue;
+            }
+
+            // For target collections holding a single entity, remove the existing source before adding a new one
+            let current_source_to_remove = world
+                .get_entity(target_entity)
+                .ok()
+                .and_then(|target_entity_ref| target_entity_ref.get::<Self::RelationshipTarget>())
+                .and_then(|relationship_target| {
+                    relationship_target
+                        .collection()
+                        .source_to_remove_before_add()
+                });
+            if let Some(current_source) = current_source_to_remove
+                && current_source != entity
+            {
+                world.commands().entity(current_source).queue_silenced(
+                    move |mut current_source: EntityWorldMut| {
+                        current_source.remove_many_targets::<Self>(&[target_entity]);
+                    },
+                );
+            }
+
+            if let Ok(mut entity_commands) = world.commands().get_entity(target_entity) {
+                // Deferring is necessary for batch mode
+                entity_commands
+                    .entry::<Self::RelationshipTarget>()
+                    .and_modify(move |mut relationship_target| {
+                        // The source may already be there, e.g. if the relationship was re-inserted.
+                        if !relationship_target.iter().any(|source| source == entity) {
+                            relationship_target.collection_mut_risky().add(entity);
+                        }
+                    })
+                    .or_insert_with(move || {
+                        let mut target = Self::RelationshipTarget::with_capacity(1);
+                        target.collection_mut_risky().add(entity);
+                        target
+                    });
+            } else {
+                warn!(
+                    "{}The {} relationship on entity {entity:?} targets {target_entity:?}, which does not exist. The invalid target has been removed.",
+                    caller.map(|location|format!("{location}: ")).unwrap_or_default(),
+                    DebugName::type_name::<Self>(),
+                );
+                invalid_targets.push(target_entity);
+            }
+        }
+
+        if !invalid_targets.is_empty() {
+            world
+                .commands()
+                .entity(entity)
+                .queue_silenced(move |mut entity: EntityWorldMut| {
+                    remove_targets_without_hooks::<Self>(&mut entity, &invalid_targets);
+                });
+        }
+    }
+
+    /// The `on_replace` component hook that maintains the [`ManyToManyRelationship`] / [`ManyToManyRelationshipTarget`] connection.
+    // note: think of this as "on_drop"
+    fn on_replace(
+        mut world: DeferredWorld,
+        HookContext {
+            entity,
+            relationship_hook_mode,
+            ..
+        }: HookContext,
+    ) {
+        let targets: Vec<Entity> = world.entity(entity).get::<Self>().unwrap().iter().collect();
+        if Self::FRAGMENTING {
+            let targets = targets.clone();
+            world.commands().queue(move |world: &mut World| {
+                for target in targets {
+                    remove_relationship_pair::<Self>(world, entity, target);
+                }
+            });
+        }
+        if !should_run_hook::<Self::RelationshipTarget>(relationship_hook_mode) {
+            return;
+        }
+        for target_entity in targets {
+            if let Ok(mut target_entity_mut) = world.get_entity_mut(target_entity)
+                && let Some(mut relationship_target) =
+                    target_entity_mut.get_mut::<Self::RelationshipTarget>()
+            {
+                relationship_target.collection_mut_risky().remove(entity);
+                if relationship_target.is_empty() {
+                    let command = |mut entity: EntityWorldMut| {
+                        // this "remove" operation must check emptiness because in the event that an identical
+                        // relationship is inserted on top, this removal would break that relationship
+                        if entity
+                            .get::<Self::RelationshipTarget>()
+                            .is_some_and(ManyToManyRelationshipTarget::is_empty)
+                        {
+                            entity.remove::<Self::RelationshipTarget>();
+                        }
+                    };
+
+                    world
+                        .commands()
+                        .queue_silenced(command.with_entity(target_entity));
+                }
+            }
+        }
+    }
+
+    /// Creates this [`ManyToManyRelationship`] with the given pre-allocated entity capacity.
+    fn with_capacity(capacity: usize) -> Self {
+        let collection =
+            <Self::Collection as RelationshipSourceCollection>::with_capacity(capacity);
+        Self::from_collection_risky(collection)
+    }
+
+    /// Iterates the target entities stored in this collection.
+    #[inline]
+    fn iter(&self) -> <Self::Collection as RelationshipSourceCollection>::SourceIter<'_> {
+        self.collection().iter()
+    }
+
+    /// Returns the number of target entities in this collection.
+    #[inline]
+    fn len(&self) -> usize {
+        self.collection().len()
+    }
+
+    /// Returns true if this collection is empty.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.collection().is_empty()
+    }
+}
+
+/// A [`Component`] containing the collection of entities that relate to this [`Entity`] via the associated
+/// [`ManyToManyRelationship`] type. See the [`ManyToManyRelationship`] documentation for more information.
+pub trait ManyToManyRelationshipTarget:
+    RelatedEntities + Component<Mutability = Mutable> + Sized
+{
+    /// If this is true, when despawning this entity, the related entities targeting this entity will also be despawned.
+    ///
+    /// This defaults to false when derived.
+    const LINKED_SPAWN: bool;
+
+    /// The [`ManyToManyRelationship`] that populates this [`ManyToManyRelationshipTarget`] collection.
+    type Relationship: ManyToManyRelationship<RelationshipTarget = Self>;
+
+    /// The collection type that stores the "source" entities for this [`ManyToManyRelationshipTarget`] component.
+    type Collection: RelationshipSourceCollection;
+
+    /// Returns a reference to the stored [`ManyToManyRelationshipTarget::Collection`].
+    fn collection(&self) -> &Self::Collection;
+
+    /// Returns a mutable reference to the stored [`ManyToManyRelationshipTarget::Collection`].
+    ///
+    /// # Warning
+    /// This should generally not be called by user code, as modifying the internal collection could invalidate the relationship.
+    /// The collection should not contain duplicates.
+    fn collection_mut_risky(&mut self) -> &mut Self::Collection;
+
+    /// Creates a new [`ManyToManyRelationshipTarget`] from the given [`ManyToManyRelationshipTarget::Collection`].
+    ///
+    /// # Warning
+    /// This should generally not be called by user code, as constructing the internal collection could invalidate the relationship.
+    /// The collection should not contain duplicates.
+    fn from_collection_risky(collection: Self::Collection) -> Self;
+
+    /// The `on_replace` component hook that maintains the [`ManyToManyRelationship`] / [`ManyToManyRelationshipTarget`] connection.
+    // note: think of this as "on_drop"
+    fn on_replace(
+        mut world: DeferredWorld,
+        HookContext {
+            entity,
+            relationship_hook_mode,
+            ..
+        }: HookContext,
+    ) {
+        match relationship_hook_mode {
+            RelationshipHookMode::Run => {}
+            RelationshipHookMode::Skip | RelationshipHookMode::RunIfNotLinked => return,
+        }
+        let (entities, mut commands) = world.entities_and_commands();
+        let relationship_target = entities.get(entity).unwrap().get::<Self>().unwrap();
+        for source_entity in relationship_target.iter() {
+            commands.entity(source_entity).queue_silenced(
+                move |mut source_entity: EntityWorldMut| {
+                    remove_targets_without_hooks::<Self::Relationship>(
+                        &mut source_entity,
+                        &[entity],
+                    );
+                },
+            );
+        }
+    }
+
+    /// The `on_despawn` component hook that despawns entities stored in an entity's [`ManyToManyRelationshipTarget`]
+    /// when that entity is despawned.
+    // note: think of this as "on_drop"
+    fn on_despawn(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+        let (entities, mut commands) = world.entities_and_commands();
+        let relationship_target = entities.get(entity).unwrap().get::<Self>().unwrap();
+        for source_entity in relationship_target.iter() {
+            commands.entity(source_entity).try_despawn();
+        }
+    }
+
+    /// Creates this [`ManyToManyRelationshipTarget`] with the given pre-allocated entity capacity.
+    fn with_capacity(capacity: usize) -> Self {
+        let collection =
+            <Self::Collection as RelationshipSourceCollection>::with_capacity(capacity);
+        Self::from_collection_risky(collection)
+    }
+
+    /// Iterates the source entities stored in this collection.
+    #[inline]
+    fn iter(&self) -> <Self::Collection as RelationshipSourceCollection>::SourceIter<'_> {
+        self.collection().iter()
+    }
+
+    /// Returns the number of source entities in this collection.
+    #[inline]
+    fn len(&self) -> usize {
+        self.collection().len()
+    }
+
+    /// Returns true if this collection is empty.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.collection().is_empty()
+    }
+}
+
+fn should_run_hook<T: ManyToManyRelationshipTarget>(
+    relationship_hook_mode: RelationshipHookMode,
+) -> bool {
+    match relationship_hook_mode {
+        RelationshipHookMode::Run => true,
+        RelationshipHookMode::Skip => false,
+        RelationshipHookMode::RunIfNotLinked => !T::LINKED_SPAWN,
+    }
+}
+
+/// Removes the given `targets` from the `R` relationship of `entity`, without running the relationship hooks,
+/// and removes the relationship if it is left empty.
+fn remove_targets_without_hooks<R: ManyToManyRelationship>(
+    entity: &mut EntityWorldMut,
+    targets: &[Entity],
+) {
+    let id = entity.id();
+    let is_empty = entity.world_scope(|world| {
+        let is_empty = DeferredWorld::from(&mut *world)
+            .modify_component_with_relationship_hook_mode::<R, _>(
+                id,
+                RelationshipHookMode::Skip,
+                |relationship| {
+                    let collection = relationship.collection_mut_risky();
+                    for target in targets {
+                        collection.remove(*target);
+                    }
+                    collection.is_empty()
+                },
+            )
+            .ok()
+            .flatten();
+
+        world.flush();
+
+        is_empty
+    });
+
+    if is_empty == Some(true) {
+        entity.remove::<R>();
+    }
+}
+
+/// Registers the clone of a [`ManyToManyRelationship`] with the targets that aren't cloned along with it.
+///
+/// When cloning the sources of a `linked_spawn` [`ManyToManyRelationshipTarget`], the relationship hooks of the
+/// cloned sources don't run, and the cloned target lists them instead. The other targets of the cloned sources
+/// are added to here.
+pub fn clone_many_to_many_relationship<R: ManyToManyRelationship>(
+    component: &R,
+    context: &mut ComponentCloneCtx,
+) {
+    if !(context.linked_cloning() && R::RelationshipTarget::LINKED_SPAWN) {
+        return;
+    }
+    let cloned_source = context.target();
+    let targets: Vec<Entity> = component.iter().collect();
+    context.queue_deferred(move |world, mapper| {
+        for target in targets {
+            // Targets that are cloned too list the cloned source already.
+            if mapper.get_mapped(target) != target {
+                continue;
+            }
+            _ = DeferredWorld::from(&mut *world)
+                .modify_component_with_relationship_hook_mode::<R::RelationshipTarget, ()>(
+                    target,
+                    RelationshipHookMode::Skip,
+                    |relationship_target| {
+                        if !relationship_target
+                            .iter()
+                            .any(|source| source == cloned_source)
+                        {
+                            relationship_target
+                                .collection_mut_risky()
+                                .add(cloned_source);
+                        }
+                    },
+                );
+        }
+    });
+}
+
+/// Fills the collection of the clone of a [`ManyToManyRelationshipTarget`], the way
+/// [`clone_relationship_target`](super::clone_relationship_target) does for a
+/// [`RelationshipTarget`](super::RelationshipTarget).
+///
+/// When cloning with `linked_spawn`, the sources are cloned too. When moving, the sources are updated to target
+/// the new entity instead of the old one.
+pub fn clone_many_to_many_relationship_target<T: ManyToManyRelationshipTarget>(
+    component: &T,
+    cloned: &mut T,
+    context: &mut ComponentCloneCtx,
+) {
+    if context.linked_cloning() && T::LINKED_SPAWN {
+        let collection = cloned.collection_mut_risky();
+        for entity in component.iter() {
+            collection.add(entity);
+            context.queue_entity_clone(entity);
+        }
+    } else if context.moving() {
+        let source = context.source();
+        let target = context.target();
+        let collection = cloned.collection_mut_risky();
+        for entity in component.iter() {
+            collection.add(entity);
+            context.queue_deferred(move |world, _mapper| {
+                // We don't want relationships hooks to run because we are manually constructing the collection here
+                _ = DeferredWorld::from(world)
+                    .modify_component_with_relationship_hook_mode::<T::Relationship, ()>(
+                        entity,
+                        RelationshipHookMode::Skip,
+                        |relationship| {
+                            let collection = relationship.collection_mut_risky();
+                            collection.remove(source);
+                            collection.add(target);
+                        },
+                    );
+            });
+        }
+    }
+}
+
+/// Specialized trait for many-to-many relationship clone specialization using autoderef.
+#[doc(hidden)]
+pub trait ManyToManyCloneBehaviorViaReflect {
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior;
+}
+
+#[cfg(feature = "bevy_reflect")]
+impl<C: ManyToManyRelationship + bevy_reflect::Reflect + bevy_reflect::TypePath>
+    ManyToManyCloneBehaviorViaReflect for &RelationshipCloneBehaviorSpecialization<C>
+{
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior {
+        ComponentCloneBehavior::Custom(|source, context| {
+            if let Some(component) = source.read::<C>()
+                && let Ok(cloned) = component.reflect_clone_and_take::<C>()
+            {
+                clone_many_to_many_relationship(component, context);
+                context.write_target_component(cloned);
+            }
+        })
+    }
+}
+
+/// Specialized trait for many-to-many relationship clone specialization using autoderef.
+#[doc(hidden)]
+pub trait ManyToManyCloneBehaviorViaClone {
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior;
+}
+
+impl<C: ManyToManyRelationship + Clone> ManyToManyCloneBehaviorViaClone
+    for &&RelationshipCloneBehaviorSpecialization<C>
+{
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior {
+        ComponentCloneBehavior::Custom(|source, context| {
+            if let Some(component) = source.read::<C>() {
+                clone_many_to_many_relationship(component, context);
+                context.write_target_component(component.clone());
+            }
+        })
+    }
+}
+
+/// Specialized trait for many-to-many relationship target clone specialization using autoderef.
+#[doc(hidden)]
+pub trait ManyToManyTargetCloneBehaviorViaReflect {
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior;
+}
+
+#[cfg(feature = "bevy_reflect")]
+impl<C: ManyToManyRelationshipTarget + bevy_reflect::Reflect + bevy_reflect::TypePath>
+    ManyToManyTargetCloneBehaviorViaReflect for &&&RelationshipCloneBehaviorSpecialization<C>
+{
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior {
+        ComponentCloneBehavior::Custom(|source, context| {
+            if let Some(component) = source.read::<C>()
+                && let Ok(mut cloned) = component.reflect_clone_and_take::<C>()
+            {
+                cloned.collection_mut_risky().clear();
+                clone_many_to_many_relationship_target(component, &mut cloned, context);
+                context.write_target_component(cloned);
+            }
+        })
+    }
+}
+
+/// Specialized trait for many-to-many relationship target clone specialization using autoderef.
+#[doc(hidden)]
+pub trait ManyToManyTargetCloneBehaviorViaClone {
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior;
+}
+
+impl<C: ManyToManyRelationshipTarget + Clone> ManyToManyTargetCloneBehaviorViaClone
+    for &&&&RelationshipCloneBehaviorSpecialization<C>
+{
+    fn default_clone_behavior(&self) -> ComponentCloneBehavior {
+        ComponentCloneBehavior::Custom(|source, context| {
+            if let Some(component) = source.read::<C>() {
+                let mut cloned = component.clone();
+                cloned.collection_mut_risky().clear();
+                clone_many_to_many_relationship_target(component, &mut cloned, context);
+                context.write_target_component(cloned);
+            }
+        })
+    }
+}
@@ -1,6 +1,9 @@
 //! This module provides functionality to link entities to each other using specialized components called "relationships". See the [`Relationship`] trait for more info.
 
+mod fragmenting;
+mod many_to_many;
 mod related_methods;
+mod related_query_data;
 mod relationship_query;
 mod relationship_source_collection;
 
@@ -9,7 +12,10 @@ use core::marker::PhantomData;
 use alloc::format;
 
 use bevy_utils::prelude::DebugName;
+pub use fragmenting::*;
+pub use many_to_many::*;
 pub use related_methods::*;
+pub use related_query_data::*;
 pub use relationship_query::*;
 pub use relationship_source_collection::*;
 
@@ -18,7 +24,7 @@ use crate::{
     entity::{ComponentCloneCtx, Entity},
     error::CommandWithEntity,
     lifecycle::HookContext,
-    world::{DeferredWorld, EntityWorldMut},
+    world::{DeferredWorld, EntityWorldMut, World},
 };
 use log::warn;
 
@@ -73,11 +79,41 @@ use log::warn;
 /// #[relationship_target(relationship = ChildOf, linked_spawn)]
 /// pub struct Children(Vec<Entity>);
 /// ```
+///
+/// When deriving [`Relationship`] you can specify the `#[relationship(fragmenting)]` attribute to split the sources
+/// into one archetype per target. See [`RelationshipPairs`] for more information.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// #[derive(Component)]
+/// #[relationship(relationship_target = TeamMembers, fragmenting)]
+/// pub struct OnTeam(pub Entity);
+///
+/// #[derive(Component)]
+/// #[relationship_target(relationship = OnTeam)]
+/// pub struct TeamMembers(Vec<Entity>);
+///
+/// let mut world = World::new();
+/// let red = world.spawn_empty().id();
+/// let blue = world.spawn_empty().id();
+/// let player = world.spawn(OnTeam(red)).id();
+/// world.spawn(OnTeam(blue));
+///
+/// let on_red = world.relationship_pair_id::<OnTeam>(red).unwrap();
+/// let mut query = QueryBuilder::<Entity>::new(&mut world).with_id(on_red).build();
+/// assert_eq!(query.iter(&world).collect::<Vec<_>>(), [player]);
+/// ```
 pub trait Relationship: Component + Sized {
     /// The [`Component`] added to the "target" entities of this [`Relationship`], which contains the list of all "source"
     /// entities that relate to the "target".
     type RelationshipTarget: RelationshipTarget<Relationship = Self>;
 
+    /// If this is true, the sources of each target get their own marker component, which splits them into one
+    /// archetype per target. See [`RelationshipPairs`] for more information.
+    ///
+    /// This defaults to false when derived.
+    const FRAGMENTING: bool = false;
+
     /// Gets the [`Entity`] ID of the related entity.
     fn get(&self) -> Entity;
 
@@ -108,6 +144,13 @@ pub trait Relationship: Component + Sized {
             ..
         }: HookContext,
     ) {
+        if Self::FRAGMENTING {
+            // The marker tracks the relationship itself, so it's inserted even if the hooks are skipped.
+            let target_entity = world.entity(entity).get::<Self>().unwrap().get();
+            world.commands().queue(move |world: &mut World| {
+                insert_relationship_pair::<Self>(world, entity, target_entity);
+            });
+        }
         match relationship_hook_mode {
             RelationshipHookMode::Run => {}
             RelationshipHookMode::Skip => return,
@@ -176,6 +219,12 @@ pub trait Relationship: Component + Sized {
             ..
         }: HookContext,
     ) {
+        if Self::FRAGMENTING {
+            let target_entity = world.entity(entity).get::<Self>().unwrap().get();
+            world.commands().queue(move |world: &mut World| {
+                remove_relationship_pair::<Self>(world, entity, target_entity);
+            });
+        }
         match relationship_hook_mode {
             RelationshipHookMode::Run => {}
             RelationshipHookMode::Skip => return,
@@ -213,6 +262,33 @@ pub trait Relationship: Component + Sized {
     }
 }
 
+/// A [`Component`] that stores a collection of entities related to the [`Entity`] it is on.
+///
+/// This is implemented for every [`RelationshipTarget`], as well as for both sides of a
+/// [`ManyToManyRelationship`], and is used to traverse relationships, for example with
+/// [`Query::iter_descendants`](crate::system::Query::iter_descendants).
+pub trait RelatedEntities: Component {
+    /// The type of iterator returned by [`RelatedEntities::iter_related`].
+    type RelatedIter<'a>: Iterator<Item = Entity>
+    where
+        Self: 'a;
+
+    /// Iterates the related entities stored in this component.
+    fn iter_related(&self) -> Self::RelatedIter<'_>;
+}
+
+impl<T: RelationshipTarget> RelatedEntities for T {
+    type RelatedIter<'a> = SourceIter<'a, T>;
+
+    #[inline]
+    fn iter_related(&self) -> Self::RelatedIter<'_> {
+        self.iter()
+    }
+}
+
+/// The iterator type for the entities stored in a [`RelatedEntities`] component.
+pub type RelatedIter<'w, R> = <R as RelatedEntities>::RelatedIter<'w>;
+
 /// The iterator type for the source entities in a [`RelationshipTarget`] collection,
 /// as defined in the [`RelationshipSourceCollection`] trait.
 pub type SourceIter<'w, R> =
@@ -675,4 +751,389 @@ mod tests {
         assert!(world.get::<ChildOf>(child).is_some());
         assert!(world.get::<Children>(parent).is_some());
     }
+
+    #[derive(Component)]
+    #[relationship(relationship_target = LikedBy, many_to_many)]
+    struct Likes(Vec<Entity>);
+
+    #[derive(Component)]
+    #[relationship_target(relationship = Likes, many_to_many)]
+    struct LikedBy(Vec<Entity>);
+
+    #[test]
+    fn many_to_many_relationship() {
+        use crate::relationship::ManyToManyRelationship;
+
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let c = world.spawn(Likes(alloc::vec![a, b])).id();
+        let d = world.spawn(Likes(alloc::vec![a])).id();
+        assert_eq!(world.entity(a).get::<LikedBy>().unwrap().0, &[c, d]);
+        assert_eq!(world.entity(b).get::<LikedBy>().unwrap().0, &[c]);
+
+        world.entity_mut(d).add_many_targets::<Likes>(&[a, b]);
+        assert_eq!(world.entity(d).get::<Likes>().unwrap().0, &[a, b]);
+        assert_eq!(world.entity(b).get::<LikedBy>().unwrap().0, &[c, d]);
+
+        world.entity_mut(c).remove_many_targets::<Likes>(&[b]);
+        assert_eq!(world.entity(c).get::<Likes>().unwrap().0, &[a]);
+        assert_eq!(world.entity(b).get::<LikedBy>().unwrap().0, &[d]);
+
+        // Removing the last target removes the relationship.
+        world.entity_mut(a).remove_many_related::<Likes>(&[c]);
+        assert!(!world.entity(c).contains::<Likes>());
+        assert_eq!(world.entity(a).get::<LikedBy>().unwrap().0, &[d]);
+
+        // Despawning a target removes it from its sources.
+        world.despawn(a);
+        world.flush();
+        assert_eq!(
+            world
+                .entity(d)
+                .get::<Likes>()
+                .unwrap()
+                .iter()
+                .collect::<Vec<_>>(),
+            [b]
+        );
+
+        // Despawning a source removes it from its targets.
+        world.despawn(d);
+        assert!(!world.entity(b).contains::<LikedBy>());
+    }
+
+    #[test]
+    fn many_to_many_relationship_removes_invalid_targets() {
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        world.despawn(b);
+        let c = world.spawn_empty().id();
+        world.entity_mut(c).insert(Likes(alloc::vec![a, b, c]));
+        world.flush();
+        assert_eq!(world.entity(c).get::<Likes>().unwrap().0, &[a]);
+        assert_eq!(world.entity(a).get::<LikedBy>().unwrap().0, &[c]);
+        assert!(!world.entity(c).contains::<LikedBy>());
+    }
+
+    #[test]
+    fn many_to_many_relationship_removes_duplicate_targets() {
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let c = world.spawn(Likes(alloc::vec![a, b, a])).id();
+        world.flush();
+        assert_eq!(world.entity(c).get::<Likes>().unwrap().0, &[b, a]);
+        assert_eq!(world.entity(a).get::<LikedBy>().unwrap().0, &[c]);
+
+        // Removing the edge leaves no stale source behind.
+        world.entity_mut(c).remove_many_targets::<Likes>(&[a]);
+        assert!(!world.entity(a).contains::<LikedBy>());
+        assert_eq!(world.entity(b).get::<LikedBy>().unwrap().0, &[c]);
+    }
+
+    #[test]
+    fn many_to_many_relationship_reinsertion_does_not_duplicate_sources() {
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let c = world.spawn(Likes(alloc::vec![a])).id();
+        world.entity_mut(c).insert(Likes(alloc::vec![a]));
+        // Re-inserting in a batch of commands defers the hooks of the earlier insertions.
+        let mut commands = world.commands();
+        commands.entity(c).insert(Likes(alloc::vec![a]));
+        commands.entity(c).insert(Likes(alloc::vec![a]));
+        world.flush();
+        assert_eq!(world.entity(a).get::<LikedBy>().unwrap().0, &[c]);
+
+        world.entity_mut(c).remove::<Likes>();
+        assert!(!world.entity(a).contains::<LikedBy>());
+    }
+
+    #[test]
+    fn many_to_many_relationship_commands_and_traversal() {
+        use crate::{prelude::Commands, system::Query, system::RunSystemOnce};
+
+        let mut world = World::new();
+        let [a, b, c, d] = core::array::from_fn(|_| world.spawn_empty().id());
+        world
+            .run_system_once(move |mut commands: Commands| {
+                commands.entity(a).add_many_targets::<Likes>(&[b, c]);
+                commands.entity(d).add_many_related::<Likes>(&[b, c]);
+            })
+            .unwrap();
+        assert_eq!(world.entity(d).get::<LikedBy>().unwrap().0, &[b, c]);
+
+        let targets = world
+            .run_system_once(move |query: Query<&Likes>| {
+                query.iter_descendants::<Likes>(a).collect::<Vec<_>>()
+            })
+            .unwrap();
+        assert_eq!(targets, [b, c, d]);
+        let sources = world
+            .run_system_once(move |query: Query<&LikedBy>| {
+                query
+                    .iter_descendants_depth_first::<LikedBy>(d)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap();
+        assert_eq!(sources, [b, a, c]);
+
+        world
+            .run_system_once(move |mut commands: Commands| {
+                commands.entity(d).clear_many_related::<Likes>();
+                commands.entity(a).clear_many_targets::<Likes>();
+            })
+            .unwrap();
+        for entity in [a, b, c, d] {
+            assert!(!world.entity(entity).contains::<Likes>());
+            assert!(!world.entity(entity).contains::<LikedBy>());
+        }
+    }
+
+    #[test]
+    fn many_to_many_relationship_linked_spawn() {
+        #[derive(Component)]
+        #[relationship(relationship_target = StoredBy, many_to_many)]
+        struct StoredIn(Vec<Entity>);
+
+        #[derive(Component)]
+        #[relationship_target(relationship = StoredIn, many_to_many, linked_spawn)]
+        struct StoredBy(Vec<Entity>);
+
+        let mut world = World::new();
+        let container = world.spawn_empty().id();
+        let other_container = world.spawn_empty().id();
+        let item = world
+            .spawn(StoredIn(alloc::vec![container, other_container]))
+            .id();
+        world.despawn(container);
+        assert!(world.get_entity(item).is_err());
+        assert!(!world.entity(other_container).contains::<StoredBy>());
+    }
+
+    #[test]
+    fn many_to_many_relationship_clone() {
+        use crate::entity::EntityCloner;
+
+        #[derive(Component, Clone)]
+        #[relationship(relationship_target = StoredBy, many_to_many)]
+        struct StoredIn(Vec<Entity>);
+
+        #[derive(Component, Clone)]
+        #[relationship_target(relationship = StoredIn, many_to_many, linked_spawn)]
+        struct StoredBy(Vec<Entity>);
+
+        let mut world = World::new();
+        let container = world.spawn_empty().id();
+        let other_container = world.spawn_empty().id();
+        let item = world
+            .spawn(StoredIn(alloc::vec![container, other_container]))
+            .id();
+
+        // Cloning a source relates the clone to the same targets.
+        let item_clone = world.entity_mut(item).clone_and_spawn();
+        assert_eq!(
+            world.entity(item_clone).get::<StoredIn>().unwrap().0,
+            &[container, other_container]
+        );
+        assert_eq!(
+            world.entity(container).get::<StoredBy>().unwrap().0,
+            &[item, item_clone]
+        );
+        world.despawn(item_clone);
+
+        // Linked cloning a target clones its sources, which keep their other targets.
+        let container_clone = world.spawn_empty().id();
+        EntityCloner::build_opt_out(&mut world)
+            .linked_cloning(true)
+            .clone_entity(container, container_clone);
+        let cloned_items = world
+            .entity(container_clone)
+            .get::<StoredBy>()
+            .unwrap()
+            .0
+            .clone();
+        assert_eq!(cloned_items.len(), 1);
+        let cloned_item = cloned_items[0];
+        assert_ne!(cloned_item, item);
+        assert_eq!(
+            world.entity(cloned_item).get::<StoredIn>().unwrap().0,
+            &[container_clone, other_container]
+        );
+        assert_eq!(
+            world.entity(container).get::<StoredBy>().unwrap().0,
+            &[item]
+        );
+        assert_eq!(
+            world.entity(other_container).get::<StoredBy>().unwrap().0,
+            &[item, cloned_item]
+        );
+
+        // Moving a target moves its sources to the new target.
+        let new_container = world.spawn_empty().id();
+        world
+            .entity_mut(container)
+            .move_components::<StoredBy>(new_container);
+        assert!(!world.entity(container).contains::<StoredBy>());
+        assert_eq!(
+            world.entity(new_container).get::<StoredBy>().unwrap().0,
+            &[item]
+        );
+        assert_eq!(
+            world.entity(item).get::<StoredIn>().unwrap().0,
+            &[other_container, new_container]
+        );
+    }
+
+    #[test]
+    fn fragmenting_relationship() {
+        use crate::query::QueryBuilder;
+
+        #[derive(Component)]
+        #[relationship(relationship_target = TeamMembers, fragmenting)]
+        struct OnTeam(Entity);
+
+        #[derive(Component)]
+        #[relationship_target(relationship = OnTeam)]
+        struct TeamMembers(Vec<Entity>);
+
+        #[derive(Component)]
+        #[relationship(relationship_target = TaggedBy, many_to_many, fragmenting)]
+        struct Tagged(Vec<Entity>);
+
+        #[derive(Component)]
+        #[relationship_target(relationship = Tagged, many_to_many)]
+        struct TaggedBy(Vec<Entity>);
+
+        let mut world = World::new();
+        let red = world.spawn_empty().id();
+        let blue = world.spawn_empty().id();
+        let a = world.spawn(OnTeam(red)).id();
+        let b = world.spawn(OnTeam(blue)).id();
+        let c = world.spawn(OnTeam(red)).id();
+        assert_ne!(
+            world.entity(a).archetype().id(),
+            world.entity(b).archetype().id()
+        );
+        assert_eq!(
+            world.entity(a).archetype().id(),
+            world.entity(c).archetype().id()
+        );
+
+        let on_red = world.relationship_pair_id::<OnTeam>(red).unwrap();
+        let on_blue = world.relationship_pair_id::<OnTeam>(blue).unwrap();
+        let mut query = QueryBuilder::<Entity>::new(&mut world)
+            .with_id(on_red)
+            .build();
+        let mut members = query.iter(&world).collect::<Vec<_>>();
+        let mut expected = [a, c];
+        members.sort();
+        expected.sort();
+        assert_eq!(members, expected);
+
+        // Changing the target moves the marker.
+        world.entity_mut(c).insert(OnTeam(blue));
+        assert!(!world.entity(c).contains_id(on_red));
+        assert!(world.entity(c).contains_id(on_blue));
+        world.entity_mut(a).remove::<OnTeam>();
+        assert!(!world.entity(a).contains_id(on_red));
+        assert_eq!(query.iter(&world).count(), 0);
+
+        // Many-to-many sources get a marker for each target.
+        let d = world.spawn(Tagged(alloc::vec![red, blue])).id();
+        let tagged_red = world.relationship_pair_id::<Tagged>(red).unwrap();
+        let tagged_blue = world.relationship_pair_id::<Tagged>(blue).unwrap();
+        assert_ne!(tagged_red, on_red);
+        assert!(world.entity(d).contains_id(tagged_red));
+        assert!(world.entity(d).contains_id(tagged_blue));
+        world.entity_mut(d).remove_many_targets::<Tagged>(&[red]);
+        assert!(!world.entity(d).contains_id(tagged_red));
+        assert!(world.entity(d).contains_id(tagged_blue));
+    }
+
+    #[test]
+    fn related_query_data() {
+        use crate::relationship::{Related, Up};
+        use crate::system::{Query, RunSystemOnce};
+
+        #[derive(Component, PartialEq, Debug)]
+        struct Value(u32);
+
+        let mut world = World::new();
+        let grandparent = world.spawn(Value(1)).id();
+        let parent = world.spawn(ChildOf(grandparent)).id();
+        let child = world.spawn((Value(3), ChildOf(parent))).id();
+        world.spawn(Value(4));
+
+        let mut results = world
+            .run_system_once(|query: Query<(Entity, Related<ChildOf, &Value>)>| {
+                query
+                    .iter()
+                    .map(|(entity, value)| (entity, value.map(|value| value.0)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap();
+        results.sort();
+        let mut expected = [(parent, Some(1)), (child, None)];
+        expected.sort();
+        assert_eq!(results, expected);
+
+        let mut results = world
+            .run_system_once(|query: Query<(Entity, Up<ChildOf, &Value>)>| {
+                query
+                    .iter()
+                    .map(|(entity, value)| (entity, value.map(|value| value.0)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap();
+        results.sort();
+        let mut expected = [(parent, Some(1)), (child, Some(1))];
+        expected.sort();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn related_query_data_conflicts_with_query() {
+        use crate::relationship::Related;
+
+        #[derive(Component)]
+        struct Value;
+
+        let mut world = World::new();
+        world.query::<(&mut Value, Related<ChildOf, &Value>)>();
+    }
+
+    #[test]
+    fn related_query_data_access_ignores_filters() {
+        use crate::{
+            query::{With, Without},
+            relationship::Related,
+        };
+
+        #[derive(Component)]
+        struct Value;
+
+        #[derive(Component)]
+        struct Marker;
+
+        let mut world = World::new();
+        let write = world
+            .query_filtered::<&mut Value, With<Marker>>()
+            .component_access()
+            .clone();
+        let read = world
+            .query_filtered::<&Value, Without<Marker>>()
+            .component_access()
+            .clone();
+        let related = world
+            .query_filtered::<Related<ChildOf, &Value>, Without<Marker>>()
+            .component_access()
+            .clone();
+        assert!(write.is_compatible(&read));
+        assert!(!write.is_compatible(&related));
+        assert!(!related.is_compatible(&write));
+        assert!(read.is_compatible(&related));
+    }
 }
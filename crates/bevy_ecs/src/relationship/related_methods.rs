@@ -3,7 +3,8 @@ use crate::{
     entity::{hash_set::EntityHashSet, Entity},
     prelude::Children,
     relationship::{
-        Relationship, RelationshipHookMode, RelationshipSourceCollection, RelationshipTarget,
+        ManyToManyRelationship, RelatedEntities, Relationship, RelationshipHookMode,
+        RelationshipSourceCollection, RelationshipTarget,
     },
     system::{Commands, EntityCommands},
     world::{DeferredWorld, EntityWorldMut, World},
@@ -296,13 +297,13 @@ impl<'w> EntityWorldMut<'w> {
         self.add_related::<R>(&[entity])
     }
 
-    /// Despawns entities that relate to this one via the given [`RelationshipTarget`].
+    /// Despawns entities that relate to this one via the given [`RelatedEntities`] component, like a [`RelationshipTarget`].
     /// This entity will not be despawned.
-    pub fn despawn_related<S: RelationshipTarget>(&mut self) -> &mut Self {
+    pub fn despawn_related<S: RelatedEntities>(&mut self) -> &mut Self {
         if let Some(sources) = self.get::<S>() {
             // We have to collect here to defer removal, allowing observers and hooks to see this data
             // before it is finally removed.
-            let sources = sources.iter().collect::<Vec<_>>();
+            let sources = sources.iter_related().collect::<Vec<_>>();
             self.world_scope(|world| {
                 for entity in sources {
                     if let Ok(entity_mut) = world.get_entity_mut(entity) {
@@ -332,13 +333,13 @@ impl<'w> EntityWorldMut<'w> {
     /// Any cycles will cause this method to loop infinitely.
     // We could keep track of a list of visited entities and track cycles,
     // but this is not a very well-defined operation (or hard to write) for arbitrary relationships.
-    pub fn insert_recursive<S: RelationshipTarget>(
+    pub fn insert_recursive<S: RelatedEntities>(
         &mut self,
         bundle: impl Bundle + Clone,
     ) -> &mut Self {
         self.insert(bundle.clone());
         if let Some(relationship_target) = self.get::<S>() {
-            let related_vec: Vec<Entity> = relationship_target.iter().collect();
+            let related_vec: Vec<Entity> = relationship_target.iter_related().collect();
             for related in related_vec {
                 self.world_scope(|world| {
                     world
@@ -358,10 +359,10 @@ impl<'w> EntityWorldMut<'w> {
     ///
     /// This method should only be called on relationships that form a tree-like structure.
     /// Any cycles will cause this method to loop infinitely.
-    pub fn remove_recursive<S: RelationshipTarget, B: Bundle>(&mut self) -> &mut Self {
+    pub fn remove_recursive<S: RelatedEntities, B: Bundle>(&mut self) -> &mut Self {
         self.remove::<B>();
         if let Some(relationship_target) = self.get::<S>() {
-            let related_vec: Vec<Entity> = relationship_target.iter().collect();
+            let related_vec: Vec<Entity> = relationship_target.iter_related().collect();
             for related in related_vec {
                 self.world_scope(|world| {
                     world.entity_mut(related).remove_recursive::<S, B>();
@@ -372,6 +373,90 @@ impl<'w> EntityWorldMut<'w> {
         self
     }
 
+    /// Adds the given entities to the targets of the many-to-many relationship `R` on this entity,
+    /// inserting `R` if it is missing.
+    ///
+    /// Entities that are already targets of `R` are ignored.
+    pub fn add_many_targets<R: ManyToManyRelationship>(&mut self, targets: &[Entity]) -> &mut Self {
+        fn add_new<C: RelationshipSourceCollection>(collection: &mut C, targets: &[Entity]) {
+            for target in targets {
+                if !collection.iter().any(|entity| entity == *target) {
+                    collection.add(*target);
+                }
+            }
+        }
+
+        let modified = self
+            .modify_component::<R, _>(|relationship| {
+                add_new(relationship.collection_mut_risky(), targets);
+            })
+            .is_some();
+        if !modified {
+            let mut relationship = R::with_capacity(targets.len());
+            add_new(relationship.collection_mut_risky(), targets);
+            self.insert(relationship);
+        }
+        self
+    }
+
+    /// Removes the given entities from the targets of the many-to-many relationship `R` on this entity.
+    ///
+    /// If no target is left, `R` is removed.
+    pub fn remove_many_targets<R: ManyToManyRelationship>(
+        &mut self,
+        targets: &[Entity],
+    ) -> &mut Self {
+        let is_empty = self.modify_component::<R, _>(|relationship| {
+            let collection = relationship.collection_mut_risky();
+            for target in targets {
+                collection.remove(*target);
+            }
+            collection.is_empty()
+        });
+        if is_empty == Some(true) {
+            self.remove::<R>();
+        }
+        self
+    }
+
+    /// Removes the many-to-many relationship `R` between this entity and all its targets.
+    pub fn clear_many_targets<R: ManyToManyRelationship>(&mut self) -> &mut Self {
+        self.remove::<R>()
+    }
+
+    /// Relates the given entities to this entity with the many-to-many relation `R`,
+    /// adding this entity to their targets.
+    pub fn add_many_related<R: ManyToManyRelationship>(&mut self, related: &[Entity]) -> &mut Self {
+        let id = self.id();
+        self.world_scope(|world| {
+            for related in related {
+                world.entity_mut(*related).add_many_targets::<R>(&[id]);
+            }
+        });
+        self
+    }
+
+    /// Removes the many-to-many relation `R` between this entity and the given entities.
+    pub fn remove_many_related<R: ManyToManyRelationship>(
+        &mut self,
+        related: &[Entity],
+    ) -> &mut Self {
+        let id = self.id();
+        self.world_scope(|world| {
+            for related in related {
+                if let Ok(mut related) = world.get_entity_mut(*related) {
+                    related.remove_many_targets::<R>(&[id]);
+                }
+            }
+        });
+        self
+    }
+
+    /// Removes the many-to-many relation `R` between this entity and all the entities that relate to it.
+    pub fn clear_many_related<R: ManyToManyRelationship>(&mut self) -> &mut Self {
+        self.remove::<R::RelationshipTarget>()
+    }
+
     fn modify_or_insert_relation_with_relationship_hook_mode<R: Relationship>(
         &mut self,
         entity: Entity,
@@ -514,9 +599,71 @@ impl<'a> EntityCommands<'a> {
         })
     }
 
-    /// Despawns entities that relate to this one via the given [`RelationshipTarget`].
+    /// Adds the given entities to the targets of the many-to-many relationship `R` on this entity,
+    /// inserting `R` if it is missing.
+    ///
+    /// Entities that are already targets of `R` are ignored.
+    pub fn add_many_targets<R: ManyToManyRelationship>(&mut self, targets: &[Entity]) -> &mut Self {
+        let targets: Box<[Entity]> = targets.into();
+
+        self.queue(move |mut entity: EntityWorldMut| {
+            entity.add_many_targets::<R>(&targets);
+        })
+    }
+
+    /// Removes the given entities from the targets of the many-to-many relationship `R` on this entity.
+    ///
+    /// If no target is left, `R` is removed.
+    pub fn remove_many_targets<R: ManyToManyRelationship>(
+        &mut self,
+        targets: &[Entity],
+    ) -> &mut Self {
+        let targets: Box<[Entity]> = targets.into();
+
+        self.queue(move |mut entity: EntityWorldMut| {
+            entity.remove_many_targets::<R>(&targets);
+        })
+    }
+
+    /// Removes the many-to-many relationship `R` between this entity and all its targets.
+    pub fn clear_many_targets<R: ManyToManyRelationship>(&mut self) -> &mut Self {
+        self.queue(|mut entity: EntityWorldMut| {
+            entity.clear_many_targets::<R>();
+        })
+    }
+
+    /// Relates the given entities to this entity with the many-to-many relation `R`,
+    /// adding this entity to their targets.
+    pub fn add_many_related<R: ManyToManyRelationship>(&mut self, related: &[Entity]) -> &mut Self {
+        let related: Box<[Entity]> = related.into();
+
+        self.queue(move |mut entity: EntityWorldMut| {
+            entity.add_many_related::<R>(&related);
+        })
+    }
+
+    /// Removes the many-to-many relation `R` between this entity and the given entities.
+    pub fn remove_many_related<R: ManyToManyRelationship>(
+        &mut self,
+        related: &[Entity],
+    ) -> &mut Self {
+        let related: Box<[Entity]> = related.into();
+
+        self.queue(move |mut entity: EntityWorldMut| {
+            entity.remove_many_related::<R>(&related);
+        })
+    }
+
+    /// Removes the many-to-many relation `R` between this entity and all the entities that relate to it.
+    pub fn clear_many_related<R: ManyToManyRelationship>(&mut self) -> &mut Self {
+        self.queue(|mut entity: EntityWorldMut| {
+            entity.clear_many_related::<R>();
+        })
+    }
+
+    /// Despawns entities that relate to this one via the given [`RelatedEntities`] component, like a [`RelationshipTarget`].
     /// This entity will not be despawned.
-    pub fn despawn_related<S: RelationshipTarget>(&mut self) -> &mut Self {
+    pub fn despawn_related<S: RelatedEntities>(&mut self) -> &mut Self {
         self.queue(move |mut entity: EntityWorldMut| {
             entity.despawn_related::<S>();
         })
@@ -537,7 +684,7 @@ impl<'a> EntityCommands<'a> {
     ///
     /// This method should only be called on relationships that form a tree-like structure.
     /// Any cycles will cause this method to loop infinitely.
-    pub fn insert_recursive<S: RelationshipTarget>(
+    pub fn insert_recursive<S: RelatedEntities>(
         &mut self,
         bundle: impl Bundle + Clone,
     ) -> &mut Self {
@@ -553,7 +700,7 @@ impl<'a> EntityCommands<'a> {
     ///
     /// This method should only be called on relationships that form a tree-like structure.
     /// Any cycles will cause this method to loop infinitely.
-    pub fn remove_recursive<S: RelationshipTarget, B: Bundle>(&mut self) -> &mut Self {
+    pub fn remove_recursive<S: RelatedEntities, B: Bundle>(&mut self) -> &mut Self {
         self.queue(move |mut entity: EntityWorldMut| {
             entity.remove_recursive::<S, B>();
         })
@@ -0,0 +1,395 @@
+use core::marker::PhantomData;
+
+use crate::{
+    archetype::Archetype,
+    component::{ComponentId, Components, Tick},
+    entity::Entity,
+    query::{FilteredAccess, QueryData, ReadOnlyQueryData, ReleaseStateQueryData, WorldQuery},
+    relationship::Relationship,
+    storage::{Table, TableRow},
+    world::{unsafe_world_cell::UnsafeWorldCell, World},
+};
+
+/// [`QueryData`] that fetches `D` from the entity targeted by the `R` [`Relationship`] of the queried entity.
+///
+/// This only matches entities that have the `R` component. The item is `None` if the target entity
+/// doesn't exist or doesn't match `D`.
+///
+/// The data accessed on the target entity isn't restricted by the filters of the query,
+/// so systems using `Related` conflict with any system that writes to the components read by `D`,
+/// even if their filters are disjoint.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::relationship::Related;
+/// #[derive(Component)]
+/// struct Velocity(f32);
+///
+/// #[derive(Component)]
+/// struct Position(f32);
+///
+/// fn follow_parent(mut query: Query<(&mut Position, Related<ChildOf, &Velocity>)>) {
+///     for (mut position, parent_velocity) in &mut query {
+///         if let Some(velocity) = parent_velocity {
+///             position.0 += velocity.0;
+///         }
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(follow_parent);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `D` accesses components that conflict with the other accesses of the query, such as
+/// `(&mut Velocity, Related<ChildOf, &Velocity>)`, since the target could be one of the queried entities.
+pub struct Related<R: Relationship, D: ReadOnlyQueryData>(PhantomData<(R, D)>);
+
+/// [`QueryData`] that walks up the `R` [`Relationship`] of the queried entity, fetching `D` from the
+/// first ancestor that matches it.
+///
+/// This only matches entities that have the `R` component. The queried entity itself is not considered,
+/// and the item is `None` if no ancestor matches `D`.
+///
+/// Like [`Related`], the data accessed on the ancestors isn't restricted by the filters of the query.
+///
+/// # Warning
+///
+/// For relationship graphs that contain loops, this could loop infinitely.
+/// If your relationship is not a tree (like Bevy's hierarchy), make sure that a matching entity is always reached.
+///
+/// # Panics
+///
+/// Panics if `D` or `R` accesses components that conflict with the other accesses of the query.
+pub struct Up<R: Relationship, D: ReadOnlyQueryData>(PhantomData<(R, D)>);
+
+#[doc(hidden)]
+pub struct RelatedFetch<'w, R: Relationship, D: ReadOnlyQueryData> {
+    relationship: <&'static R as WorldQuery>::Fetch<'w>,
+    data: D::Fetch<'w>,
+    world: UnsafeWorldCell<'w>,
+}
+
+impl<R: Relationship, D: ReadOnlyQueryData> Clone for RelatedFetch<'_, R, D> {
+    fn clone(&self) -> Self {
+        Self {
+            relationship: self.relationship,
+            data: self.data.clone(),
+            world: self.world,
+        }
+    }
+}
+
+impl<'w, R: Relationship, D: ReadOnlyQueryData> RelatedFetch<'w, R, D> {
+    /// # Safety
+    ///
+    /// `world` must have permission to read the components accessed by `D` on every entity.
+    unsafe fn init<'s>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s (ComponentId, D::State),
+        last_run: Tick,
+        this_run: Tick,
+    ) -> Self {
+        Self {
+            // SAFETY: The invariants are upheld by the caller.
+            relationship: unsafe {
+                <&R as WorldQuery>::init_fetch(world, &state.0, last_run, this_run)
+            },
+            // SAFETY: The invariants are upheld by the caller.
+            data: unsafe { D::init_fetch(world, &state.1, last_run, this_run) },
+            world,
+        }
+    }
+
+    /// Returns the target of the `R` component of `entity`, which must be in the current archetype or table.
+    ///
+    /// # Safety
+    ///
+    /// Must uphold the invariants of [`QueryData::fetch`] for `&R`.
+    unsafe fn target<'s>(
+        &mut self,
+        state: &'s (ComponentId, D::State),
+        entity: Entity,
+        table_row: TableRow,
+    ) -> Entity {
+        // SAFETY: The invariants are upheld by the caller.
+        unsafe { <&R as QueryData>::fetch(&state.0, &mut self.relationship, entity, table_row) }
+            .get()
+    }
+
+    /// Fetches `D` from `entity`, if it exists and matches `D`.
+    ///
+    /// # Safety
+    ///
+    /// `self.world` must have permission to read the components accessed by `D` on `entity`.
+    unsafe fn fetch_data<'s>(
+        &mut self,
+        state: &'s D::State,
+        entity: Entity,
+    ) -> Option<D::Item<'w, 's>> {
+        let location = self.world.entities().get(entity)?;
+        let archetype = self.world.archetypes().get(location.archetype_id)?;
+        if !D::matches_component_set(state, &|id| archetype.contains(id)) {
+            return None;
+        }
+        // SAFETY: Only the table storage metadata is read here.
+        let table = unsafe { self.world.storages() }
+            .tables
+            .get(location.table_id)?;
+        // SAFETY:
+        // - `archetype` and `table` belong to `entity`, which matches `D`.
+        // - Read access to the components of `D` is upheld by the caller.
+        unsafe {
+            D::set_archetype(&mut self.data, state, archetype, table);
+            Some(D::fetch(state, &mut self.data, entity, location.table_row))
+        }
+    }
+}
+
+/// Adds the access of `R` to `access`, and the access of `data_access` as related access.
+fn update_related_access<R: Relationship>(
+    state: &ComponentId,
+    data_access: &FilteredAccess,
+    access: &mut FilteredAccess,
+    name: &str,
+) {
+    <&R as WorldQuery>::update_component_access(state, access);
+    assert!(
+        data_access
+            .access()
+            .is_components_compatible(access.access()),
+        "{name} conflicts with a previous access in this query. Data fetched from related entities cannot coincide with exclusive access.",
+    );
+    access.add_related_access(data_access.access());
+}
+
+/// SAFETY:
+/// `fetch` reads `R` on the queried entity, and the components accessed by `D` on its target.
+/// This is sound because `update_component_access` adds a read of `R`, and the access of `D` as related access.
+/// `D` is read-only, and panics if its access conflicts with the rest of the query.
+/// This only matches entities that have `R`, which is added as a filter.
+unsafe impl<R: Relationship, D: ReadOnlyQueryData> WorldQuery for Related<R, D> {
+    type Fetch<'w> = RelatedFetch<'w, R, D>;
+    type State = (ComponentId, D::State);
+
+    fn shrink_fetch<'wlong: 'wshort, 'wshort>(fetch: Self::Fetch<'wlong>) -> Self::Fetch<'wshort> {
+        RelatedFetch {
+            relationship: <&R as WorldQuery>::shrink_fetch(fetch.relationship),
+            data: D::shrink_fetch(fetch.data),
+            world: fetch.world,
+        }
+    }
+
+    #[inline]
+    unsafe fn init_fetch<'w, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s Self::State,
+        last_run: Tick,
+        this_run: Tick,
+    ) -> Self::Fetch<'w> {
+        // SAFETY: The invariants are upheld by the caller.
+        unsafe { RelatedFetch::init(world, state, last_run, this_run) }
+    }
+
+    const IS_DENSE: bool = <&R as WorldQuery>::IS_DENSE;
+
+    #[inline]
+    unsafe fn set_archetype<'w, 's>(
+        fetch: &mut Self::Fetch<'w>,
+        state: &'s Self::State,
+        archetype: &'w Archetype,
+        table: &'w Table,
+    ) {
+        // SAFETY: The invariants are upheld by the caller.
+        unsafe {
+            <&R as WorldQuery>::set_archetype(&mut fetch.relationship, &state.0, archetype, table);
+        }
+    }
+
+    #[inline]
+    unsafe fn set_table<'w, 's>(
+        fetch: &mut Self::Fetch<'w>,
+        state: &'s Self::State,
+        table: &'w Table,
+    ) {
+        // SAFETY: The invariants are upheld by the caller.
+        unsafe { <&R as WorldQuery>::set_table(&mut fetch.relationship, &state.0, table) }
+    }
+
+    fn update_component_access(state: &Self::State, access: &mut FilteredAccess) {
+        let mut data_access = FilteredAccess::matches_everything();
+        D::update_component_access(&state.1, &mut data_access);
+        update_related_access::<R>(&state.0, &data_access, access, "Related");
+    }
+
+    fn init_state(world: &mut World) -> Self::State {
+        (<&R as WorldQuery>::init_state(world), D::init_state(world))
+    }
+
+    fn get_state(components: &Components) -> Option<Self::State> {
+        Some((
+            <&R as WorldQuery>::get_state(components)?,
+            D::get_state(components)?,
+        ))
+    }
+
+    fn matches_component_set(
+        state: &Self::State,
+        set_contains_id: &impl Fn(ComponentId) -> bool,
+    ) -> bool {
+        set_contains_id(state.0)
+    }
+}
+
+// SAFETY: `Self` is the same as `Self::ReadOnly`
+unsafe impl<R: Relationship, D: ReadOnlyQueryData> QueryData for Related<R, D> {
+    const IS_READ_ONLY: bool = true;
+    type ReadOnly = Self;
+    type Item<'w, 's> = Option<D::Item<'w, 's>>;
+
+    fn shrink<'wlong: 'wshort, 'wshort, 's>(
+        item: Self::Item<'wlong, 's>,
+    ) -> Self::Item<'wshort, 's> {
+        item.map(D::shrink)
+    }
+
+    #[inline(always)]
+    unsafe fn fetch<'w, 's>(
+        state: &'s Self::State,
+        fetch: &mut Self::Fetch<'w>,
+        entity: Entity,
+        table_row: TableRow,
+    ) -> Self::Item<'w, 's> {
+        // SAFETY: The invariants are upheld by the caller.
+        let target = unsafe { fetch.target(state, entity, table_row) };
+        // SAFETY: The access of `D` was added as related access in `update_component_access`.
+        unsafe { fetch.fetch_data(&state.1, target) }
+    }
+}
+
+/// SAFETY: `Related` only reads `R`, and `D` is read only
+unsafe impl<R: Relationship, D: ReadOnlyQueryData> ReadOnlyQueryData for Related<R, D> {}
+
+impl<R: Relationship, D: ReadOnlyQueryData + ReleaseStateQueryData> ReleaseStateQueryData
+    for Related<R, D>
+{
+    fn release_state<'w>(item: Self::Item<'w, '_>) -> Self::Item<'w, 'static> {
+        item.map(D::release_state)
+    }
+}
+
+/// SAFETY:
+/// `fetch` reads `R` on the queried entity and its ancestors, and the components accessed by `D` on its ancestors.
+/// This is sound because `update_component_access` adds a read of `R`, and the access of `D` and a read of `R`
+/// as related access.
+/// `D` is read-only, and panics if its access conflicts with the rest of the query.
+/// This only matches entities that have `R`, which is added as a filter.
+unsafe impl<R: Relationship, D: ReadOnlyQueryData> WorldQuery for Up<R, D> {
+    type Fetch<'w> = RelatedFetch<'w, R, D>;
+    type State = (ComponentId, D::State);
+
+    fn shrink_fetch<'wlong: 'wshort, 'wshort>(fetch: Self::Fetch<'wlong>) -> Self::Fetch<'wshort> {
+        <Related<R, D> as WorldQuery>::shrink_fetch(fetch)
+    }
+
+    #[inline]
+    unsafe fn init_fetch<'w, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s Self::State,
+        last_run: Tick,
+        this_run: Tick,
+    ) -> Self::Fetch<'w> {
+        // SAFETY: The invariants are upheld by the caller.
+        unsafe { RelatedFetch::init(world, state, last_run, this_run) }
+    }
+
+    const IS_DENSE: bool = <&R as WorldQuery>::IS_DENSE;
+
+    #[inline]
+    unsafe fn set_archetype<'w, 's>(
+        fetch: &mut Self::Fetch<'w>,
+        state: &'s Self::State,
+        archetype: &'w Archetype,
+        table: &'w Table,
+    ) {
+        // SAFETY: The invariants are upheld by the caller.
+        unsafe { <Related<R, D> as WorldQuery>::set_archetype(fetch, state, archetype, table) }
+    }
+
+    #[inline]
+    unsafe fn set_table<'w, 's>(
+        fetch: &mut Self::Fetch<'w>,
+        state: &'s Self::State,
+        table: &'w Table,
+    ) {
+        // SAFETY: The invariants are upheld by the caller.
+        unsafe { <Related<R, D> as WorldQuery>::set_table(fetch, state, table) }
+    }
+
+    fn update_component_access(state: &Self::State, access: &mut FilteredAccess) {
+        let mut data_access = FilteredAccess::matches_everything();
+        D::update_component_access(&state.1, &mut data_access);
+        data_access.add_component_read(state.0);
+        update_related_access::<R>(&state.0, &data_access, access, "Up");
+    }
+
+    fn init_state(world: &mut World) -> Self::State {
+        <Related<R, D> as WorldQuery>::init_state(world)
+    }
+
+    fn get_state(components: &Components) -> Option<Self::State> {
+        <Related<R, D> as WorldQuery>::get_state(components)
+    }
+
+    fn matches_component_set(
+        state: &Self::State,
+        set_contains_id: &impl Fn(ComponentId) -> bool,
+    ) -> bool {
+        set_contains_id(state.0)
+    }
+}
+
+// SAFETY: `Self` is the same as `Self::ReadOnly`
+unsafe impl<R: Relationship, D: ReadOnlyQueryData> QueryData for Up<R, D> {
+    const IS_READ_ONLY: bool = true;
+    type ReadOnly = Self;
+    type Item<'w, 's> = Option<D::Item<'w, 's>>;
+
+    fn shrink<'wlong: 'wshort, 'wshort, 's>(
+        item: Self::Item<'wlong, 's>,
+    ) -> Self::Item<'wshort, 's> {
+        item.map(D::shrink)
+    }
+
+    #[inline(always)]
+    unsafe fn fetch<'w, 's>(
+        state: &'s Self::State,
+        fetch: &mut Self::Fetch<'w>,
+        entity: Entity,
+        table_row: TableRow,
+    ) -> Self::Item<'w, 's> {
+        // SAFETY: The invariants are upheld by the caller.
+        let mut ancestor = unsafe { fetch.target(state, entity, table_row) };
+        loop {
+            // SAFETY: The access of `D` was added as related access in `update_component_access`.
+            if let Some(item) = unsafe { fetch.fetch_data(&state.1, ancestor) } {
+                return Some(item);
+            }
+            let cell = fetch.world.get_entity(ancestor).ok()?;
+            // SAFETY: A read of `R` was added as related access in `update_component_access`.
+            ancestor = unsafe { cell.get::<R>() }?.get();
+        }
+    }
+}
+
+/// SAFETY: `Up` only reads `R`, and `D` is read only
+unsafe impl<R: Relationship, D: ReadOnlyQueryData> ReadOnlyQueryData for Up<R, D> {}
+
+impl<R: Relationship, D: ReadOnlyQueryData + ReleaseStateQueryData> ReleaseStateQueryData
+    for Up<R, D>
+{
+    fn release_state<'w>(item: Self::Item<'w, '_>) -> Self::Item<'w, 'static> {
+        item.map(D::release_state)
+    }
+}
@@ -1,13 +1,13 @@
 use crate::{
-    entity::Entity,
+    entity::{Entity, EntityHashSet},
     query::{QueryData, QueryFilter},
-    relationship::{Relationship, RelationshipTarget},
+    relationship::{RelatedEntities, Relationship, RelationshipTarget},
     system::Query,
 };
 use alloc::collections::VecDeque;
 use smallvec::SmallVec;
 
-use super::SourceIter;
+use super::RelatedIter;
 
 impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
     /// If the given `entity` contains the `R` [`Relationship`] component, returns the
@@ -19,9 +19,10 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
         self.get(entity).map(R::get).ok()
     }
 
-    /// If the given `entity` contains the `S` [`RelationshipTarget`] component, returns the
-    /// source entities stored on that component.
-    pub fn relationship_sources<S: RelationshipTarget>(
+    /// If the given `entity` contains the `S` [`RelatedEntities`] component, like a
+    /// [`RelationshipTarget`], returns the
+    /// entities stored on that component.
+    pub fn relationship_sources<S: RelatedEntities>(
         &'w self,
         entity: Entity,
     ) -> impl Iterator<Item = Entity> + 'w
@@ -30,7 +31,7 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
     {
         self.get(entity)
             .into_iter()
-            .flat_map(RelationshipTarget::iter)
+            .flat_map(RelatedEntities::iter_related)
     }
 
     /// Recursively walks up the tree defined by the given `R` [`Relationship`] until
@@ -51,24 +52,21 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
         }
     }
 
-    /// Iterates all "leaf entities" as defined by the [`RelationshipTarget`] hierarchy.
+    /// Iterates all "leaf entities" as defined by the [`RelatedEntities`] hierarchy, like a [`RelationshipTarget`].
     ///
-    /// # Warning
-    ///
-    /// For relationship graphs that contain loops, this could loop infinitely.
-    /// If your relationship is not a tree (like Bevy's hierarchy), be sure to stop if you encounter a duplicate entity.
-    pub fn iter_leaves<S: RelationshipTarget>(
+    /// Like [`Query::iter_descendants_depth_first`], each leaf is returned once.
+    pub fn iter_leaves<S: RelatedEntities>(
         &'w self,
         entity: Entity,
     ) -> impl Iterator<Item = Entity> + use<'w, 's, S, D, F>
     where
         <D as QueryData>::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
-        SourceIter<'w, S>: DoubleEndedIterator,
+        RelatedIter<'w, S>: DoubleEndedIterator,
     {
         self.iter_descendants_depth_first(entity).filter(|entity| {
             self.get(*entity)
                 // These are leaf nodes if they have the `Children` component but it's empty
-                .map(|children| children.len() == 0)
+                .map(|children| children.iter_related().next().is_none())
                 // Or if they don't have the `Children` component at all
                 .unwrap_or(true)
         })
@@ -91,14 +89,15 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
             .flat_map(move |children| children.iter().filter(move |child| *child != entity))
     }
 
-    /// Iterates all descendant entities as defined by the given `entity`'s [`RelationshipTarget`] and their recursive
-    /// [`RelationshipTarget`].
+    /// Iterates all descendant entities as defined by the given `entity`'s [`RelatedEntities`] component, like a
+    /// [`RelationshipTarget`], and their recursive [`RelatedEntities`].
     ///
-    /// # Warning
+    /// For a [`ManyToManyRelationship`](crate::relationship::ManyToManyRelationship), this can walk the graph
+    /// in either direction, using either side of the relationship as `S`.
     ///
-    /// For relationship graphs that contain loops, this could loop infinitely.
-    /// If your relationship is not a tree (like Bevy's hierarchy), be sure to stop if you encounter a duplicate entity.
-    pub fn iter_descendants<S: RelationshipTarget>(
+    /// Each descendant is returned once, even if it's reachable from several entities, and the walk stops at
+    /// entities that were already visited, so graphs with loops are fine.
+    pub fn iter_descendants<S: RelatedEntities>(
         &'w self,
         entity: Entity,
     ) -> DescendantIter<'w, 's, D, F, S>
@@ -108,20 +107,18 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
         DescendantIter::new(self, entity)
     }
 
-    /// Iterates all descendant entities as defined by the given `entity`'s [`RelationshipTarget`] and their recursive
-    /// [`RelationshipTarget`] in depth-first order.
+    /// Iterates all descendant entities as defined by the given `entity`'s [`RelatedEntities`] component, like a
+    /// [`RelationshipTarget`], and their recursive [`RelatedEntities`] in depth-first order.
     ///
-    /// # Warning
-    ///
-    /// For relationship graphs that contain loops, this could loop infinitely.
-    /// If your relationship is not a tree (like Bevy's hierarchy), be sure to stop if you encounter a duplicate entity.
-    pub fn iter_descendants_depth_first<S: RelationshipTarget>(
+    /// Each descendant is returned once, even if it's reachable from several entities, and the walk stops at
+    /// entities that were already visited, so graphs with loops are fine.
+    pub fn iter_descendants_depth_first<S: RelatedEntities>(
         &'w self,
         entity: Entity,
     ) -> DescendantDepthFirstIter<'w, 's, D, F, S>
     where
         D::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
-        SourceIter<'w, S>: DoubleEndedIterator,
+        RelatedIter<'w, S>: DoubleEndedIterator,
     {
         DescendantDepthFirstIter::new(self, entity)
     }
@@ -146,32 +143,44 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
 /// An [`Iterator`] of [`Entity`]s over the descendants of an [`Entity`].
 ///
 /// Traverses the hierarchy breadth-first.
-pub struct DescendantIter<'w, 's, D: QueryData, F: QueryFilter, S: RelationshipTarget>
+pub struct DescendantIter<'w, 's, D: QueryData, F: QueryFilter, S: RelatedEntities>
 where
     D::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
 {
     children_query: &'w Query<'w, 's, D, F>,
     vecdeque: VecDeque<Entity>,
+    /// The entities that were queued so far, so that each is returned once.
+    visited: EntityHashSet,
 }
 
-impl<'w, 's, D: QueryData, F: QueryFilter, S: RelationshipTarget> DescendantIter<'w, 's, D, F, S>
+impl<'w, 's, D: QueryData, F: QueryFilter, S: RelatedEntities> DescendantIter<'w, 's, D, F, S>
 where
     D::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
 {
     /// Returns a new [`DescendantIter`].
     pub fn new(children_query: &'w Query<'w, 's, D, F>, entity: Entity) -> Self {
-        DescendantIter {
+        let mut iter = DescendantIter {
             children_query,
-            vecdeque: children_query
-                .get(entity)
-                .into_iter()
-                .flat_map(RelationshipTarget::iter)
-                .collect(),
+            vecdeque: VecDeque::new(),
+            visited: EntityHashSet::default(),
+        };
+        iter.visited.insert(entity);
+        iter.queue_children(entity);
+        iter
+    }
+
+    fn queue_children(&mut self, entity: Entity) {
+        if let Ok(children) = self.children_query.get(entity) {
+            for child in children.iter_related() {
+                if self.visited.insert(child) {
+                    self.vecdeque.push_back(child);
+                }
+            }
         }
     }
 }
 
-impl<'w, 's, D: QueryData, F: QueryFilter, S: RelationshipTarget> Iterator
+impl<'w, 's, D: QueryData, F: QueryFilter, S: RelatedEntities> Iterator
     for DescendantIter<'w, 's, D, F, S>
 where
     D::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
@@ -180,11 +189,7 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         let entity = self.vecdeque.pop_front()?;
-
-        if let Ok(children) = self.children_query.get(entity) {
-            self.vecdeque.extend(children.iter());
-        }
-
+        self.queue_children(entity);
         Some(entity)
     }
 }
@@ -192,19 +197,21 @@ where
 /// An [`Iterator`] of [`Entity`]s over the descendants of an [`Entity`].
 ///
 /// Traverses the hierarchy depth-first.
-pub struct DescendantDepthFirstIter<'w, 's, D: QueryData, F: QueryFilter, S: RelationshipTarget>
+pub struct DescendantDepthFirstIter<'w, 's, D: QueryData, F: QueryFilter, S: RelatedEntities>
 where
     D::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
 {
     children_query: &'w Query<'w, 's, D, F>,
     stack: SmallVec<[Entity; 8]>,
+    /// The entities that were returned so far, so that each is returned once.
+    visited: EntityHashSet,
 }
 
-impl<'w, 's, D: QueryData, F: QueryFilter, S: RelationshipTarget>
+impl<'w, 's, D: QueryData, F: QueryFilter, S: RelatedEntities>
     DescendantDepthFirstIter<'w, 's, D, F, S>
 where
     D::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
-    SourceIter<'w, S>: DoubleEndedIterator,
+    RelatedIter<'w, S>: DoubleEndedIterator,
 {
     /// Returns a new [`DescendantDepthFirstIter`].
     pub fn new(children_query: &'w Query<'w, 's, D, F>, entity: Entity) -> Self {
@@ -212,24 +219,37 @@ where
             children_query,
             stack: children_query
                 .get(entity)
-                .map_or(SmallVec::new(), |children| children.iter().rev().collect()),
+                .map_or(SmallVec::new(), |children| {
+                    children.iter_related().rev().collect()
+                }),
+            visited: EntityHashSet::from_iter([entity]),
         }
     }
 }
 
-impl<'w, 's, D: QueryData, F: QueryFilter, S: RelationshipTarget> Iterator
+impl<'w, 's, D: QueryData, F: QueryFilter, S: RelatedEntities> Iterator
     for DescendantDepthFirstIter<'w, 's, D, F, S>
 where
     D::ReadOnly: QueryData<Item<'w, 's> = &'w S>,
-    SourceIter<'w, S>: DoubleEndedIterator,
+    RelatedIter<'w, S>: DoubleEndedIterator,
 {
     type Item = Entity;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let entity = self.stack.pop()?;
+        let entity = loop {
+            let entity = self.stack.pop()?;
+            if self.visited.insert(entity) {
+                break entity;
+            }
+        };
 
         if let Ok(children) = self.children_query.get(entity) {
-            self.stack.extend(children.iter().rev());
+            self.stack.extend(
+                children
+                    .iter_related()
+                    .rev()
+                    .filter(|child| !self.visited.contains(child)),
+            );
         }
 
         Some(entity)
@@ -0,0 +1,513 @@
+//! Provides checkpoints of a [`World`] that can be restored later, for rollback networking.
+//!
+//! Components and resources are opted into rollback with [`World::register_rollback_component`]
+//! and [`World::register_rollback_resource`]. A checkpoint is saved with [`World::save_checkpoint`],
+//! which stores a [`Clone`] of every registered component and resource along with the state of the
+//! entity allocator, keyed by the current change [`Tick`] of the world.
+//!
+//! [`World::restore_checkpoint`] rewinds the world to a saved checkpoint:
+//! - Entities spawned after the checkpoint are despawned.
+//! - Entities despawned after the checkpoint are spawned again, with the same [`Entity`] IDs.
+//! - The registered components and resources are restored to their saved values.
+//! - Entities spawned after restoring are given the same IDs as the ones spawned after the checkpoint was saved.
+//!
+//! Components and resources that were not registered are left as they are. Note that entities
+//! despawned after the checkpoint are spawned again with only the registered components.
+//!
+//! [`Internal`] entities, like observers and registered systems, are not rolled back: they are neither
+//! despawned nor spawned again, and keep their IDs. If one of them was spawned with the ID of an entity
+//! despawned after the checkpoint, that entity isn't spawned again.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! #[derive(Component, Clone)]
+//! struct Position(f32);
+//!
+//! let mut world = World::new();
+//! world.register_rollback_component::<Position>();
+//! let player = world.spawn(Position(0.0)).id();
+//! let checkpoint = world.save_checkpoint();
+//!
+//! // Predict a few frames ahead.
+//! world.get_mut::<Position>(player).unwrap().0 += 1.0;
+//! let bullet = world.spawn(Position(2.0)).id();
+//!
+//! // A correction arrives from the server, rewind and simulate again.
+//! world.restore_checkpoint(checkpoint).unwrap();
+//! assert_eq!(world.get::<Position>(player).unwrap().0, 0.0);
+//! assert!(world.get_entity(bullet).is_err());
+//! assert_eq!(world.spawn(Position(3.0)).id(), bullet);
+//! ```
+
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+use core::any::{Any, TypeId};
+
+use bevy_utils::TypeIdMap;
+use thiserror::Error;
+
+use crate::{
+    archetype::{Archetype, ArchetypeEntity},
+    change_detection::{MaybeLocation, Mut},
+    component::{Component, Tick},
+    entity::{hash_set::EntityHashSet, EntitiesSnapshot, Entity},
+    entity_disabling::Internal,
+    resource::Resource,
+    world::World,
+};
+
+/// The default value of [`RollbackCheckpoints::max_checkpoints`].
+pub const DEFAULT_MAX_CHECKPOINTS: usize = 16;
+
+type SavedData = Box<dyn Any + Send + Sync>;
+
+/// Saves and restores a single registered component or resource type.
+#[derive(Clone, Copy)]
+struct RollbackFns {
+    save: fn(&World) -> SavedData,
+    restore: fn(&mut World, &SavedData),
+}
+
+struct Checkpoint {
+    tick: Tick,
+    entities: EntitiesSnapshot,
+    /// The [`Internal`] entities alive when the checkpoint was saved, which are not spawned again.
+    internal: EntityHashSet,
+    data: Vec<SavedData>,
+}
+
+/// Returns the entities of the archetypes that match `filter`.
+fn entities_where(world: &World, filter: impl Fn(&Archetype) -> bool) -> Vec<Entity> {
+    world
+        .archetypes()
+        .iter()
+        .filter(|archetype| filter(archetype))
+        .flat_map(Archetype::entities)
+        .map(ArchetypeEntity::id)
+        .collect()
+}
+
+/// Stores the component and resource types registered for rollback, and the checkpoints saved
+/// with [`World::save_checkpoint`].
+///
+/// See the [module docs](crate::rollback) for more information.
+#[derive(Resource)]
+pub struct RollbackCheckpoints {
+    registered: TypeIdMap<()>,
+    fns: Vec<RollbackFns>,
+    checkpoints: VecDeque<Checkpoint>,
+    max_checkpoints: usize,
+}
+
+impl Default for RollbackCheckpoints {
+    fn default() -> Self {
+        Self {
+            registered: TypeIdMap::default(),
+            fns: Vec::new(),
+            checkpoints: VecDeque::new(),
+            max_checkpoints: DEFAULT_MAX_CHECKPOINTS,
+        }
+    }
+}
+
+impl RollbackCheckpoints {
+    /// Returns the maximum number of checkpoints that are kept.
+    /// When a new checkpoint is saved, the oldest ones are dropped to stay under this limit.
+    pub fn max_checkpoints(&self) -> usize {
+        self.max_checkpoints
+    }
+
+    /// Sets the maximum number of checkpoints that are kept, dropping the oldest ones if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_checkpoints` is zero.
+    pub fn set_max_checkpoints(&mut self, max_checkpoints: usize) {
+        assert!(max_checkpoints > 0, "at least one checkpoint must be kept");
+        self.max_checkpoints = max_checkpoints;
+        self.truncate_oldest();
+    }
+
+    /// Returns the ticks of the saved checkpoints, from oldest to newest.
+    pub fn ticks(&self) -> impl Iterator<Item = Tick> + '_ {
+        self.checkpoints.iter().map(|checkpoint| checkpoint.tick)
+    }
+
+    /// Returns true if a checkpoint was saved at `tick`.
+    pub fn contains(&self, tick: Tick) -> bool {
+        self.position(tick).is_some()
+    }
+
+    /// Returns the number of saved checkpoints.
+    pub fn len(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Returns true if no checkpoints are saved.
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    /// Drops all saved checkpoints, keeping the registered types.
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    fn register(&mut self, type_id: TypeId, fns: RollbackFns) {
+        if self.registered.insert(type_id, ()).is_none() {
+            self.fns.push(fns);
+        }
+    }
+
+    fn position(&self, tick: Tick) -> Option<usize> {
+        self.checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.tick == tick)
+    }
+
+    fn truncate_oldest(&mut self) {
+        while self.checkpoints.len() > self.max_checkpoints {
+            self.checkpoints.pop_front();
+        }
+    }
+}
+
+/// An error returned by [`World::restore_checkpoint`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("No rollback checkpoint was saved at {0:?}")]
+pub struct CheckpointNotFoundError(pub Tick);
+
+fn save_component<C: Component + Clone>(world: &World) -> SavedData {
+    let mut saved = Vec::<(Entity, C)>::new();
+    if let Some(id) = world.component_id::<C>() {
+        for archetype in world
+            .archetypes()
+            .iter()
+            .filter(|archetype| archetype.contains(id))
+        {
+            saved.extend(archetype.entities().iter().filter_map(|archetype_entity| {
+                let entity = archetype_entity.id();
+                let component = world.entity(entity).get::<C>()?;
+                Some((entity, component.clone()))
+            }));
+        }
+    }
+    Box::new(saved)
+}
+
+fn restore_component<C: Component + Clone>(world: &mut World, saved: &SavedData) {
+    let Some(saved) = saved.downcast_ref::<Vec<(Entity, C)>>() else {
+        return;
+    };
+    if let Some(id) = world.component_id::<C>() {
+        let saved_entities = saved
+            .iter()
+            .map(|(entity, _)| *entity)
+            .collect::<EntityHashSet>();
+        let added = world
+            .archetypes()
+            .iter()
+            .filter(|archetype| archetype.contains(id))
+            .flat_map(Archetype::entities)
+            .map(ArchetypeEntity::id)
+            .filter(|entity| !saved_entities.contains(entity))
+            .collect::<Vec<_>>();
+        for entity in added {
+            if let Ok(mut entity) = world.get_entity_mut(entity) {
+                entity.remove::<C>();
+            }
+        }
+    }
+    for (entity, component) in saved {
+        if let Ok(mut entity) = world.get_entity_mut(*entity) {
+            entity.insert(component.clone());
+        }
+    }
+}
+
+fn save_resource<R: Resource + Clone>(world: &World) -> SavedData {
+    Box::new(world.get_resource::<R>().cloned())
+}
+
+fn restore_resource<R: Resource + Clone>(world: &mut World, saved: &SavedData) {
+    match saved.downcast_ref::<Option<R>>() {
+        Some(Some(resource)) => world.insert_resource(resource.clone()),
+        Some(None) => {
+            world.remove_resource::<R>();
+        }
+        None => {}
+    }
+}
+
+impl World {
+    /// Registers the component `C` for rollback, so that its values are saved by [`World::save_checkpoint`]
+    /// and restored by [`World::restore_checkpoint`].
+    ///
+    /// Checkpoints saved before registering `C` don't affect it.
+    /// This method is idempotent.
+    ///
+    /// See the [module docs](crate::rollback) for more information.
+    pub fn register_rollback_component<C: Component + Clone>(&mut self) -> &mut Self {
+        self.register_component::<C>();
+        self.get_resource_or_init::<RollbackCheckpoints>().register(
+            TypeId::of::<C>(),
+            RollbackFns {
+                save: save_component::<C>,
+                restore: restore_component::<C>,
+            },
+        );
+        self
+    }
+
+    /// Registers the resource `R` for rollback, so that its value is saved by [`World::save_checkpoint`]
+    /// and restored by [`World::restore_checkpoint`].
+    ///
+    /// If `R` doesn't exist when a checkpoint is saved, it is removed when that checkpoint is restored.
+    /// Checkpoints saved before registering `R` don't affect it.
+    /// This method is idempotent.
+    ///
+    /// See the [module docs](crate::rollback) for more information.
+    pub fn register_rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.get_resource_or_init::<RollbackCheckpoints>().register(
+            TypeId::of::<R>(),
+            RollbackFns {
+                save: save_resource::<R>,
+                restore: restore_resource::<R>,
+            },
+        );
+        self
+    }
+
+    /// Saves a checkpoint of the entity IDs and the components and resources registered for rollback,
+    /// returning the [`Tick`] it can be restored from with [`World::restore_checkpoint`].
+    ///
+    /// Checkpoints are keyed by the current [change tick](World::change_tick) of the world: a checkpoint
+    /// saved at the same tick as an existing one replaces it. Once more than
+    /// [`RollbackCheckpoints::max_checkpoints`] are saved, the oldest ones are dropped.
+    ///
+    /// See the [module docs](crate::rollback) for more information.
+    pub fn save_checkpoint(&mut self) -> Tick {
+        self.flush();
+        let tick = self.change_tick();
+        let entities = self.entities.snapshot();
+        let internal_id = self.register_component::<Internal>();
+        let internal = entities_where(self, |archetype| archetype.contains(internal_id))
+            .into_iter()
+            .collect();
+        self.init_resource::<RollbackCheckpoints>();
+        self.resource_scope(|world, mut rollback: Mut<RollbackCheckpoints>| {
+            let data = rollback.fns.iter().map(|fns| (fns.save)(world)).collect();
+            let checkpoint = Checkpoint {
+                tick,
+                entities,
+                internal,
+                data,
+            };
+            match rollback.position(tick) {
+                Some(index) => rollback.checkpoints[index] = checkpoint,
+                None => {
+                    rollback.checkpoints.push_back(checkpoint);
+                    rollback.truncate_oldest();
+                }
+            }
+        });
+        tick
+    }
+
+    /// Restores the checkpoint saved at `tick` by [`World::save_checkpoint`].
+    ///
+    /// Entities spawned after the checkpoint are despawned, and entities despawned after it are spawned again
+    /// with the same IDs. The components and resources registered for rollback are then set to their saved values.
+    /// Checkpoints saved after `tick` are dropped, since they describe a future that no longer happened.
+    ///
+    /// [`Internal`] entities, like observers and registered systems, are left alone: they are neither despawned
+    /// nor spawned again.
+    ///
+    /// This triggers the hooks and observers of the despawned entities and restored components.
+    ///
+    /// See the [module docs](crate::rollback) for more information.
+    pub fn restore_checkpoint(&mut self, tick: Tick) -> Result<(), CheckpointNotFoundError> {
+        if !self
+            .get_resource::<RollbackCheckpoints>()
+            .is_some_and(|rollback| rollback.contains(tick))
+        {
+            return Err(CheckpointNotFoundError(tick));
+        }
+        self.resource_scope(|world, mut rollback: Mut<RollbackCheckpoints>| {
+            let index = rollback.position(tick).unwrap();
+            rollback.checkpoints.truncate(index + 1);
+            let rollback = rollback.into_inner();
+            let checkpoint = &rollback.checkpoints[index];
+
+            // Despawning can run hooks and observers that spawn more entities, so repeat until none are left.
+            let internal_id = world.register_component::<Internal>();
+            loop {
+                world.flush();
+                let mut spawned =
+                    entities_where(world, |archetype| !archetype.contains(internal_id));
+                spawned.retain(|entity| !checkpoint.entities.contains(*entity));
+                if spawned.is_empty() {
+                    break;
+                }
+                for entity in spawned {
+                    if let Ok(entity) = world.get_entity_mut(entity) {
+                        entity.despawn();
+                    }
+                }
+            }
+
+            // Internal entities despawned after the checkpoint stay despawned.
+            let restored = world
+                .entities
+                .restore_snapshot(&checkpoint.entities, &checkpoint.internal);
+            for entity in restored {
+                // SAFETY: `restore_snapshot` allocated `entity` without giving it a location.
+                unsafe { world.spawn_at_empty_internal(entity, MaybeLocation::caller()) };
+            }
+
+            for (fns, data) in rollback.fns.iter().zip(&checkpoint.data) {
+                (fns.restore)(world, data);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Clone, PartialEq, Debug)]
+    struct Position(i32);
+
+    #[derive(Component, Clone, PartialEq, Debug)]
+    struct Velocity(i32);
+
+    #[derive(Component)]
+    struct NotRolledBack;
+
+    #[derive(Resource, Clone, PartialEq, Debug)]
+    struct Score(u32);
+
+    #[test]
+    fn restore_components_and_resources() {
+        let mut world = World::new();
+        world
+            .register_rollback_component::<Position>()
+            .register_rollback_component::<Velocity>()
+            .register_rollback_resource::<Score>();
+        let a = world.spawn((Position(0), Velocity(1))).id();
+        let b = world.spawn((Position(5), NotRolledBack)).id();
+        world.insert_resource(Score(1));
+        let checkpoint = world.save_checkpoint();
+
+        world.get_mut::<Position>(a).unwrap().0 = 1;
+        world.entity_mut(a).remove::<Velocity>();
+        world.entity_mut(b).insert(Velocity(2));
+        world.entity_mut(b).remove::<NotRolledBack>();
+        world.remove_resource::<Score>();
+
+        world.restore_checkpoint(checkpoint).unwrap();
+        assert_eq!(world.get::<Position>(a), Some(&Position(0)));
+        assert_eq!(world.get::<Velocity>(a), Some(&Velocity(1)));
+        assert_eq!(world.get::<Position>(b), Some(&Position(5)));
+        assert_eq!(world.get::<Velocity>(b), None);
+        assert!(!world.entity(b).contains::<NotRolledBack>());
+        assert_eq!(world.get_resource::<Score>(), Some(&Score(1)));
+    }
+
+    #[test]
+    fn restore_entity_ids() {
+        let mut world = World::new();
+        world.register_rollback_component::<Position>();
+        let a = world.spawn(Position(0)).id();
+        let b = world.spawn(Position(1)).id();
+        world.despawn(b);
+        let checkpoint = world.save_checkpoint();
+
+        world.despawn(a);
+        let c = world.spawn(Position(2)).id();
+        let d = world.spawn(Position(3)).id();
+
+        world.restore_checkpoint(checkpoint).unwrap();
+        assert_eq!(world.get::<Position>(a), Some(&Position(0)));
+        assert!(world.get_entity(c).is_err());
+        assert!(world.get_entity(d).is_err());
+
+        // Simulating the same frames again spawns the same entities.
+        world.despawn(a);
+        assert_eq!(world.spawn(Position(2)).id(), c);
+        assert_eq!(world.spawn(Position(3)).id(), d);
+    }
+
+    #[test]
+    fn restore_keeps_internal_entities() {
+        use crate::{lifecycle::Add, observer::On, system::ResMut};
+
+        #[derive(Resource, Default)]
+        struct Added(u32);
+
+        let mut world = World::new();
+        world.register_rollback_component::<Position>();
+        world.init_resource::<Added>();
+        let a = world.spawn(Position(0)).id();
+        let system = world.register_system(|| {});
+        let removed_system = world.register_system(|| {});
+        let checkpoint = world.save_checkpoint();
+
+        let observer = world
+            .add_observer(|_: On<Add, Position>, mut added: ResMut<Added>| added.0 += 1)
+            .id();
+        world.unregister_system(removed_system).unwrap();
+        world.despawn(a);
+        let b = world.spawn(Position(1)).id();
+
+        world.restore_checkpoint(checkpoint).unwrap();
+        assert!(world.get_entity(observer).is_ok());
+        assert!(world.run_system(system).is_ok());
+        assert!(world.get_entity(removed_system.entity()).is_err());
+        assert!(world.get_entity(b).is_err());
+        assert_eq!(world.get::<Position>(a), Some(&Position(0)));
+
+        // The observer is still active, and saw the restored component being added again.
+        let added = world.resource::<Added>().0;
+        world.spawn(Position(2));
+        assert_eq!(world.resource::<Added>().0, added + 1);
+    }
+
+    #[test]
+    fn restore_drops_later_checkpoints() {
+        let mut world = World::new();
+        world.register_rollback_resource::<Score>();
+        world.insert_resource(Score(0));
+        let first = world.save_checkpoint();
+        world.increment_change_tick();
+        world.insert_resource(Score(1));
+        let second = world.save_checkpoint();
+        assert_ne!(first, second);
+
+        world.restore_checkpoint(first).unwrap();
+        assert_eq!(world.resource::<Score>(), &Score(0));
+        assert_eq!(
+            world.restore_checkpoint(second),
+            Err(CheckpointNotFoundError(second))
+        );
+        let rollback = world.resource::<RollbackCheckpoints>();
+        assert_eq!(rollback.ticks().collect::<Vec<_>>(), [first]);
+    }
+
+    #[test]
+    fn max_checkpoints() {
+        let mut world = World::new();
+        world
+            .get_resource_or_init::<RollbackCheckpoints>()
+            .set_max_checkpoints(2);
+        let ticks = (0..3)
+            .map(|_| {
+                world.increment_change_tick();
+                world.save_checkpoint()
+            })
+            .collect::<Vec<_>>();
+        let rollback = world.resource::<RollbackCheckpoints>();
+        assert_eq!(rollback.ticks().collect::<Vec<_>>(), ticks[1..]);
+    }
+}
@@ -0,0 +1,410 @@
+use alloc::{
+    collections::VecDeque,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use bevy_platform::collections::HashMap;
+use bevy_utils::prelude::DebugName;
+use fixedbitset::FixedBitSet;
+
+use crate::{
+    component::ComponentId,
+    query::Access,
+    schedule::{Schedule, ScheduleBuildError, SystemKey},
+    world::World,
+};
+
+/// Whether a conflict between two ambiguous systems is between a read and a write, or between two writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConflictKind {
+    /// One system reads the data, and the other one writes it.
+    ReadWrite,
+    /// Both systems write the data.
+    WriteWrite,
+}
+
+/// A component or resource that two ambiguous systems conflict on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataConflict {
+    /// The id of the component or resource.
+    pub id: ComponentId,
+    /// The name of the component or resource.
+    pub name: DebugName,
+    /// Is `true` if the conflict is on a resource, `false` if it is on a component.
+    pub is_resource: bool,
+    /// Whether the systems conflict as a reader and a writer, or as two writers.
+    pub kind: ConflictKind,
+}
+
+/// A pair of systems with conflicting access and no ordering between them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemAmbiguity {
+    /// The system that runs first in the current topological order of the schedule.
+    pub first: SystemKey,
+    /// The system that runs second in the current topological order of the schedule.
+    pub second: SystemKey,
+    /// The components and resources that the systems conflict on.
+    ///
+    /// If this is empty, the systems conflict on the whole [`World`], for example because one of them is exclusive.
+    pub conflicts: Vec<DataConflict>,
+}
+
+impl SystemAmbiguity {
+    /// Returns `true` if the systems conflict on the whole [`World`] rather than on specific data.
+    pub fn conflicts_on_world(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Returns the most severe [`ConflictKind`] of this ambiguity.
+    ///
+    /// Conflicts on the whole [`World`] are considered [`ConflictKind::WriteWrite`].
+    pub fn kind(&self) -> ConflictKind {
+        if self
+            .conflicts
+            .iter()
+            .all(|conflict| conflict.kind == ConflictKind::ReadWrite)
+            && !self.conflicts_on_world()
+        {
+            ConflictKind::ReadWrite
+        } else {
+            ConflictKind::WriteWrite
+        }
+    }
+}
+
+/// A change to the ordering of a schedule that resolves some of its [`SystemAmbiguity`]s.
+///
+/// Every suggestion follows the current topological order of the schedule, so applying them
+/// can't introduce cycles and keeps the order that the systems already run in with a single thread.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AmbiguityFix {
+    /// Order `before` before `after`, with `.before(after)` on the first system or `.after(before)` on the second.
+    Order {
+        /// The system that should run first.
+        before: SystemKey,
+        /// The system that should run second.
+        after: SystemKey,
+    },
+    /// Add the `systems` to a new [`SystemSet`](super::SystemSet), and order `system` before that set.
+    OrderBeforeSet {
+        /// The system that should run before the set.
+        system: SystemKey,
+        /// The systems to add to the set.
+        systems: Vec<SystemKey>,
+    },
+    /// Add the `systems` to a new [`SystemSet`](super::SystemSet), and order `system` after that set.
+    OrderAfterSet {
+        /// The system that should run after the set.
+        system: SystemKey,
+        /// The systems to add to the set.
+        systems: Vec<SystemKey>,
+    },
+}
+
+/// A structured report of the system order ambiguities of a [`Schedule`], returned by [`Schedule::ambiguity_report`].
+///
+/// Lists every pair of ambiguous systems along with the exact data they conflict on, and suggests a minimal set
+/// of ordering constraints that resolves all of them. Suggestions that are already implied by the existing
+/// constraints of the schedule or by other suggestions are left out.
+///
+/// Ambiguities that are ignored with [`ambiguous_with`](super::IntoScheduleConfigs::ambiguous_with),
+/// [`allow_ambiguous_component`](super::Schedules::allow_ambiguous_component) or
+/// [`allow_ambiguous_resource`](super::Schedules::allow_ambiguous_resource) are not reported.
+///
+/// The [`Display`](fmt::Display) implementation formats the report for humans.
+#[derive(Clone, Debug, Default)]
+pub struct AmbiguityReport {
+    /// The ambiguous pairs of systems.
+    pub ambiguities: Vec<SystemAmbiguity>,
+    /// The suggested ordering constraints that resolve every ambiguity.
+    pub fixes: Vec<AmbiguityFix>,
+    names: HashMap<SystemKey, String>,
+}
+
+impl AmbiguityReport {
+    /// Returns `true` if the schedule has no ambiguities.
+    pub fn is_empty(&self) -> bool {
+        self.ambiguities.is_empty()
+    }
+
+    /// Returns the name of a system of the schedule.
+    pub fn system_name(&self, system: SystemKey) -> &str {
+        self.names.get(&system).map_or("<unknown>", String::as_str)
+    }
+
+    /// Returns the ambiguities that involve the given system.
+    pub fn ambiguities_of(&self, system: SystemKey) -> impl Iterator<Item = &SystemAmbiguity> + '_ {
+        self.ambiguities
+            .iter()
+            .filter(move |ambiguity| ambiguity.first == system || ambiguity.second == system)
+    }
+
+    fn new(schedule: &Schedule, world: &World) -> Self {
+        let executable = schedule.executable();
+        let components = world.components();
+        let index_of = executable
+            .system_ids
+            .iter()
+            .enumerate()
+            .map(|(index, &key)| (key, index))
+            .collect::<HashMap<_, _>>();
+        let names = executable
+            .system_ids
+            .iter()
+            .zip(&executable.systems)
+            .map(|(&key, system)| (key, system.system.name().to_string()))
+            .collect();
+
+        let mut ambiguities = Vec::new();
+        for (a, b, conflicts) in schedule.graph().conflicting_systems() {
+            let (Some(&index_a), Some(&index_b)) = (index_of.get(a), index_of.get(b)) else {
+                continue;
+            };
+            let (first, second) = if index_a < index_b {
+                (index_a, index_b)
+            } else {
+                (index_b, index_a)
+            };
+            let access_first = executable.systems[first].access.combined_access();
+            let access_second = executable.systems[second].access.combined_access();
+            let conflicts = conflicts
+                .iter()
+                .map(|&id| {
+                    let is_resource =
+                        access_first.has_resource_read(id) && access_second.has_resource_read(id);
+                    let kind = if has_write(access_first, id, is_resource)
+                        && has_write(access_second, id, is_resource)
+                    {
+                        ConflictKind::WriteWrite
+                    } else {
+                        ConflictKind::ReadWrite
+                    };
+                    DataConflict {
+                        id,
+                        name: components
+                            .get_name(id)
+                            .unwrap_or_else(|| DebugName::borrowed("<unknown>")),
+                        is_resource,
+                        kind,
+                    }
+                })
+                .collect();
+            ambiguities.push((
+                first,
+                second,
+                SystemAmbiguity {
+                    first: executable.system_ids[first],
+                    second: executable.system_ids[second],
+                    conflicts,
+                },
+            ));
+        }
+
+        let edges = ambiguities
+            .iter()
+            .map(|&(first, second, _)| (first, second))
+            .collect::<Vec<_>>();
+        let fixes = suggest_fixes(
+            &edges,
+            &executable.system_dependents,
+            &executable.system_ids,
+        );
+
+        Self {
+            ambiguities: ambiguities
+                .into_iter()
+                .map(|(_, _, ambiguity)| ambiguity)
+                .collect(),
+            fixes,
+            names,
+        }
+    }
+}
+
+impl fmt::Display for AmbiguityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} pairs of systems with conflicting data access have indeterminate execution order.",
+            self.ambiguities.len()
+        )?;
+        for ambiguity in &self.ambiguities {
+            writeln!(
+                f,
+                " -- {} and {}",
+                self.system_name(ambiguity.first),
+                self.system_name(ambiguity.second)
+            )?;
+            if ambiguity.conflicts_on_world() {
+                writeln!(f, "    conflict on: World")?;
+                continue;
+            }
+            write!(f, "    conflict on:")?;
+            for (i, conflict) in ambiguity.conflicts.iter().enumerate() {
+                let separator = if i == 0 { " " } else { ", " };
+                let data = if conflict.is_resource {
+                    "resource"
+                } else {
+                    "component"
+                };
+                let kind = match conflict.kind {
+                    ConflictKind::ReadWrite => "read/write",
+                    ConflictKind::WriteWrite => "write/write",
+                };
+                write!(f, "{separator}{} ({data}, {kind})", conflict.name)?;
+            }
+            writeln!(f)?;
+        }
+
+        if self.fixes.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "Suggested fixes:")?;
+        for fix in &self.fixes {
+            match fix {
+                AmbiguityFix::Order { before, after } => writeln!(
+                    f,
+                    " -- order {} before {}",
+                    self.system_name(*before),
+                    self.system_name(*after)
+                )?,
+                AmbiguityFix::OrderBeforeSet { system, systems } => writeln!(
+                    f,
+                    " -- add {} to a system set, and order {} before it",
+                    self.join_names(systems),
+                    self.system_name(*system)
+                )?,
+                AmbiguityFix::OrderAfterSet { system, systems } => writeln!(
+                    f,
+                    " -- add {} to a system set, and order {} after it",
+                    self.join_names(systems),
+                    self.system_name(*system)
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AmbiguityReport {
+    fn join_names(&self, systems: &[SystemKey]) -> String {
+        systems
+            .iter()
+            .map(|&system| self.system_name(system))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Schedule {
+    /// Returns an [`AmbiguityReport`] listing the system order ambiguities of this schedule,
+    /// along with suggested ordering constraints that resolve them.
+    ///
+    /// This initializes the schedule if needed. Since initialization fails when
+    /// [`ScheduleBuildSettings::ambiguity_detection`](super::ScheduleBuildSettings::ambiguity_detection)
+    /// is [`LogLevel::Error`](super::LogLevel::Error) and there are ambiguities, use
+    /// [`LogLevel::Warn`](super::LogLevel::Warn) or [`LogLevel::Ignore`](super::LogLevel::Ignore) instead.
+    pub fn ambiguity_report(
+        &mut self,
+        world: &mut World,
+    ) -> Result<AmbiguityReport, ScheduleBuildError> {
+        self.initialize(world)?;
+        Ok(AmbiguityReport::new(self, world))
+    }
+}
+
+/// Suggests ordering constraints that add the given `edges` between systems, identified by their index in the
+/// topological order. `edges` must follow the topological order, and `dependents` are the existing
+/// constraints of the schedule.
+fn suggest_fixes(
+    edges: &[(usize, usize)],
+    dependents: &[Vec<usize>],
+    system_ids: &[SystemKey],
+) -> Vec<AmbiguityFix> {
+    let mut edges = edges.to_vec();
+    edges.sort_unstable();
+    edges.dedup();
+
+    let mut successors = dependents.to_vec();
+    for &(before, after) in &edges {
+        successors[before].push(after);
+    }
+
+    // Every edge follows the topological order, so the graph is acyclic, and an edge is
+    // redundant if `after` can be reached from `before` through another path.
+    let needed = edges
+        .into_iter()
+        .filter(|&(before, after)| !reachable_without_edge(&successors, before, after))
+        .collect::<Vec<_>>();
+
+    let mut after_by_before = HashMap::<usize, Vec<usize>>::default();
+    for &(before, after) in &needed {
+        after_by_before.entry(before).or_default().push(after);
+    }
+    let mut before_by_after = HashMap::<usize, Vec<usize>>::default();
+    for &(before, after) in &needed {
+        if after_by_before[&before].len() < 2 {
+            before_by_after.entry(after).or_default().push(before);
+        }
+    }
+
+    let keys = |systems: &[usize]| systems.iter().map(|&index| system_ids[index]).collect();
+    let mut fixes = Vec::new();
+    let mut befores = after_by_before.into_iter().collect::<Vec<_>>();
+    befores.sort_unstable();
+    for (before, afters) in befores {
+        if afters.len() >= 2 {
+            fixes.push(AmbiguityFix::OrderBeforeSet {
+                system: system_ids[before],
+                systems: keys(&afters),
+            });
+        }
+    }
+    let mut afters = before_by_after.into_iter().collect::<Vec<_>>();
+    afters.sort_unstable();
+    for (after, befores) in afters {
+        if let [before] = befores[..] {
+            fixes.push(AmbiguityFix::Order {
+                before: system_ids[before],
+                after: system_ids[after],
+            });
+        } else {
+            fixes.push(AmbiguityFix::OrderAfterSet {
+                system: system_ids[after],
+                systems: keys(&befores),
+            });
+        }
+    }
+    fixes
+}
+
+/// Returns `true` if `to` can be reached from `from` without using the direct edge between them.
+fn reachable_without_edge(successors: &[Vec<usize>], from: usize, to: usize) -> bool {
+    let mut visited = FixedBitSet::with_capacity(successors.len());
+    let mut queue = successors[from]
+        .iter()
+        .copied()
+        .filter(|&next| next != to)
+        .collect::<VecDeque<_>>();
+    while let Some(node) = queue.pop_front() {
+        // Nodes after `to` in the topological order can't lead back to it.
+        if node == to {
+            return true;
+        }
+        if node > to || visited.put(node) {
+            continue;
+        }
+        queue.extend(successors[node].iter().copied());
+    }
+    false
+}
+
+fn has_write(access: &Access, id: ComponentId, is_resource: bool) -> bool {
+    if is_resource {
+        access.has_resource_write(id)
+    } else {
+        access.has_component_write(id)
+    }
+}
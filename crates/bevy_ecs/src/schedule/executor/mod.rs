@@ -2,13 +2,14 @@
 mod multi_threaded;
 mod simple;
 mod single_threaded;
+mod trace;
 
 use alloc::{vec, vec::Vec};
 use bevy_utils::prelude::DebugName;
 use core::any::TypeId;
 
 #[expect(deprecated, reason = "We still need to support this.")]
-pub use self::{simple::SimpleExecutor, single_threaded::SingleThreadedExecutor};
+pub use self::{simple::SimpleExecutor, single_threaded::SingleThreadedExecutor, trace::*};
 
 #[cfg(feature = "std")]
 pub use self::multi_threaded::{MainThreadExecutor, MultiThreadedExecutor};
@@ -40,6 +41,11 @@ pub(super) trait SystemExecutor: Send + Sync {
         error_handler: fn(BevyError, ErrorContext),
     );
     fn set_apply_final_deferred(&mut self, value: bool);
+    fn apply_final_deferred(&self) -> bool;
+    /// Starts recording the [`ExecutionEvent`]s of the systems run by the executor.
+    fn start_recording(&mut self);
+    /// Stops recording, returning the [`ExecutionEvent`]s recorded since [`SystemExecutor::start_recording`].
+    fn finish_recording(&mut self) -> Vec<ExecutionEvent>;
 }
 
 /// Specifies how a [`Schedule`](super::Schedule) will be run.
@@ -575,4 +581,123 @@ mod tests {
         let counter = world.resource::<Counter>();
         assert_eq!(counter.0, 0);
     }
+
+    #[test]
+    fn record_and_replay_execution_order() {
+        use crate::schedule::{ExecutionEvent, ExecutionRecorder, ExecutionReplayer};
+        use alloc::vec::Vec;
+
+        #[derive(Resource, Default)]
+        struct Order(Vec<u8>);
+
+        for executor in EXECUTORS {
+            let mut world = World::new();
+            world.init_resource::<Order>();
+            world.init_resource::<ExecutionRecorder>();
+            let mut schedule = Schedule::default();
+            schedule.set_executor_kind(executor);
+            schedule.add_systems((
+                |mut order: ResMut<Order>| order.0.push(0),
+                |mut order: ResMut<Order>| order.0.push(1),
+                |mut order: ResMut<Order>| order.0.push(2),
+            ));
+            schedule.run(&mut world);
+
+            let mut trace = world
+                .remove_resource::<ExecutionRecorder>()
+                .unwrap()
+                .into_trace();
+            assert_eq!(trace.runs.len(), 1);
+            let run = &mut trace.runs[0];
+            let start_order = run.start_order().collect::<Vec<_>>();
+            assert_eq!(start_order.len(), 3);
+            for system in start_order.iter().copied() {
+                let started = run
+                    .events
+                    .iter()
+                    .position(|event| *event == ExecutionEvent::Started(system));
+                let finished = run
+                    .events
+                    .iter()
+                    .position(|event| *event == ExecutionEvent::Finished(system));
+                assert!(started < finished);
+            }
+
+            // Replay the systems in the reverse order.
+            let recorded = core::mem::take(&mut world.resource_mut::<Order>().0);
+            run.events = start_order
+                .iter()
+                .rev()
+                .flat_map(|&system| {
+                    [
+                        ExecutionEvent::Started(system),
+                        ExecutionEvent::Finished(system),
+                    ]
+                })
+                .collect();
+            world.insert_resource(ExecutionReplayer::new(trace));
+            schedule.run(&mut world);
+            assert!(world.resource::<ExecutionReplayer>().is_finished());
+            let replayed = core::mem::take(&mut world.resource_mut::<Order>().0);
+            assert_eq!(replayed, recorded.into_iter().rev().collect::<Vec<_>>());
+
+            // Once the trace is replayed, the schedule runs normally.
+            schedule.run(&mut world);
+            assert_eq!(world.resource::<Order>().0.len(), 3);
+        }
+    }
+
+    #[test]
+    fn replay_runs_of_several_schedules() {
+        use crate::schedule::{ExecutionRecorder, ExecutionReplayer, ScheduleLabel};
+        use alloc::vec::Vec;
+
+        #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+        struct A;
+
+        #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+        struct B;
+
+        #[derive(Resource, Default)]
+        struct Order(Vec<u8>);
+
+        let mut world = World::new();
+        world.init_resource::<Order>();
+        world.init_resource::<ExecutionRecorder>();
+        let mut a = Schedule::new(A);
+        a.add_systems((
+            |mut order: ResMut<Order>| order.0.push(0),
+            |mut order: ResMut<Order>| order.0.push(1),
+        ));
+        let mut b = Schedule::new(B);
+        b.add_systems((
+            |mut order: ResMut<Order>| order.0.push(2),
+            |mut order: ResMut<Order>| order.0.push(3),
+        ));
+        a.run(&mut world);
+        b.run(&mut world);
+
+        let mut trace = world
+            .remove_resource::<ExecutionRecorder>()
+            .unwrap()
+            .into_trace();
+        assert_eq!(trace.runs.len(), 2);
+        let recorded = core::mem::take(&mut world.resource_mut::<Order>().0);
+
+        // Replay the systems of both schedules in the reverse order, running the schedules in the reverse order too.
+        for run in &mut trace.runs {
+            run.events.reverse();
+        }
+        world.insert_resource(ExecutionReplayer::new(trace));
+        b.run(&mut world);
+        assert_eq!(world.resource::<ExecutionReplayer>().remaining(), 1);
+        a.run(&mut world);
+        assert!(world.resource::<ExecutionReplayer>().is_finished());
+
+        let replayed = core::mem::take(&mut world.resource_mut::<Order>().0);
+        let mut expected = recorded[2..].to_vec();
+        expected.reverse();
+        expected.extend(recorded[..2].iter().rev());
+        assert_eq!(replayed, expected);
+    }
 }
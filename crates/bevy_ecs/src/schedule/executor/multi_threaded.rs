@@ -16,8 +16,8 @@ use crate::{
     error::{ErrorContext, ErrorHandler, Result},
     prelude::Resource,
     schedule::{
-        is_apply_deferred, ConditionWithAccess, ExecutorKind, SystemExecutor, SystemSchedule,
-        SystemWithAccess,
+        is_apply_deferred, ConditionWithAccess, ExecutionEvent, ExecutorKind, SystemExecutor,
+        SystemSchedule, SystemWithAccess,
     },
     system::{RunSystemError, ScheduleSystem},
     world::{unsafe_world_cell::UnsafeWorldCell, World},
@@ -129,6 +129,8 @@ pub struct ExecutorState {
     completed_systems: FixedBitSet,
     /// Systems that have run but have not had their buffers applied.
     unapplied_systems: FixedBitSet,
+    /// Events of the systems that have run, if recording.
+    recorded_events: Option<Vec<ExecutionEvent>>,
 }
 
 /// References to data required by the executor.
@@ -323,6 +325,23 @@ impl SystemExecutor for MultiThreadedExecutor {
     fn set_apply_final_deferred(&mut self, value: bool) {
         self.apply_final_deferred = value;
     }
+
+    fn apply_final_deferred(&self) -> bool {
+        self.apply_final_deferred
+    }
+
+    fn start_recording(&mut self) {
+        self.state.get_mut().unwrap().recorded_events = Some(Vec::new());
+    }
+
+    fn finish_recording(&mut self) -> Vec<ExecutionEvent> {
+        self.state
+            .get_mut()
+            .unwrap()
+            .recorded_events
+            .take()
+            .unwrap_or_default()
+    }
 }
 
 impl<'scope, 'env: 'scope, 'sys> Context<'scope, 'env, 'sys> {
@@ -418,6 +437,7 @@ impl ExecutorState {
             skipped_systems: FixedBitSet::new(),
             completed_systems: FixedBitSet::new(),
             unapplied_systems: FixedBitSet::new(),
+            recorded_events: None,
         }
     }
 
@@ -510,6 +530,9 @@ impl ExecutorState {
 
                 self.running_systems.insert(system_index);
                 self.num_running_systems += 1;
+                if let Some(events) = &mut self.recorded_events {
+                    events.push(ExecutionEvent::Started(system_index));
+                }
 
                 if self.system_task_metadata[system_index].is_exclusive {
                     // SAFETY: `can_run` returned true for this system,
@@ -772,6 +795,9 @@ impl ExecutorState {
         self.running_systems.remove(system_index);
         self.completed_systems.insert(system_index);
         self.unapplied_systems.insert(system_index);
+        if let Some(events) = &mut self.recorded_events {
+            events.push(ExecutionEvent::Finished(system_index));
+        }
 
         self.signal_dependents(system_index);
     }
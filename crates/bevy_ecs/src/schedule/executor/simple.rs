@@ -1,5 +1,6 @@
 #![expect(deprecated, reason = "Everything here is deprecated")]
 
+use alloc::vec::Vec;
 use core::panic::AssertUnwindSafe;
 use fixedbitset::FixedBitSet;
 
@@ -14,8 +15,8 @@ use crate::{change_detection::DetectChanges, HotPatchChanges};
 use crate::{
     error::{ErrorContext, ErrorHandler},
     schedule::{
-        executor::is_apply_deferred, ConditionWithAccess, ExecutorKind, SystemExecutor,
-        SystemSchedule,
+        executor::is_apply_deferred, ConditionWithAccess, ExecutionEvent, ExecutorKind,
+        SystemExecutor, SystemSchedule,
     },
     system::RunSystemError,
     world::World,
@@ -35,6 +36,8 @@ pub struct SimpleExecutor {
     evaluated_sets: FixedBitSet,
     /// Systems that have run or been skipped.
     completed_systems: FixedBitSet,
+    /// Events of the systems that have run, if recording.
+    recorded_events: Option<Vec<ExecutionEvent>>,
 }
 
 impl SystemExecutor for SimpleExecutor {
@@ -124,7 +127,12 @@ impl SystemExecutor for SimpleExecutor {
                 continue;
             }
 
+            if let Some(events) = &mut self.recorded_events {
+                events.push(ExecutionEvent::Started(system_index));
+            }
+
             if is_apply_deferred(&**system) {
+                self.record_finished(system_index);
                 continue;
             }
 
@@ -155,6 +163,8 @@ impl SystemExecutor for SimpleExecutor {
             {
                 (f)();
             }
+
+            self.record_finished(system_index);
         }
 
         self.evaluated_sets.clear();
@@ -164,6 +174,19 @@ impl SystemExecutor for SimpleExecutor {
     fn set_apply_final_deferred(&mut self, _: bool) {
         // do nothing. simple executor does not do a final sync
     }
+
+    fn apply_final_deferred(&self) -> bool {
+        // system buffers are applied after each system, including the last one
+        true
+    }
+
+    fn start_recording(&mut self) {
+        self.recorded_events = Some(Vec::new());
+    }
+
+    fn finish_recording(&mut self) -> Vec<ExecutionEvent> {
+        self.recorded_events.take().unwrap_or_default()
+    }
 }
 
 impl SimpleExecutor {
@@ -173,6 +196,13 @@ impl SimpleExecutor {
         Self {
             evaluated_sets: FixedBitSet::new(),
             completed_systems: FixedBitSet::new(),
+            recorded_events: None,
+        }
+    }
+
+    fn record_finished(&mut self, system_index: usize) {
+        if let Some(events) = &mut self.recorded_events {
+            events.push(ExecutionEvent::Finished(system_index));
         }
     }
 }
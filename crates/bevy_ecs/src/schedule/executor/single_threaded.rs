@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::panic::AssertUnwindSafe;
 use fixedbitset::FixedBitSet;
 
@@ -10,7 +11,8 @@ use std::eprintln;
 use crate::{
     error::{ErrorContext, ErrorHandler},
     schedule::{
-        is_apply_deferred, ConditionWithAccess, ExecutorKind, SystemExecutor, SystemSchedule,
+        is_apply_deferred, ConditionWithAccess, ExecutionEvent, ExecutorKind, SystemExecutor,
+        SystemSchedule,
     },
     system::RunSystemError,
     world::World,
@@ -35,6 +37,8 @@ pub struct SingleThreadedExecutor {
     unapplied_systems: FixedBitSet,
     /// Setting when true applies deferred system buffers after all systems have run
     apply_final_deferred: bool,
+    /// Events of the systems that have run, if recording.
+    recorded_events: Option<Vec<ExecutionEvent>>,
 }
 
 impl SystemExecutor for SingleThreadedExecutor {
@@ -66,13 +70,71 @@ impl SystemExecutor for SingleThreadedExecutor {
             self.completed_systems |= skipped_systems;
         }
 
+        self.run_systems(schedule, world, 0..schedule.systems.len(), error_handler);
+    }
+
+    fn set_apply_final_deferred(&mut self, apply_final_deferred: bool) {
+        self.apply_final_deferred = apply_final_deferred;
+    }
+
+    fn apply_final_deferred(&self) -> bool {
+        self.apply_final_deferred
+    }
+
+    fn start_recording(&mut self) {
+        self.recorded_events = Some(Vec::new());
+    }
+
+    fn finish_recording(&mut self) -> Vec<ExecutionEvent> {
+        self.recorded_events.take().unwrap_or_default()
+    }
+}
+
+impl SingleThreadedExecutor {
+    /// Creates a new single-threaded executor for use in a [`Schedule`].
+    ///
+    /// [`Schedule`]: crate::schedule::Schedule
+    pub const fn new() -> Self {
+        Self {
+            evaluated_sets: FixedBitSet::new(),
+            completed_systems: FixedBitSet::new(),
+            unapplied_systems: FixedBitSet::new(),
+            apply_final_deferred: true,
+            recorded_events: None,
+        }
+    }
+
+    /// Runs the systems with the given indices one at a time, in the order they were started
+    /// in the recorded [`ScheduleRunTrace`](super::ScheduleRunTrace).
+    pub(crate) fn replay(
+        &mut self,
+        schedule: &mut SystemSchedule,
+        world: &mut World,
+        order: &[usize],
+        error_handler: ErrorHandler,
+    ) {
+        let system_count = schedule.systems.len();
+        let order = order
+            .iter()
+            .copied()
+            .filter(|&system_index| system_index < system_count);
+        self.run_systems(schedule, world, order, error_handler);
+    }
+
+    fn run_systems(
+        &mut self,
+        schedule: &mut SystemSchedule,
+        world: &mut World,
+        order: impl Iterator<Item = usize>,
+        error_handler: ErrorHandler,
+    ) {
         #[cfg(feature = "hotpatching")]
         let hotpatch_tick = world
             .get_resource_ref::<HotPatchChanges>()
             .map(|r| r.last_changed())
             .unwrap_or_default();
 
-        for system_index in 0..schedule.systems.len() {
+        for system_index in order {
             #[cfg(feature = "trace")]
             let name = schedule.systems[system_index].system.name();
             #[cfg(feature = "trace")]
@@ -126,8 +188,13 @@ impl SystemExecutor for SingleThreadedExecutor {
                 continue;
             }
 
+            if let Some(events) = &mut self.recorded_events {
+                events.push(ExecutionEvent::Started(system_index));
+            }
+
             if is_apply_deferred(&**system) {
                 self.apply_deferred(schedule, world);
+                self.record_finished(system_index);
                 continue;
             }
 
@@ -160,6 +227,7 @@ impl SystemExecutor for SingleThreadedExecutor {
             }
 
             self.unapplied_systems.insert(system_index);
+            self.record_finished(system_index);
         }
 
         if self.apply_final_deferred {
@@ -169,21 +237,9 @@ impl SystemExecutor for SingleThreadedExecutor {
         self.completed_systems.clear();
     }
 
-    fn set_apply_final_deferred(&mut self, apply_final_deferred: bool) {
-        self.apply_final_deferred = apply_final_deferred;
-    }
-}
-
-impl SingleThreadedExecutor {
-    /// Creates a new single-threaded executor for use in a [`Schedule`].
-    ///
-    /// [`Schedule`]: crate::schedule::Schedule
-    pub const fn new() -> Self {
-        Self {
-            evaluated_sets: FixedBitSet::new(),
-            completed_systems: FixedBitSet::new(),
-            unapplied_systems: FixedBitSet::new(),
-            apply_final_deferred: true,
+    fn record_finished(&mut self, system_index: usize) {
+        if let Some(events) = &mut self.recorded_events {
+            events.push(ExecutionEvent::Finished(system_index));
         }
     }
 
@@ -0,0 +1,216 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use bevy_platform::collections::HashMap;
+use log::warn;
+
+use crate::{
+    resource::Resource,
+    schedule::{InternedScheduleLabel, SystemSchedule},
+};
+
+/// An event of a system in a [`ScheduleRunTrace`], identified by its index in the schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExecutionEvent {
+    /// The executor started running the system.
+    Started(usize),
+    /// The executor was notified that the system finished running.
+    Finished(usize),
+}
+
+/// The order in which the systems of a single run of a [`Schedule`](super::super::Schedule) started and finished.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleRunTrace {
+    /// The label of the schedule, formatted with [`Debug`].
+    pub schedule: String,
+    /// The names of the systems in the schedule, indexed by the system indices of the [`ExecutionEvent`]s.
+    pub systems: Vec<String>,
+    /// The events of the systems that ran, in the order they happened.
+    pub events: Vec<ExecutionEvent>,
+}
+
+impl ScheduleRunTrace {
+    fn new(label: InternedScheduleLabel, schedule: &SystemSchedule) -> Self {
+        Self {
+            schedule: format_label(label),
+            systems: system_names(schedule),
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns the indices of the systems that ran, in the order they started.
+    ///
+    /// Systems that conflict with each other never run at the same time, so running the systems
+    /// one at a time in this order reproduces the order in which they accessed the world.
+    pub fn start_order(&self) -> impl Iterator<Item = usize> + '_ {
+        self.events.iter().filter_map(|event| match event {
+            ExecutionEvent::Started(index) => Some(*index),
+            ExecutionEvent::Finished(_) => None,
+        })
+    }
+
+    /// Returns the names of the systems that ran, in the order they started.
+    pub fn start_order_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.start_order()
+            .map(|index| self.systems.get(index).map_or("<unknown>", String::as_str))
+    }
+}
+
+/// The [`ScheduleRunTrace`]s of every schedule run while an [`ExecutionRecorder`] was present,
+/// in the order the runs started.
+///
+/// With the `serialize` feature, this can be saved to a file and loaded back into an [`ExecutionReplayer`]
+/// to reproduce bugs that depend on the order of systems without ordering constraints.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecutionTrace {
+    /// The traces of the schedule runs, in the order they started.
+    ///
+    /// Nested schedule runs, like the ones started by an exclusive system, come after the run that started them.
+    pub runs: Vec<ScheduleRunTrace>,
+}
+
+/// Resource that makes every [`Schedule`](super::super::Schedule) run record the order in which its systems started and
+/// finished into an [`ExecutionTrace`].
+///
+/// Recording works with every [`ExecutorKind`](super::ExecutorKind).
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule::{ExecutionRecorder, ExecutionReplayer};
+/// # fn a() {}
+/// # fn b() {}
+/// let mut world = World::new();
+/// let mut schedule = Schedule::default();
+/// schedule.add_systems((a, b));
+///
+/// world.init_resource::<ExecutionRecorder>();
+/// schedule.run(&mut world);
+/// let trace = world.remove_resource::<ExecutionRecorder>().unwrap().into_trace();
+///
+/// // Later, run the schedule in exactly the same order.
+/// world.insert_resource(ExecutionReplayer::new(trace));
+/// schedule.run(&mut world);
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct ExecutionRecorder {
+    trace: ExecutionTrace,
+}
+
+impl ExecutionRecorder {
+    /// Returns the trace recorded so far.
+    pub fn trace(&self) -> &ExecutionTrace {
+        &self.trace
+    }
+
+    /// Takes the trace recorded so far, and keeps recording into an empty trace.
+    pub fn take_trace(&mut self) -> ExecutionTrace {
+        core::mem::take(&mut self.trace)
+    }
+
+    /// Returns the recorded trace.
+    pub fn into_trace(self) -> ExecutionTrace {
+        self.trace
+    }
+
+    /// Adds a run of the schedule, returning its index to [`ExecutionRecorder::finish_run`].
+    pub(crate) fn start_run(
+        &mut self,
+        label: InternedScheduleLabel,
+        schedule: &SystemSchedule,
+    ) -> usize {
+        self.trace.runs.push(ScheduleRunTrace::new(label, schedule));
+        self.trace.runs.len() - 1
+    }
+
+    pub(crate) fn finish_run(&mut self, run: usize, events: Vec<ExecutionEvent>) {
+        if let Some(run) = self.trace.runs.get_mut(run) {
+            run.events = events;
+        }
+    }
+}
+
+/// Resource that makes each [`Schedule`](super::super::Schedule) run replay the next [`ScheduleRunTrace`]
+/// of the same schedule in an [`ExecutionTrace`], running its systems one at a time in the recorded order.
+///
+/// The runs of each schedule are replayed in the order they were recorded, independently of the runs of other
+/// schedules, so a trace spanning several schedules replays even if they don't run in the recorded order.
+///
+/// Run conditions are evaluated again, and systems that didn't run in the recording are skipped.
+/// Deferred system buffers are applied at the recorded [`ApplyDeferred`](super::ApplyDeferred) systems and at the end
+/// of the run, like the [`SingleThreadedExecutor`](super::SingleThreadedExecutor) does.
+///
+/// If the systems of a schedule changed since the recording, a warning is logged, the recorded run is consumed and
+/// the schedule runs normally. Schedules without recorded runs left run normally.
+///
+/// See [`ExecutionRecorder`] for an example.
+#[derive(Resource, Debug)]
+pub struct ExecutionReplayer {
+    trace: ExecutionTrace,
+    /// The index in `trace.runs` to look for the next run of each schedule from, keyed by schedule label.
+    next_runs: HashMap<String, usize>,
+    replayed: usize,
+}
+
+impl ExecutionReplayer {
+    /// Creates a replayer for the runs of the given [`ExecutionTrace`].
+    pub fn new(trace: ExecutionTrace) -> Self {
+        Self {
+            trace,
+            next_runs: HashMap::default(),
+            replayed: 0,
+        }
+    }
+
+    /// Returns the number of recorded runs that have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.trace.runs.len() - self.replayed
+    }
+
+    /// Returns true if every recorded run has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Consumes the next recorded run of the given schedule, and returns the order in which its systems should run,
+    /// if the systems of the schedule didn't change since the recording.
+    pub(crate) fn next_run(
+        &mut self,
+        label: InternedScheduleLabel,
+        schedule: &SystemSchedule,
+    ) -> Option<Vec<usize>> {
+        let label = format_label(label);
+        let start = self.next_runs.get(&label).copied().unwrap_or(0);
+        let index = start
+            + self.trace.runs[start..]
+                .iter()
+                .position(|run| run.schedule == label)?;
+        self.next_runs.insert(label, index + 1);
+        self.replayed += 1;
+
+        let run = &self.trace.runs[index];
+        if run.systems != system_names(schedule) {
+            warn!(
+                "The systems of schedule {} changed since the execution trace was recorded. Running it normally.",
+                run.schedule
+            );
+            return None;
+        }
+        Some(run.start_order().collect())
+    }
+}
+
+fn format_label(label: InternedScheduleLabel) -> String {
+    alloc::format!("{label:?}")
+}
+
+fn system_names(schedule: &SystemSchedule) -> Vec<String> {
+    schedule
+        .systems
+        .iter()
+        .map(|system| system.system.name().to_string())
+        .collect()
+}
@@ -1,5 +1,6 @@
 //! Contains APIs for ordering systems and executing them on a [`World`](crate::world::World)
 
+mod ambiguity_report;
 mod auto_insert_apply_deferred;
 mod condition;
 mod config;
@@ -13,7 +14,10 @@ mod stepping;
 
 pub use self::graph::GraphInfo;
 use self::graph::*;
-pub use self::{condition::*, config::*, error::*, executor::*, node::*, schedule::*, set::*};
+pub use self::{
+    ambiguity_report::*, condition::*, config::*, error::*, executor::*, node::*, schedule::*,
+    set::*,
+};
 pub use pass::ScheduleBuildPass;
 
 /// An implementation of a graph data structure.
@@ -1243,6 +1247,88 @@ mod tests {
             schedule.initialize(&mut world).unwrap();
             assert!(schedule.graph().conflicting_systems().is_empty());
         }
+
+        #[test]
+        fn ambiguity_report_conflicts() {
+            let mut world = World::new();
+            let mut schedule = Schedule::new(TestSchedule);
+            schedule.add_systems((resmut_system, res_system, write_component_system));
+            schedule.add_systems((write_component_system, write_world_system).chain());
+            let report = schedule.ambiguity_report(&mut world).unwrap();
+
+            let resource = world.components().resource_id::<R>().unwrap();
+            let component = world.components().component_id::<A>().unwrap();
+            let mut conflicts = report
+                .ambiguities
+                .iter()
+                .map(|ambiguity| {
+                    ambiguity
+                        .conflicts
+                        .iter()
+                        .map(|conflict| (conflict.id, conflict.is_resource, conflict.kind))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            conflicts.sort();
+            let mut expected = vec![
+                // The exclusive system conflicts on the world with all but the system it is ordered after.
+                vec![],
+                vec![],
+                vec![],
+                vec![(resource, true, ConflictKind::ReadWrite)],
+                vec![(component, false, ConflictKind::WriteWrite)],
+            ];
+            expected.sort();
+            assert_eq!(conflicts, expected);
+            assert_eq!(
+                report
+                    .ambiguities
+                    .iter()
+                    .filter(|ambiguity| ambiguity.conflicts_on_world())
+                    .count(),
+                3
+            );
+        }
+
+        #[test]
+        fn ambiguity_report_minimal_fixes() {
+            let mut world = World::new();
+            let mut schedule = Schedule::new(TestSchedule);
+            schedule.add_systems((resmut_system, resmut_system, resmut_system));
+            let report = schedule.ambiguity_report(&mut world).unwrap();
+            assert_eq!(report.ambiguities.len(), 3);
+
+            // Ordering the first system before the third one is implied by the other two fixes.
+            let systems = &schedule.executable().system_ids;
+            assert_eq!(
+                report.fixes,
+                vec![
+                    AmbiguityFix::Order {
+                        before: systems[0],
+                        after: systems[1],
+                    },
+                    AmbiguityFix::Order {
+                        before: systems[1],
+                        after: systems[2],
+                    },
+                ]
+            );
+
+            // Systems that read the resource don't conflict with each other, so they can be grouped into a set.
+            let mut schedule = Schedule::new(TestSchedule);
+            schedule.add_systems((resmut_system, res_system, res_system));
+            let report = schedule.ambiguity_report(&mut world).unwrap();
+            assert_eq!(report.fixes.len(), 1);
+            assert!(matches!(
+                &report.fixes[0],
+                AmbiguityFix::OrderBeforeSet { systems, .. }
+                    | AmbiguityFix::OrderAfterSet { systems, .. } if systems.len() == 2
+            ));
+
+            let mut schedule = Schedule::new(TestSchedule);
+            schedule.add_systems(empty_system);
+            assert!(schedule.ambiguity_report(&mut world).unwrap().is_empty());
+        }
     }
 
     #[cfg(feature = "bevy_debug_stepping")]
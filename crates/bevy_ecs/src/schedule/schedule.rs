@@ -488,6 +488,24 @@ impl Schedule {
 
         let error_handler = world.default_error_handler();
 
+        if let Some(order) = world
+            .get_resource_mut::<ExecutionReplayer>()
+            .and_then(|mut replayer| replayer.next_run(self.label, &self.executable))
+        {
+            let mut executor = SingleThreadedExecutor::new();
+            executor.init(&self.executable);
+            executor.set_apply_final_deferred(self.executor.apply_final_deferred());
+            executor.replay(&mut self.executable, world, &order, error_handler);
+            return;
+        }
+
+        let recorded_run = world
+            .get_resource_mut::<ExecutionRecorder>()
+            .map(|mut recorder| recorder.start_run(self.label, &self.executable));
+        if recorded_run.is_some() {
+            self.executor.start_recording();
+        }
+
         #[cfg(not(feature = "bevy_debug_stepping"))]
         self.executor
             .run(&mut self.executable, world, None, error_handler);
@@ -506,6 +524,13 @@ impl Schedule {
                 error_handler,
             );
         }
+
+        if let Some(run) = recorded_run {
+            let events = self.executor.finish_recording();
+            if let Some(mut recorder) = world.get_resource_mut::<ExecutionRecorder>() {
+                recorder.finish_run(run, events);
+            }
+        }
     }
 
     /// Initializes any newly-added systems and conditions, rebuilds the executable schedule,
@@ -611,6 +636,29 @@ impl Schedule {
         Ok(iter)
     }
 
+    /// Returns an iterator over all systems in this schedule, along with the access
+    /// returned when they were initialized.
+    ///
+    /// Note: this method will return [`ScheduleNotInitialized`] if the
+    /// schedule has never been initialized or run.
+    pub fn systems_with_access(
+        &self,
+    ) -> Result<impl Iterator<Item = (SystemKey, &SystemWithAccess)> + Sized, ScheduleNotInitialized>
+    {
+        if !self.executor_initialized {
+            return Err(ScheduleNotInitialized);
+        }
+
+        let iter = self
+            .executable
+            .system_ids
+            .iter()
+            .copied()
+            .zip(&self.executable.systems);
+
+        Ok(iter)
+    }
+
     /// Returns the number of systems in this schedule.
     pub fn systems_len(&self) -> usize {
         if !self.executor_initialized {
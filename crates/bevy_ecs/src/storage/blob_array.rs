@@ -115,6 +115,21 @@ impl BlobArray {
         unsafe { self.get_ptr_mut().byte_add(index * size) }
     }
 
+    /// Swaps the elements at `a` and `b`. This method doesn't do any bounds checking.
+    ///
+    /// # Safety
+    /// - `a` and `b` must be safe to access, and must not be equal.
+    pub unsafe fn swap_unchecked(&mut self, a: usize, b: usize) {
+        debug_assert_ne!(a, b);
+        let size = self.item_layout.size();
+        // SAFETY: The caller ensures that `a` and `b` are safe to access.
+        let a = unsafe { self.get_unchecked_mut(a) }.as_ptr();
+        // SAFETY: The caller ensures that `a` and `b` are safe to access.
+        let b = unsafe { self.get_unchecked_mut(b) }.as_ptr();
+        // SAFETY: The caller ensures that `a` and `b` are valid and different elements, so they don't overlap.
+        unsafe { core::ptr::swap_nonoverlapping(a, b, size) };
+    }
+
     /// Gets a [`Ptr`] to the start of the array
     #[inline]
     pub fn get_ptr(&self) -> Ptr<'_> {
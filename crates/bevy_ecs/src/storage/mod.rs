@@ -32,6 +32,13 @@ pub use sparse_set::*;
 pub use table::*;
 
 use crate::component::{ComponentInfo, StorageType};
+use alloc::vec::Vec;
+use bevy_platform::collections::HashMap;
+use core::{
+    iter::Sum,
+    mem::size_of,
+    ops::{Add, AddAssign},
+};
 
 /// The raw data stores of a [`World`](crate::world::World)
 #[derive(Default)]
@@ -60,3 +67,74 @@ impl Storages {
         }
     }
 }
+
+/// The heap memory of a storage, in bytes.
+///
+/// Returned by the `memory_usage` methods of the storages, and used by
+/// [`World::memory_report`](crate::world::World::memory_report).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemoryUsage {
+    /// The number of bytes that hold values.
+    pub used: usize,
+    /// The number of bytes that are allocated, including the unused capacity.
+    pub allocated: usize,
+}
+
+impl MemoryUsage {
+    /// Creates a [`MemoryUsage`] of `len` values of `size` bytes, in an allocation with room for `capacity` values.
+    pub const fn of_elements(size: usize, len: usize, capacity: usize) -> Self {
+        Self {
+            used: size * len,
+            allocated: size * capacity,
+        }
+    }
+
+    /// Returns the number of bytes that are allocated but unused.
+    pub const fn slack(&self) -> usize {
+        self.allocated.saturating_sub(self.used)
+    }
+
+    /// Returns the fraction of the allocated bytes that are unused, between `0.0` and `1.0`.
+    pub fn slack_ratio(&self) -> f32 {
+        if self.allocated == 0 {
+            0.0
+        } else {
+            self.slack() as f32 / self.allocated as f32
+        }
+    }
+
+    pub(crate) fn of_vec<T>(vec: &Vec<T>) -> Self {
+        Self::of_elements(size_of::<T>(), vec.len(), vec.capacity())
+    }
+
+    pub(crate) fn of_slice<T>(slice: &[T]) -> Self {
+        Self::of_elements(size_of::<T>(), slice.len(), slice.len())
+    }
+
+    pub(crate) fn of_map<K, V, S>(map: &HashMap<K, V, S>) -> Self {
+        Self::of_elements(size_of::<(K, V)>(), map.len(), map.capacity())
+    }
+}
+
+impl Add for MemoryUsage {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            used: self.used + rhs.used,
+            allocated: self.allocated + rhs.allocated,
+        }
+    }
+}
+
+impl AddAssign for MemoryUsage {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for MemoryUsage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
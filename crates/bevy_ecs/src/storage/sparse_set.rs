@@ -2,7 +2,7 @@ use crate::{
     change_detection::MaybeLocation,
     component::{CheckChangeTicks, ComponentId, ComponentInfo, ComponentTicks, Tick, TickCells},
     entity::{Entity, EntityRow},
-    storage::{Column, TableRow},
+    storage::{Column, MemoryUsage, TableRow},
 };
 use alloc::{boxed::Box, vec::Vec};
 use bevy_ptr::{OwningPtr, Ptr};
@@ -100,6 +100,19 @@ impl<I: SparseSetIndex, V> SparseArray<I, V> {
         self.values.clear();
     }
 
+    /// Returns the heap memory used by this array.
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of_vec(&self.values)
+    }
+
+    /// Removes the trailing empty slots, and shrinks the capacity of the array to its length.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        while let Some(None) = self.values.last() {
+            self.values.pop();
+        }
+        self.values.shrink_to_fit();
+    }
+
     /// Converts the [`SparseArray`] into an immutable variant.
     pub(crate) fn into_immutable(self) -> ImmutableSparseArray<I, V> {
         ImmutableSparseArray {
@@ -363,6 +376,18 @@ impl ComponentSparseSet {
     pub(crate) fn check_change_ticks(&mut self, check: CheckChangeTicks) {
         self.dense.check_change_ticks(check);
     }
+
+    /// Shrinks the capacity of this sparse set to its number of components.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.dense.shrink_to_fit();
+        self.entities.shrink_to_fit();
+        self.sparse.shrink_to_fit();
+    }
+
+    /// Returns the heap memory of this sparse set, including the components and the entity lookups.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.dense.memory_usage() + MemoryUsage::of_vec(&self.entities) + self.sparse.memory_usage()
+    }
 }
 
 /// A data structure that blends dense and sparse storage
@@ -557,6 +582,29 @@ impl<I: SparseSetIndex, V> SparseSet<I, V> {
             sparse: self.sparse.into_immutable(),
         }
     }
+
+    /// Shrinks the capacity of the sparse set to its length, not including the memory owned by its values.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.dense.shrink_to_fit();
+        self.indices.shrink_to_fit();
+        self.sparse.shrink_to_fit();
+    }
+
+    /// Returns the heap memory of the sparse set, not including the heap memory owned by its values.
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of_vec(&self.dense)
+            + MemoryUsage::of_vec(&self.indices)
+            + self.sparse.memory_usage()
+    }
+}
+
+impl<I: SparseSetIndex, V> ImmutableSparseSet<I, V> {
+    /// Returns the heap memory of the sparse set, not including the heap memory owned by its values.
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of_slice(&self.dense)
+            + MemoryUsage::of_slice(&self.indices)
+            + MemoryUsage::of_slice(&self.sparse.values)
+    }
 }
 
 /// Represents something that can be stored in a [`SparseSet`] as an integer.
@@ -650,6 +698,14 @@ impl SparseSets {
         }
     }
 
+    /// Shrinks the capacity of every [`ComponentSparseSet`] to its number of components.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for set in self.sets.values_mut() {
+            set.shrink_to_fit();
+        }
+        self.sets.shrink_to_fit();
+    }
+
     pub(crate) fn check_change_ticks(&mut self, check: CheckChangeTicks) {
         for set in self.sets.values_mut() {
             set.check_change_ticks(check);
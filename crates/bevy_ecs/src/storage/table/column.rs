@@ -6,7 +6,7 @@ use crate::{
 };
 use alloc::vec::Vec;
 use bevy_ptr::PtrMut;
-use core::panic::Location;
+use core::{mem::size_of, panic::Location};
 
 /// Very similar to a normal [`Column`], but with the capacities and lengths cut out for performance reasons.
 ///
@@ -36,6 +36,17 @@ impl ThinColumn {
         }
     }
 
+    /// Returns the heap memory of this column, which holds `len` components and has room for `capacity` components.
+    pub fn memory_usage(&self, len: usize, capacity: usize) -> MemoryUsage {
+        let changed_by = self
+            .changed_by
+            .as_ref()
+            .map(|_| size_of::<&'static Location<'static>>())
+            .unwrap_or_default();
+        let row_size = self.data.layout().size() + 2 * size_of::<Tick>() + changed_by;
+        MemoryUsage::of_elements(row_size, len, capacity)
+    }
+
     /// Swap-remove and drop the removed element, but the component at `row` must not be the last element.
     ///
     /// # Safety
@@ -104,6 +115,38 @@ impl ThinColumn {
             .map(|changed_by| changed_by.swap_remove_unchecked(row.index(), last_element_index));
     }
 
+    /// Swaps the components and ticks at `a` and `b`.
+    ///
+    /// # Safety
+    /// - `a` and `b` must be less than the length of the column, and must not be equal.
+    pub(crate) unsafe fn swap_unchecked(&mut self, a: TableRow, b: TableRow) {
+        let (a, b) = (a.index(), b.index());
+        // SAFETY: The caller ensures that `a` and `b` are in bounds and different.
+        unsafe { self.data.swap_unchecked(a, b) };
+        // SAFETY: The caller ensures that `a` and `b` are in bounds, and the tick arrays
+        // have the same length as `data`.
+        unsafe {
+            core::ptr::swap(
+                self.added_ticks.get_unchecked_raw(a),
+                self.added_ticks.get_unchecked_raw(b),
+            );
+            core::ptr::swap(
+                self.changed_ticks.get_unchecked_raw(a),
+                self.changed_ticks.get_unchecked_raw(b),
+            );
+        }
+        if let Some(changed_by) = self.changed_by.as_mut().into_option() {
+            // SAFETY: The caller ensures that `a` and `b` are in bounds, and `changed_by` has
+            // the same length as `data`.
+            unsafe {
+                core::ptr::swap(
+                    changed_by.get_unchecked_raw(a),
+                    changed_by.get_unchecked_raw(b),
+                );
+            }
+        }
+    }
+
     /// Call [`realloc`](std::alloc::realloc) to expand / shrink the memory allocation for this [`ThinColumn`]
     ///
     /// # Safety
@@ -365,6 +408,31 @@ impl Column {
         self.data.layout()
     }
 
+    /// Shrinks the capacity of the column to its length.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        self.added_ticks.shrink_to_fit();
+        self.changed_ticks.shrink_to_fit();
+        self.changed_by.as_mut().map(Vec::shrink_to_fit);
+    }
+
+    /// Returns the heap memory of this column.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let data = MemoryUsage::of_elements(
+            self.data.layout().size(),
+            self.data.len(),
+            self.data.capacity(),
+        );
+        let changed_by = self
+            .changed_by
+            .as_ref()
+            .map(MemoryUsage::of_vec)
+            .unwrap_or_default();
+        data + MemoryUsage::of_vec(&self.added_ticks)
+            + MemoryUsage::of_vec(&self.changed_ticks)
+            + changed_by
+    }
+
     /// Writes component data to the column at given row.
     /// Assumes the slot is initialized, calls drop.
     ///
@@ -3,7 +3,7 @@ use crate::{
     component::{CheckChangeTicks, ComponentId, ComponentInfo, ComponentTicks, Components, Tick},
     entity::Entity,
     query::DebugCheckedUnwrap,
-    storage::{blob_vec::BlobVec, ImmutableSparseSet, SparseSet},
+    storage::{blob_vec::BlobVec, ImmutableSparseSet, MemoryUsage, SparseSet},
 };
 use alloc::{boxed::Box, vec, vec::Vec};
 use bevy_platform::collections::HashMap;
@@ -639,6 +639,69 @@ impl Table {
         self.entities.capacity()
     }
 
+    /// Shrinks the capacity of the table to its number of entities, deallocating its columns if it is empty.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        let current_capacity = self.capacity();
+        if current_capacity == self.entities.len() {
+            return;
+        }
+        // If any of these reallocations trigger an unwind, the wrong capacity will be used while dropping this table - UB.
+        let _guard = AbortOnPanic;
+        if self.entities.is_empty() {
+            for col in self.columns.values_mut() {
+                // SAFETY: `current_capacity` is the capacity of the columns, and they have no elements.
+                unsafe { col.drop(current_capacity, 0) };
+            }
+            self.entities = Vec::new();
+        } else {
+            self.entities.shrink_to_fit();
+            let new_capacity = self.capacity();
+            if new_capacity < current_capacity {
+                // SAFETY:
+                // - `current_capacity` is the capacity of the columns
+                // - both capacities are non-zero, since the table isn't empty
+                unsafe {
+                    self.realloc_columns(
+                        NonZeroUsize::new_unchecked(current_capacity),
+                        NonZeroUsize::new_unchecked(new_capacity),
+                    );
+                }
+            }
+        }
+        core::mem::forget(_guard);
+    }
+
+    /// Swaps the entities and components at rows `a` and `b`.
+    ///
+    /// # Safety
+    /// - `a` and `b` must be less than the number of entities in the table, and must not be equal.
+    /// - The caller must update the locations of the swapped entities.
+    pub(crate) unsafe fn swap_rows_unchecked(&mut self, a: TableRow, b: TableRow) {
+        debug_assert!(a.index() < self.entities.len() && b.index() < self.entities.len());
+        self.entities.swap(a.index(), b.index());
+        for col in self.columns.values_mut() {
+            // SAFETY: The caller ensures that `a` and `b` are in bounds and different.
+            unsafe { col.swap_unchecked(a, b) };
+        }
+    }
+
+    /// Returns the heap memory of this table, including its entity list and all of its columns.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of_vec(&self.entities)
+            + self
+                .column_memory_usage()
+                .map(|(_, memory)| memory)
+                .sum::<MemoryUsage>()
+    }
+
+    /// Returns the heap memory of each column of this table.
+    pub fn column_memory_usage(&self) -> impl Iterator<Item = (ComponentId, MemoryUsage)> + '_ {
+        let (len, capacity) = (self.entities.len(), self.entities.capacity());
+        self.columns
+            .iter()
+            .map(move |(id, column)| (*id, column.memory_usage(len, capacity)))
+    }
+
     /// Checks if the [`Table`] is empty or not.
     ///
     /// Returns `true` if the table contains no entities, `false` otherwise.
@@ -812,6 +875,45 @@ impl Tables {
         }
     }
 
+    /// Removes every [`Table`] other than the empty table for which `keep` returns false, and renumbers the
+    /// remaining ones.
+    ///
+    /// Returns the new id of each table, indexed by its old id.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(TableId) -> bool) -> Vec<Option<TableId>> {
+        let mut next_id = 0;
+        let new_ids = (0..self.tables.len())
+            .map(|index| {
+                let id = TableId::from_usize(index);
+                (id == TableId::empty() || keep(id)).then(|| {
+                    next_id += 1;
+                    TableId::from_usize(next_id - 1)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut index = 0;
+        self.tables.retain(|_| {
+            index += 1;
+            new_ids[index - 1].is_some()
+        });
+        self.table_ids.retain(|_, id| match new_ids[id.as_usize()] {
+            Some(new_id) => {
+                *id = new_id;
+                true
+            }
+            None => false,
+        });
+        new_ids
+    }
+
+    /// Shrinks the capacity of every table to its number of entities.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for table in &mut self.tables {
+            table.shrink_to_fit();
+        }
+        self.table_ids.shrink_to_fit();
+    }
+
     pub(crate) fn check_change_ticks(&mut self, check: CheckChangeTicks) {
         for table in &mut self.tables {
             table.check_change_ticks(check);
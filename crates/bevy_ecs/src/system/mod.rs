@@ -130,6 +130,7 @@ mod input;
 mod observer_system;
 mod query;
 mod schedule_system;
+mod sorted_query;
 mod system;
 mod system_name;
 mod system_param;
@@ -148,6 +149,7 @@ pub use input::*;
 pub use observer_system::*;
 pub use query::*;
 pub use schedule_system::*;
+pub use sorted_query::*;
 pub use system::*;
 pub use system_name::*;
 pub use system_param::*;
@@ -0,0 +1,275 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{
+    change_detection::{DetectChanges, Ref},
+    component::{Component, Tick},
+    entity::{unique_slice::UniqueEntitySlice, Entity, EntityHashSet},
+    query::{QueryData, QueryFilter, QueryItem, ROQueryItem, With},
+    system::{Local, Query, SystemChangeTick, SystemParam},
+};
+
+/// A [`SystemParam`] that iterates over the entities of a [`Query`] in the order of their `K` component,
+/// keeping that order across system runs.
+///
+/// Unlike [`QueryIter::sort`](crate::query::QueryIter::sort), which sorts every entity each time it's called,
+/// a `SortedQuery` only re-sorts the entities whose `K` component changed, was inserted or was removed since
+/// it was last iterated, and merges them into the order it already has. This makes it cheap to iterate
+/// thousands of entities in a stable order every frame, like a render order or a turn order, when few of their
+/// keys change.
+///
+/// Entities with equal keys can also be iterated together with [`SortedQuery::iter_groups`].
+///
+/// Only entities that match `D` and `F` and have a `K` component are returned.
+/// `D` can't access `K` mutably, since the keys are read alongside it.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::system::SortedQuery;
+/// #[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Initiative(u32);
+///
+/// #[derive(Component)]
+/// struct Name(&'static str);
+///
+/// fn take_turns(mut turns: SortedQuery<&Name, (), Initiative>) {
+///     for name in turns.iter() {
+///         println!("{}'s turn", name.0);
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(take_turns);
+/// ```
+#[derive(SystemParam)]
+pub struct SortedQuery<
+    'w,
+    's,
+    D: QueryData + 'static,
+    F: QueryFilter + 'static,
+    K: Component + Ord + Clone,
+> {
+    query: Query<'w, 's, D, (F, With<K>)>,
+    keys: Query<'w, 's, (Entity, Ref<'static, K>), F>,
+    order: Local<'s, SortedQueryOrder<K>>,
+    ticks: SystemChangeTick,
+}
+
+/// The order of the entities of a [`SortedQuery`], kept across system runs.
+pub struct SortedQueryOrder<K> {
+    keys: Vec<K>,
+    entities: Vec<Entity>,
+    contained: EntityHashSet,
+    last_update: Option<Tick>,
+}
+
+impl<K> Default for SortedQueryOrder<K> {
+    fn default() -> Self {
+        Self {
+            keys: Vec::new(),
+            entities: Vec::new(),
+            contained: EntityHashSet::default(),
+            last_update: None,
+        }
+    }
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter, K: Component + Ord + Clone>
+    SortedQuery<'w, 's, D, F, K>
+{
+    /// Returns the underlying [`Query`], over every entity with a `K` component in no particular order.
+    pub fn query(&self) -> &Query<'w, 's, D, (F, With<K>)> {
+        &self.query
+    }
+
+    /// Returns the entities of the query, sorted by their `K` component.
+    pub fn entities(&mut self) -> &[Entity] {
+        self.update();
+        &self.order.entities
+    }
+
+    /// Returns an iterator over the read-only query items, sorted by the `K` component of their entities.
+    pub fn iter(&mut self) -> impl Iterator<Item = ROQueryItem<'_, 's, D>> {
+        self.update();
+        self.query
+            .iter_many_unique(self.order.slice(0..self.order.len()))
+    }
+
+    /// Returns an iterator over the query items, sorted by the `K` component of their entities.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = QueryItem<'_, 's, D>> {
+        self.update();
+        let entities = self.order.slice(0..self.order.len());
+        self.query.iter_many_unique_mut(entities)
+    }
+
+    /// Returns an iterator over every distinct value of `K`, in order, along with the read-only query items
+    /// of the entities that share it.
+    pub fn iter_groups(
+        &mut self,
+    ) -> impl Iterator<Item = (&K, impl Iterator<Item = ROQueryItem<'_, 's, D>>)> {
+        self.update();
+        let order = &*self.order;
+        let query = &self.query;
+        order
+            .keys
+            .chunk_by(PartialEq::eq)
+            .scan(0, |start, keys| {
+                let group = *start..*start + keys.len();
+                *start = group.end;
+                Some(group)
+            })
+            .map(move |group| {
+                (
+                    &order.keys[group.start],
+                    query.iter_many_unique(order.slice(group)),
+                )
+            })
+    }
+
+    /// Returns an iterator over the query items of the entities whose `K` component is equal to `key`.
+    pub fn iter_group_mut(&mut self, key: &K) -> impl Iterator<Item = QueryItem<'_, 's, D>> {
+        self.update();
+        let start = self.order.keys.partition_point(|k| k < key);
+        let end = self.order.keys.partition_point(|k| k <= key);
+        let entities = self.order.slice(start..end);
+        self.query.iter_many_unique_mut(entities)
+    }
+
+    /// Brings the order up to date with the keys that changed since the last update.
+    fn update(&mut self) {
+        let this_run = self.ticks.this_run();
+        let order = &mut *self.order;
+        let last_update = order.last_update.replace(this_run);
+
+        let mut changed = Vec::new();
+        let mut unchanged = 0;
+        for (entity, key) in &self.keys {
+            let is_changed = last_update
+                .is_none_or(|last_update| key.last_changed().is_newer_than(last_update, this_run));
+            if !is_changed && order.contained.contains(&entity) {
+                unchanged += 1;
+            } else {
+                changed.push((key.clone(), entity));
+            }
+        }
+        if changed.is_empty() && unchanged == order.len() {
+            return;
+        }
+
+        // Remove the entities that changed or no longer match, then merge the changed ones back in.
+        let changed_entities = changed
+            .iter()
+            .map(|(_, entity)| *entity)
+            .collect::<EntityHashSet>();
+        let mut kept = order
+            .keys
+            .drain(..)
+            .zip(order.entities.drain(..))
+            .filter(|(_, entity)| !changed_entities.contains(entity) && self.keys.contains(*entity))
+            .peekable();
+        changed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut changed = changed.into_iter().peekable();
+
+        let mut keys = Vec::with_capacity(unchanged + changed.len());
+        let mut entities = Vec::with_capacity(unchanged + changed.len());
+        loop {
+            let next = match (kept.peek(), changed.peek()) {
+                (Some((kept_key, _)), Some((changed_key, _))) if changed_key < kept_key => {
+                    changed.next()
+                }
+                (Some(_), _) => kept.next(),
+                (None, _) => changed.next(),
+            };
+            let Some((key, entity)) = next else {
+                break;
+            };
+            keys.push(key);
+            entities.push(entity);
+        }
+        drop(kept);
+
+        order.contained = entities.iter().copied().collect();
+        order.keys = keys;
+        order.entities = entities;
+    }
+}
+
+impl<K> SortedQueryOrder<K> {
+    fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    fn slice(&self, range: Range<usize>) -> &UniqueEntitySlice {
+        // SAFETY: Entities are only added to the order if they aren't already in it.
+        unsafe { UniqueEntitySlice::from_slice_unchecked(&self.entities[range]) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        component::Component,
+        prelude::World,
+        query::Without,
+        system::{SortedQuery, SystemState},
+    };
+
+    #[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    struct Key(u32);
+
+    #[derive(Component, PartialEq, Debug)]
+    struct Value(u32);
+
+    #[derive(Component)]
+    struct Hidden;
+
+    type Sorted = SortedQuery<'static, 'static, &'static Value, Without<Hidden>, Key>;
+
+    fn values(world: &mut World, state: &mut SystemState<Sorted>) -> Vec<u32> {
+        let mut sorted = state.get_mut(world);
+        sorted.iter().map(|value| value.0).collect()
+    }
+
+    #[test]
+    fn sorted_query_updates_incrementally() {
+        let mut world = World::new();
+        let entities = [3, 1, 2, 1].map(|key| world.spawn((Key(key), Value(key * 10))).id());
+        let mut state = SystemState::<Sorted>::new(&mut world);
+        assert_eq!(values(&mut world, &mut state), [10, 10, 20, 30]);
+
+        world.get_mut::<Key>(entities[0]).unwrap().0 = 0;
+        world.entity_mut(entities[1]).remove::<Key>();
+        world.entity_mut(entities[2]).insert(Hidden);
+        let spawned = world.spawn((Key(2), Value(25))).id();
+        assert_eq!(values(&mut world, &mut state), [30, 10, 25]);
+
+        // Entities without the data of the query are skipped, but stay in order.
+        world.entity_mut(entities[3]).remove::<Value>();
+        assert_eq!(values(&mut world, &mut state), [30, 25]);
+        let mut sorted = state.get_mut(&mut world);
+        assert_eq!(sorted.entities(), [entities[0], entities[3], spawned]);
+    }
+
+    #[test]
+    fn sorted_query_groups() {
+        let mut world = World::new();
+        for (key, value) in [(2, 1), (1, 2), (2, 3), (0, 4), (1, 5)] {
+            world.spawn((Key(key), Value(value)));
+        }
+        let mut state = SystemState::<SortedQuery<&mut Value, (), Key>>::new(&mut world);
+        let mut sorted = state.get_mut(&mut world);
+
+        for mut value in sorted.iter_group_mut(&Key(1)) {
+            value.0 *= 10;
+        }
+        let groups = sorted
+            .iter_groups()
+            .map(|(key, values)| {
+                let mut values = values.map(|value| value.0).collect::<Vec<_>>();
+                values.sort();
+                (key.0, values)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(groups, [(0, vec![4]), (1, vec![20, 50]), (2, vec![1, 3])]);
+    }
+}
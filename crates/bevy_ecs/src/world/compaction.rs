@@ -0,0 +1,335 @@
+use alloc::vec::Vec;
+
+use fixedbitset::FixedBitSet;
+use nonmax::NonMaxU32;
+
+use crate::{
+    archetype::ArchetypeRow,
+    storage::{TableId, TableRow},
+    world::{EntityRef, World},
+};
+
+impl World {
+    /// Releases the memory that the storages of this world have allocated but don't use.
+    ///
+    /// Tables, sparse sets and archetypes keep the capacity they grew to, so that entities can be spawned again
+    /// without reallocating, and archetypes and tables are kept even once all their entities are gone. After
+    /// despawning many entities, for example when unloading a level, this removes every empty
+    /// [`Archetype`](crate::archetype::Archetype) and every [`Table`](crate::storage::Table) that no archetype
+    /// uses anymore, and shrinks the columns of the remaining tables, the sparse sets and the archetype entity
+    /// lists to the number of entities they hold. Resources are not affected.
+    ///
+    /// Removing archetypes and tables renumbers the remaining ones, so this changes the
+    /// [`ArchetypeId`](crate::archetype::ArchetypeId)s and [`TableId`]s in entity locations, and
+    /// [`Archetypes::compactions`](crate::archetype::Archetypes::compactions) increases. Every
+    /// [`QueryState`](crate::query::QueryState) rebuilds its cache of matched archetypes and tables the next time
+    /// it is updated, which systems and the non-`manual` query methods do automatically. The `manual` query
+    /// methods panic until [`QueryState::update_archetypes`](crate::query::QueryState::update_archetypes) is called.
+    ///
+    /// Use [`World::memory_report`] to see how much memory this releases, and
+    /// [`World::sort_table_rows_by_key`] to also improve the iteration order of the tables.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Component)]
+    /// struct Tree;
+    ///
+    /// let mut world = World::new();
+    /// let level = world.spawn_batch((0..1000).map(|_| Tree)).collect::<Vec<_>>();
+    /// for tree in level {
+    ///     world.despawn(tree);
+    /// }
+    ///
+    /// let before = world.memory_report().total();
+    /// world.compact();
+    /// assert!(world.memory_report().total().allocated < before.allocated);
+    /// ```
+    pub fn compact(&mut self) {
+        self.flush();
+        self.remove_empty_archetypes();
+        self.storages.tables.shrink_to_fit();
+        self.storages.sparse_sets.shrink_to_fit();
+        self.archetypes.shrink_to_fit();
+    }
+
+    /// Removes the empty archetypes and the tables they leave unused, and updates the entity locations.
+    fn remove_empty_archetypes(&mut self) {
+        if !self.archetypes.remove_empty() {
+            return;
+        }
+
+        let mut used_tables = FixedBitSet::with_capacity(self.storages.tables.len());
+        for archetype in self.archetypes.iter() {
+            used_tables.insert(archetype.table_id().as_usize());
+        }
+        let new_table_ids = self
+            .storages
+            .tables
+            .retain(|table_id| used_tables.contains(table_id.as_usize()));
+        self.archetypes.remap_table_ids(&new_table_ids);
+
+        for archetype in self.archetypes.iter() {
+            for entity in archetype.entities() {
+                let mut location = self
+                    .entities
+                    .get(entity.id())
+                    .expect("entities in an archetype must be spawned");
+                location.archetype_id = archetype.id();
+                location.table_id = archetype.table_id();
+                // SAFETY: The entity is spawned, and only the ids of its archetype and table changed.
+                unsafe { self.entities.set(entity.id().index(), Some(location)) };
+            }
+        }
+    }
+
+    /// Reorders the rows of every [`Table`](crate::storage::Table) by the key that `key` returns for each
+    /// entity, so that queries iterate over the entities of a table in that order.
+    ///
+    /// This keeps entities that are accessed together next to each other in memory, for example by sorting them
+    /// by a spatial cell or a parent. The sort is stable, and it doesn't trigger change detection.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Component)]
+    /// struct Depth(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn_batch([Depth(3), Depth(1), Depth(2)]);
+    ///
+    /// world.sort_table_rows_by_key(|entity| entity.get::<Depth>().map(|depth| depth.0));
+    /// let mut query = world.query::<&Depth>();
+    /// let depths = query.iter(&world).map(|depth| depth.0).collect::<Vec<_>>();
+    /// assert_eq!(depths, [1, 2, 3]);
+    /// ```
+    pub fn sort_table_rows_by_key<K: Ord>(&mut self, mut key: impl FnMut(EntityRef) -> K) {
+        self.flush();
+        for index in 0..self.storages.tables.len() {
+            let table_id = TableId::from_usize(index);
+            let table = &self.storages.tables[table_id];
+            if table.entity_count() < 2 {
+                continue;
+            }
+            let mut keys = table
+                .entities()
+                .iter()
+                .enumerate()
+                .map(|(row, &entity)| (key(self.entity(entity)), row))
+                .collect::<Vec<_>>();
+            keys.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let order = keys.into_iter().map(|(_, row)| row).collect::<Vec<_>>();
+            if order.iter().enumerate().all(|(row, &source)| row == source) {
+                continue;
+            }
+
+            let table = &mut self.storages.tables[table_id];
+            // `position[row]` is the current position of the entity at `row` before sorting,
+            // and `source[position]` is the row before sorting of the entity at `position`.
+            let mut position = (0..order.len()).collect::<Vec<_>>();
+            let mut source = position.clone();
+            for (row, &wanted) in order.iter().enumerate() {
+                let current = position[wanted];
+                if current != row {
+                    // SAFETY: Both rows are less than the number of entities, and they are different.
+                    unsafe { table.swap_rows_unchecked(table_row(row), table_row(current)) };
+                    let displaced = source[row];
+                    source[row] = wanted;
+                    source[current] = displaced;
+                    position[wanted] = row;
+                    position[displaced] = current;
+                }
+            }
+
+            for (row, &entity) in table.entities().iter().enumerate() {
+                let mut location = self
+                    .entities
+                    .get(entity)
+                    .expect("entities in a table must be spawned");
+                location.table_row = table_row(row);
+                self.archetypes[location.archetype_id]
+                    .set_entity_table_row(location.archetype_row, location.table_row);
+                // SAFETY: The entity is spawned, and only its table row changed.
+                unsafe { self.entities.set(entity.index(), Some(location)) };
+            }
+        }
+
+        // Make archetypes, which queries over sparse set components iterate, follow the order of their table.
+        for archetype in &mut self.archetypes.archetypes {
+            if !archetype.sort_entities_by_table_row() {
+                continue;
+            }
+            for (row, entity) in archetype.entities().iter().enumerate() {
+                let mut location = self
+                    .entities
+                    .get(entity.id())
+                    .expect("entities in an archetype must be spawned");
+                location.archetype_row = ArchetypeRow::new(nonmax_row(row));
+                // SAFETY: The entity is spawned, and only its archetype row changed.
+                unsafe { self.entities.set(entity.id().index(), Some(location)) };
+            }
+        }
+    }
+}
+
+fn table_row(row: usize) -> TableRow {
+    TableRow::new(nonmax_row(row))
+}
+
+fn nonmax_row(row: usize) -> NonMaxU32 {
+    // SAFETY: Table and archetype rows are always less than `u32::MAX`.
+    unsafe { NonMaxU32::new_unchecked(row as u32) }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        component::Component,
+        entity::Entity,
+        query::Without,
+        system::{Query, SystemState},
+        world::World,
+    };
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq)]
+    struct A(u32);
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq)]
+    #[component(storage = "SparseSet")]
+    struct B(u32);
+
+    #[test]
+    fn compact_keeps_entities() {
+        let mut world = World::new();
+        let entities = world
+            .spawn_batch((0..100).map(|i| (A(i), B(i))))
+            .collect::<Vec<_>>();
+        for &entity in &entities[10..] {
+            world.despawn(entity);
+        }
+        let empty = world.spawn(A(0)).id();
+        world.despawn(empty);
+
+        let before = world.memory_report();
+        world.compact();
+        let after = world.memory_report();
+        assert!(after.component_data().allocated < before.component_data().allocated);
+        assert_eq!(after.component_data().slack(), 0);
+
+        for (i, &entity) in entities[..10].iter().enumerate() {
+            assert_eq!(world.get::<A>(entity), Some(&A(i as u32)));
+            assert_eq!(world.get::<B>(entity), Some(&B(i as u32)));
+        }
+        let mut query = world.query::<(&A, &B)>();
+        assert_eq!(query.iter(&world).count(), 10);
+
+        // Compacted storages grow again when needed.
+        world.spawn_batch((0..100).map(|i| (A(i), B(i))));
+        world.spawn(A(0));
+        assert_eq!(query.iter(&world).count(), 110);
+    }
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq)]
+    struct C(u32);
+
+    #[test]
+    fn compact_removes_empty_archetypes_and_tables() {
+        let mut world = World::new();
+        let mut dense = world.query::<&A>();
+        let mut sparse = world.query::<(Entity, &B)>();
+        let mut system_state = SystemState::<Query<&A, Without<C>>>::new(&mut world);
+
+        let kept = world.spawn((A(0), B(0))).id();
+        let a = world.spawn(A(1)).id();
+        let removed = [
+            world.spawn((A(2), C(2))).id(),
+            world.spawn((A(3), B(3), C(3))).id(),
+            world.spawn(C(4)).id(),
+        ];
+        assert_eq!(dense.iter(&world).count(), 4);
+        assert_eq!(sparse.iter(&world).count(), 2);
+        assert_eq!(system_state.get(&world).iter().count(), 2);
+        for entity in removed {
+            world.despawn(entity);
+        }
+
+        let archetypes = world.archetypes().len();
+        let tables = world.storages().tables.len();
+        world.compact();
+        assert_eq!(world.archetypes().len(), archetypes - 3);
+        // `(A, C)` and `(A, B, C)` share a table, since `B` is stored in a sparse set.
+        assert_eq!(world.storages().tables.len(), tables - 2);
+        assert_eq!(world.archetypes().compactions(), 1);
+
+        // Cached queries match the renumbered archetypes and tables again.
+        let mut values = dense.iter(&world).map(|a| a.0).collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, [0, 1]);
+        assert_eq!(sparse.iter(&world).collect::<Vec<_>>(), [(kept, &B(0))]);
+        assert_eq!(system_state.get(&world).iter().count(), 2);
+        assert_eq!(world.get::<A>(a), Some(&A(1)));
+        for entity in [kept, a] {
+            let location = world.entity(entity).location();
+            let archetype = &world.archetypes()[location.archetype_id];
+            assert_eq!(archetype.table_id(), location.table_id);
+            let table = &world.storages().tables[location.table_id];
+            assert_eq!(table.entities()[location.table_row.index()], entity);
+        }
+
+        // Entities can move to archetypes that were removed.
+        world.entity_mut(a).insert((B(1), C(1)));
+        world.spawn(C(5));
+        values = dense.iter(&world).map(|a| a.0).collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, [0, 1]);
+        assert_eq!(sparse.iter(&world).count(), 2);
+        assert_eq!(system_state.get(&world).iter().count(), 1);
+
+        // Compacting a world without empty archetypes keeps the ids.
+        world.compact();
+        assert_eq!(world.archetypes().compactions(), 2);
+        world.compact();
+        assert_eq!(world.archetypes().compactions(), 2);
+        assert_eq!(system_state.get(&world).iter().count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "The World was compacted")]
+    fn manual_query_panics_after_compact() {
+        let mut world = World::new();
+        let mut query = world.query::<&A>();
+        let entity = world.spawn((A(0), B(0))).id();
+        world.spawn(A(1));
+        query.update_archetypes(&world);
+        world.despawn(entity);
+        world.compact();
+        query.iter_manual(&world).count();
+    }
+
+    #[test]
+    fn sort_table_rows() {
+        let mut world = World::new();
+        let keys = [5, 3, 9, 1, 7, 3, 0, 8];
+        let entities = world
+            .spawn_batch(keys.map(|key| (A(key), B(key))))
+            .collect::<Vec<_>>();
+
+        world.sort_table_rows_by_key(|entity| entity.get::<A>().map(|a| a.0));
+
+        let mut query = world.query::<(&A, &B)>();
+        let sorted = query.iter(&world).map(|(a, _)| a.0).collect::<Vec<_>>();
+        assert_eq!(sorted, [0, 1, 3, 3, 5, 7, 8, 9]);
+        for (&entity, &key) in entities.iter().zip(&keys) {
+            assert_eq!(world.get::<A>(entity), Some(&A(key)));
+            assert_eq!(world.get::<B>(entity), Some(&B(key)));
+            let location = world.entity(entity).location();
+            let table = &world.storages().tables[location.table_id];
+            assert_eq!(table.entities()[location.table_row.index()], entity);
+            let archetype = &world.archetypes()[location.archetype_id];
+            assert_eq!(
+                archetype.entities()[location.archetype_row.index()].table_row(),
+                location.table_row
+            );
+        }
+    }
+}
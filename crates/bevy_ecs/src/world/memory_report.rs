@@ -0,0 +1,273 @@
+use alloc::vec::Vec;
+
+use bevy_platform::collections::HashMap;
+use bevy_utils::prelude::DebugName;
+
+use crate::{
+    archetype::ArchetypeId,
+    component::{ComponentId, ComponentInfo, Components, StorageType},
+    storage::{MemoryUsage, TableId},
+    world::World,
+};
+
+/// The memory used by all instances of a component type, part of a [`MemoryReport`].
+#[derive(Clone, Debug)]
+pub struct ComponentMemoryReport {
+    /// The id of the component.
+    pub id: ComponentId,
+    /// The name of the component.
+    pub name: DebugName,
+    /// How the component is stored.
+    pub storage_type: StorageType,
+    /// The number of entities with this component.
+    pub instances: usize,
+    /// The memory of the component values and their change detection ticks, including the unused capacity
+    /// of the [`Table`](crate::storage::Table) columns or of the sparse set.
+    pub memory: MemoryUsage,
+}
+
+/// The memory used by a [`Table`](crate::storage::Table), part of a [`MemoryReport`].
+#[derive(Clone, Debug)]
+pub struct TableMemoryReport {
+    /// The id of the table.
+    pub id: TableId,
+    /// The number of entities in the table.
+    pub entity_count: usize,
+    /// The number of entities the table can hold without reallocating.
+    pub entity_capacity: usize,
+    /// The number of component columns of the table.
+    pub component_count: usize,
+    /// The memory of the columns and the entity list of the table.
+    pub memory: MemoryUsage,
+}
+
+/// The size of an [`Archetype`](crate::archetype::Archetype), part of a [`MemoryReport`].
+#[derive(Clone, Debug)]
+pub struct ArchetypeMemoryReport {
+    /// The id of the archetype.
+    pub id: ArchetypeId,
+    /// The id of the table that stores the table components of the archetype.
+    pub table_id: TableId,
+    /// The number of entities in the archetype.
+    pub entity_count: usize,
+    /// The number of components of the archetype.
+    pub component_count: usize,
+    /// The memory of the metadata of the archetype.
+    pub memory: MemoryUsage,
+}
+
+/// A breakdown of the heap memory used by the entities and components of a [`World`], returned by
+/// [`World::memory_report`].
+///
+/// Resources are not included.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryReport {
+    /// The memory used by each component type, sorted by allocated memory, largest first.
+    pub components: Vec<ComponentMemoryReport>,
+    /// The memory used by each table, in the order of their [`TableId`]s.
+    pub tables: Vec<TableMemoryReport>,
+    /// The size of each archetype, in the order of their [`ArchetypeId`]s.
+    pub archetypes: Vec<ArchetypeMemoryReport>,
+    /// The memory of the metadata of all entities, see [`Entities::memory_usage`](crate::entity::Entities::memory_usage).
+    pub entities: MemoryUsage,
+    /// The memory of the metadata of all archetypes, see [`Archetypes::memory_usage`](crate::archetype::Archetypes::memory_usage).
+    pub archetype_metadata: MemoryUsage,
+    /// The memory of the registered bundles, see [`Bundles::memory_usage`](crate::bundle::Bundles::memory_usage).
+    pub bundle_metadata: MemoryUsage,
+}
+
+impl MemoryReport {
+    /// Returns the total memory of the report.
+    pub fn total(&self) -> MemoryUsage {
+        self.component_data() + self.metadata()
+    }
+
+    /// Returns the memory of all tables and sparse sets.
+    pub fn component_data(&self) -> MemoryUsage {
+        let tables = self
+            .tables
+            .iter()
+            .map(|table| table.memory)
+            .sum::<MemoryUsage>();
+        let sparse_sets = self
+            .components
+            .iter()
+            .filter(|component| component.storage_type == StorageType::SparseSet)
+            .map(|component| component.memory)
+            .sum::<MemoryUsage>();
+        tables + sparse_sets
+    }
+
+    /// Returns the memory of the entity, archetype and bundle metadata.
+    pub fn metadata(&self) -> MemoryUsage {
+        self.entities + self.archetype_metadata + self.bundle_metadata
+    }
+
+    /// Returns the archetypes with at most `max_entities` entities.
+    ///
+    /// Many archetypes with few entities are a sign of fragmentation, which is usually caused by
+    /// inserting and removing components with many different combinations.
+    pub fn small_archetypes(
+        &self,
+        max_entities: usize,
+    ) -> impl Iterator<Item = &ArchetypeMemoryReport> + '_ {
+        self.archetypes
+            .iter()
+            .filter(move |archetype| archetype.entity_count <= max_entities)
+    }
+
+    /// Returns the tables where at least `min_slack_ratio` of the allocated memory is unused,
+    /// for example because most of their entities were despawned.
+    pub fn tables_with_slack(
+        &self,
+        min_slack_ratio: f32,
+    ) -> impl Iterator<Item = &TableMemoryReport> + '_ {
+        self.tables.iter().filter(move |table| {
+            table.memory.allocated > 0 && table.memory.slack_ratio() >= min_slack_ratio
+        })
+    }
+}
+
+impl World {
+    /// Returns a breakdown of the heap memory used by the entities and components of this world,
+    /// per component type, table and archetype.
+    ///
+    /// This walks every table, sparse set and archetype of the world, so it's best not to call it every frame
+    /// in large worlds.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(f32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn_batch((0..100).map(|_| Health(10.0)));
+    ///
+    /// let report = world.memory_report();
+    /// let health = &report.components[0];
+    /// assert_eq!(health.instances, 100);
+    /// assert!(health.memory.used >= 100 * size_of::<Health>());
+    /// ```
+    pub fn memory_report(&self) -> MemoryReport {
+        let components = self.components();
+        let storages = self.storages();
+
+        let mut component_reports = HashMap::<ComponentId, ComponentMemoryReport>::default();
+
+        let mut tables = Vec::with_capacity(storages.tables.len());
+        for (index, table) in storages.tables.iter().enumerate() {
+            for (id, memory) in table.column_memory_usage() {
+                let report = component_entry(&mut component_reports, components, id);
+                report.instances += table.entity_count() as usize;
+                report.memory += memory;
+            }
+            tables.push(TableMemoryReport {
+                id: TableId::from_usize(index),
+                entity_count: table.entity_count() as usize,
+                entity_capacity: table.entity_capacity(),
+                component_count: table.component_count(),
+                memory: table.memory_usage(),
+            });
+        }
+        for (id, sparse_set) in storages.sparse_sets.iter() {
+            let report = component_entry(&mut component_reports, components, id);
+            report.instances += sparse_set.len();
+            report.memory += sparse_set.memory_usage();
+        }
+
+        let archetypes = self
+            .archetypes()
+            .iter()
+            .map(|archetype| ArchetypeMemoryReport {
+                id: archetype.id(),
+                table_id: archetype.table_id(),
+                entity_count: archetype.len() as usize,
+                component_count: archetype.component_count(),
+                memory: archetype.memory_usage(),
+            })
+            .collect();
+
+        let mut components = component_reports.into_values().collect::<Vec<_>>();
+        components.sort_by(|a, b| {
+            b.memory
+                .allocated
+                .cmp(&a.memory.allocated)
+                .then(a.id.cmp(&b.id))
+        });
+
+        MemoryReport {
+            components,
+            tables,
+            archetypes,
+            entities: self.entities().memory_usage(),
+            archetype_metadata: self.archetypes().memory_usage(),
+            bundle_metadata: self.bundles().memory_usage(),
+        }
+    }
+}
+
+fn component_entry<'a>(
+    reports: &'a mut HashMap<ComponentId, ComponentMemoryReport>,
+    components: &Components,
+    id: ComponentId,
+) -> &'a mut ComponentMemoryReport {
+    reports.entry(id).or_insert_with(|| {
+        let info = components.get_info(id);
+        ComponentMemoryReport {
+            id,
+            name: info.map_or_else(|| DebugName::borrowed("<unknown>"), ComponentInfo::name),
+            storage_type: info.map_or(StorageType::Table, ComponentInfo::storage_type),
+            instances: 0,
+            memory: MemoryUsage::default(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        component::{Component, StorageType},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct A(#[expect(dead_code, reason = "Only used to give the component a size.")] u64);
+
+    #[derive(Component)]
+    #[component(storage = "SparseSet")]
+    struct B(#[expect(dead_code, reason = "Only used to give the component a size.")] u32);
+
+    #[test]
+    fn memory_report() {
+        let mut world = World::new();
+        let entities = world
+            .spawn_batch((0..64).map(|i| (A(i), B(i as u32))))
+            .collect::<alloc::vec::Vec<_>>();
+        world.spawn(A(0));
+
+        let report = world.memory_report();
+        let a = world.component_id::<A>().unwrap();
+        let b = world.component_id::<B>().unwrap();
+        let a = report.components.iter().find(|c| c.id == a).unwrap();
+        let b = report.components.iter().find(|c| c.id == b).unwrap();
+        assert_eq!(a.instances, 65);
+        assert_eq!(b.instances, 64);
+        assert_eq!(b.storage_type, StorageType::SparseSet);
+        assert!(a.memory.used >= 65 * size_of::<u64>());
+        assert!(b.memory.used >= 64 * size_of::<u32>());
+        assert!(a.memory.allocated >= a.memory.used);
+        assert_eq!(report.small_archetypes(1).count(), 2);
+        assert!(report.total().used > a.memory.used + b.memory.used);
+
+        for &entity in &entities[1..] {
+            world.despawn(entity);
+        }
+        let report = world.memory_report();
+        let table = world.entity(entities[0]).location().table_id;
+        let slack = report
+            .tables_with_slack(0.9)
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(slack.len(), 1);
+        assert_eq!(slack[0].id, table);
+    }
+}
@@ -1,12 +1,14 @@
 //! Defines the [`World`] and APIs for accessing it directly.
 
 pub(crate) mod command_queue;
+mod compaction;
 mod deferred_world;
 mod entity_fetch;
 mod entity_ref;
 pub mod error;
 mod filtered_resource;
 mod identifier;
+mod memory_report;
 mod spawn_batch;
 pub mod unsafe_world_cell;
 
@@ -34,6 +36,7 @@ pub use entity_ref::{
 };
 pub use filtered_resource::*;
 pub use identifier::WorldId;
+pub use memory_report::*;
 pub use spawn_batch::*;
 
 use crate::{
@@ -1188,7 +1191,7 @@ impl World {
 
     /// # Safety
     /// must be called on an entity that was just allocated
-    unsafe fn spawn_at_empty_internal(
+    pub(crate) unsafe fn spawn_at_empty_internal(
         &mut self,
         entity: Entity,
         caller: MaybeLocation,
@@ -2,20 +2,28 @@
 
 use core::any::TypeId;
 
+use alloc::collections::VecDeque;
 use anyhow::{anyhow, Result as AnyhowResult};
+use bevy_app::Update;
 use bevy_ecs::{
-    component::ComponentId,
+    change_detection::Mut,
+    component::{ComponentId, Tick},
     entity::Entity,
     event::EventCursor,
     hierarchy::ChildOf,
     lifecycle::RemovedComponentEntity,
     query::QueryBuilder,
-    reflect::{AppTypeRegistry, ReflectComponent, ReflectResource},
-    system::{In, Local},
+    reflect::{
+        AppTypeRegistry, ReflectBufferedEvent, ReflectComponent, ReflectEntityEvent, ReflectEvent,
+        ReflectResource,
+    },
+    resource::Resource,
+    schedule::{InternedScheduleLabel, Schedules, Stepping},
+    system::{In, Local, ResMut},
     world::{EntityRef, EntityWorldMut, FilteredEntityRef, World},
 };
 use bevy_log::warn_once;
-use bevy_platform::collections::HashMap;
+use bevy_platform::collections::{HashMap, HashSet};
 use bevy_reflect::{
     serde::{ReflectSerializer, TypedReflectDeserializer},
     GetPath, PartialReflect, TypeRegistration, TypeRegistry,
@@ -28,6 +36,7 @@ use crate::{
     schemas::{
         json_schema::{export_type, JsonSchemaBevyType},
         open_rpc::OpenRpcDocument,
+        schedule_graph::export_schedule_graph,
     },
     BrpError, BrpResult,
 };
@@ -35,6 +44,21 @@ use crate::{
 #[cfg(all(feature = "http", not(target_family = "wasm")))]
 use {crate::schemas::open_rpc::ServerObject, bevy_utils::default};
 
+#[cfg(feature = "bevy_scene")]
+use {
+    bevy_ecs::entity::EntityHashMap,
+    bevy_scene::{
+        serde::{SceneDeserializer, SceneSerializer},
+        DynamicSceneBuilder, SceneFilter,
+    },
+};
+
+#[cfg(feature = "bevy_time")]
+use {
+    bevy_reflect::TypePath,
+    bevy_time::{Time, Virtual},
+};
+
 /// The method path for a `world.get_components` request.
 pub const BRP_GET_COMPONENTS_METHOD: &str = "world.get_components";
 
@@ -68,6 +92,9 @@ pub const BRP_GET_COMPONENTS_AND_WATCH_METHOD: &str = "world.get_components+watc
 /// The method path for a `world.list_components+watch` request.
 pub const BRP_LIST_COMPONENTS_AND_WATCH_METHOD: &str = "world.list_components+watch";
 
+/// The method path for a `world.query+watch` request.
+pub const BRP_QUERY_AND_WATCH_METHOD: &str = "world.query+watch";
+
 /// The method path for a `world.get_resources` request.
 pub const BRP_GET_RESOURCE_METHOD: &str = "world.get_resources";
 
@@ -86,6 +113,51 @@ pub const BRP_LIST_RESOURCES_METHOD: &str = "world.list_resources";
 /// The method path for a `registry.schema` request.
 pub const BRP_REGISTRY_SCHEMA_METHOD: &str = "registry.schema";
 
+/// The method path for a `app.pause` request.
+pub const BRP_PAUSE_METHOD: &str = "app.pause";
+
+/// The method path for a `app.resume` request.
+pub const BRP_RESUME_METHOD: &str = "app.resume";
+
+/// The method path for a `app.step` request.
+pub const BRP_STEP_METHOD: &str = "app.step";
+
+/// The method path for a `app.status` request.
+pub const BRP_STATUS_METHOD: &str = "app.status";
+
+/// The method path for a `schedule.run` request.
+pub const BRP_RUN_SCHEDULE_METHOD: &str = "schedule.run";
+
+/// The method path for a `time.set_relative_speed` request.
+#[cfg(feature = "bevy_time")]
+pub const BRP_SET_RELATIVE_SPEED_METHOD: &str = "time.set_relative_speed";
+
+/// The method path for a `schedule.graph` request.
+pub const BRP_SCHEDULE_GRAPH_METHOD: &str = "schedule.graph";
+
+/// The method path for a `world.send_event` request.
+pub const BRP_SEND_EVENT_METHOD: &str = "world.send_event";
+
+/// The method path for a `world.trigger_event` request.
+pub const BRP_TRIGGER_EVENT_METHOD: &str = "world.trigger_event";
+
+/// The method path for a `world.watch_history` request.
+pub const BRP_WATCH_HISTORY_METHOD: &str = "world.watch_history";
+
+/// The method path for a `world.unwatch_history` request.
+pub const BRP_UNWATCH_HISTORY_METHOD: &str = "world.unwatch_history";
+
+/// The method path for a `world.history` request.
+pub const BRP_HISTORY_METHOD: &str = "world.history";
+
+/// The method path for a `world.snapshot` request.
+#[cfg(feature = "bevy_scene")]
+pub const BRP_SNAPSHOT_METHOD: &str = "world.snapshot";
+
+/// The method path for a `world.restore` request.
+#[cfg(feature = "bevy_scene")]
+pub const BRP_RESTORE_METHOD: &str = "world.restore";
+
 /// The method path for a `rpc.discover` request.
 pub const RPC_DISCOVER_METHOD: &str = "rpc.discover";
 
@@ -299,6 +371,247 @@ pub struct BrpMutateResourcesParams {
     pub value: Value,
 }
 
+/// `app.pause`: Enables [`Stepping`] for the given schedules, so that their systems only run
+/// when stepped with `app.step`.
+///
+/// The server responds with a null.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpPauseParams {
+    /// The names of the schedules to pause, as printed by their
+    /// [`Debug`](core::fmt::Debug) implementation: e.g. `Update` or `FixedUpdate`.
+    ///
+    /// Defaults to `["Update"]`.
+    #[serde(default = "BrpPauseParams::default_schedules")]
+    pub schedules: Vec<String>,
+}
+
+impl BrpPauseParams {
+    fn default_schedules() -> Vec<String> {
+        vec![format!("{:?}", Update)]
+    }
+}
+
+impl Default for BrpPauseParams {
+    fn default() -> Self {
+        Self {
+            schedules: Self::default_schedules(),
+        }
+    }
+}
+
+/// `app.step`: Runs the paused schedules for the given number of frames.
+///
+/// The server responds with a null.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpStepParams {
+    /// The number of frames to run the paused schedules for. Defaults to 1.
+    #[serde(default = "BrpStepParams::default_frames")]
+    pub frames: u32,
+}
+
+impl BrpStepParams {
+    fn default_frames() -> u32 {
+        1
+    }
+}
+
+impl Default for BrpStepParams {
+    fn default() -> Self {
+        Self {
+            frames: Self::default_frames(),
+        }
+    }
+}
+
+/// `schedule.run`: Runs a schedule once, immediately.
+///
+/// The server responds with a null.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpRunScheduleParams {
+    /// The name of the schedule to run, as printed by its [`Debug`](core::fmt::Debug)
+    /// implementation: e.g. `FixedUpdate`.
+    pub label: String,
+}
+
+/// `time.set_relative_speed`: Sets the speed at which [`Time<Virtual>`] advances relative to
+/// the real time.
+///
+/// The server responds with a null.
+///
+/// [`Time<Virtual>`]: bevy_time::Virtual
+#[cfg(feature = "bevy_time")]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpSetRelativeSpeedParams {
+    /// The new relative speed: e.g. `2.0` to run twice as fast, or `0.0` to stop the clock.
+    pub speed: f64,
+}
+
+/// `schedule.graph`: Exports the systems, system sets and system graph of schedules.
+///
+/// The server responds with a list of [`ScheduleGraphSchema`]s.
+///
+/// [`ScheduleGraphSchema`]: crate::schemas::schedule_graph::ScheduleGraphSchema
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BrpScheduleGraphParams {
+    /// The names of the schedules to export, as printed by their
+    /// [`Debug`](core::fmt::Debug) implementation: e.g. `Update` or `FixedUpdate`.
+    ///
+    /// If this is empty, every schedule is exported.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<String>,
+}
+
+/// `world.send_event`: Writes a [`BufferedEvent`] to its [`Events`] resource.
+///
+/// The server responds with a null.
+///
+/// [`BufferedEvent`]: bevy_ecs::event::BufferedEvent
+/// [`Events`]: bevy_ecs::event::Events
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpSendEventParams {
+    /// The [full path] of the event type to send.
+    ///
+    /// [full path]: bevy_reflect::TypePath::type_path
+    pub event: String,
+
+    /// The serialized value of the event to be sent.
+    pub value: Value,
+}
+
+/// `world.trigger_event`: Triggers an [`Event`], running the observers watching for it.
+///
+/// The server responds with a null.
+///
+/// [`Event`]: bevy_ecs::event::Event
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpTriggerEventParams {
+    /// The [full path] of the event type to trigger.
+    ///
+    /// [full path]: bevy_reflect::TypePath::type_path
+    pub event: String,
+
+    /// The serialized value of the event to be triggered.
+    pub value: Value,
+
+    /// The entities to trigger the event on.
+    ///
+    /// If this is empty, the event is triggered without a target. Otherwise, the event must be
+    /// an [`EntityEvent`](bevy_ecs::event::EntityEvent).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<Entity>,
+}
+
+/// `world.watch_history`: Starts recording the values of components of an entity, every time
+/// they change.
+///
+/// The server responds with a null.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpWatchHistoryParams {
+    /// The ID of the entity whose components will be recorded.
+    pub entity: Entity,
+
+    /// The [full paths] of the component types to record.
+    ///
+    /// [full paths]: bevy_reflect::TypePath::type_path
+    pub components: Vec<String>,
+
+    /// The maximum number of values to keep for each component, after which the oldest values
+    /// are discarded. Defaults to 300.
+    #[serde(default = "BrpWatchHistoryParams::default_capacity")]
+    pub capacity: usize,
+}
+
+impl BrpWatchHistoryParams {
+    fn default_capacity() -> usize {
+        300
+    }
+}
+
+/// `world.unwatch_history`: Stops recording components of an entity, and discards their history.
+///
+/// The server responds with a null.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpUnwatchHistoryParams {
+    /// The ID of the entity whose components should no longer be recorded.
+    pub entity: Entity,
+
+    /// The [full paths] of the component types to stop recording.
+    ///
+    /// If this is empty, every component of the entity stops being recorded.
+    ///
+    /// [full paths]: bevy_reflect::TypePath::type_path
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<String>,
+}
+
+/// `world.history`: Returns the recorded values of components of an entity.
+///
+/// The server responds with a [`BrpHistoryResponse`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpHistoryParams {
+    /// The ID of the entity whose history is requested.
+    pub entity: Entity,
+
+    /// The [full paths] of the component types whose history is requested.
+    ///
+    /// [full paths]: bevy_reflect::TypePath::type_path
+    pub components: Vec<String>,
+
+    /// Only return the values recorded over this many last frames, along with the value the
+    /// component had at the start of that window.
+    ///
+    /// If this is absent, every recorded value is returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frames: Option<u32>,
+}
+
+/// `world.snapshot`: Serializes the entities and resources of the world into a
+/// [`DynamicScene`](bevy_scene::DynamicScene).
+///
+/// The server responds with the serialized scene.
+#[cfg(feature = "bevy_scene")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BrpSnapshotParams {
+    /// The components to include in the snapshot. Defaults to every reflected component.
+    #[serde(default)]
+    pub components: BrpSceneFilter,
+
+    /// The resources to include in the snapshot. Defaults to every reflected resource.
+    #[serde(default)]
+    pub resources: BrpSceneFilter,
+}
+
+/// A [`SceneFilter`] that names its types by their full type paths.
+#[cfg(feature = "bevy_scene")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BrpSceneFilter {
+    /// Every type is included.
+    #[default]
+    All,
+    /// Only the listed types are included.
+    Allow(Vec<String>),
+    /// Every type except the listed ones is included.
+    Deny(Vec<String>),
+}
+
+/// `world.restore`: Writes a scene produced by `world.snapshot` back into the world.
+///
+/// The server responds with a [`BrpRestoreResponse`].
+#[cfg(feature = "bevy_scene")]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpRestoreParams {
+    /// The serialized scene, as returned by `world.snapshot`.
+    pub scene: Value,
+
+    /// Maps entities of the scene to existing entities of the world, which will be
+    /// updated in place instead of being spawned.
+    ///
+    /// Scene entities that aren't in this map are spawned as new entities.
+    #[serde(default)]
+    pub entity_map: HashMap<Entity, Entity>,
+}
+
 /// Describes the data that is to be fetched in a query.
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 pub struct BrpQuery {
@@ -407,6 +720,39 @@ pub struct BrpGetResourcesResponse {
     pub value: Value,
 }
 
+/// A response from the world to a `world.restore` request.
+#[cfg(feature = "bevy_scene")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BrpRestoreResponse {
+    /// Maps each entity of the scene to the world entity it was written to.
+    pub entities: HashMap<Entity, Entity>,
+}
+
+/// A response from the world to a `world.history` request.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BrpHistoryResponse {
+    /// The current `FrameCount`, or 0 without the `bevy_diagnostic` feature.
+    pub frame: u32,
+
+    /// A map associating each requested component type path with its recorded values, from
+    /// oldest to newest.
+    pub components: HashMap<String, Vec<BrpHistoryRecord>>,
+}
+
+/// A value of a component recorded by `world.watch_history`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpHistoryRecord {
+    /// The `FrameCount` at which the value was recorded, or 0 without the `bevy_diagnostic` feature.
+    pub frame: u32,
+
+    /// The [`Tick`] at which the component was changed to this value, or removed.
+    pub tick: u32,
+
+    /// The serialized value of the component, or null if the component was removed from the
+    /// entity or the entity was despawned.
+    pub value: Value,
+}
+
 /// A single response from a `world.get_components+watch` request.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
@@ -435,6 +781,16 @@ pub enum BrpGetComponentsWatchingResponse {
 /// The response to a `world.list_components` request.
 pub type BrpListComponentsResponse = Vec<String>;
 
+/// The response to a `app.status` request.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpStatusResponse {
+    /// Whether [`Stepping`] is currently enabled, i.e. whether the app was paused with `app.pause`.
+    pub paused: bool,
+
+    /// The number of frames requested by `app.step` that have not run yet.
+    pub pending_frames: u32,
+}
+
 /// The response to a `world.list_resources` request.
 pub type BrpListResourcesResponse = Vec<String>;
 
@@ -448,6 +804,24 @@ pub struct BrpListComponentsWatchingResponse {
 /// The response to a `world.query` request.
 pub type BrpQueryResponse = Vec<BrpQueryRow>;
 
+/// A single response from a `world.query+watch` request.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpQueryWatchingResponse {
+    /// Rows for entities that started matching the query in the last tick.
+    #[serde(default)]
+    pub added: Vec<BrpQueryRow>,
+
+    /// Rows for entities that kept matching the query but had one of their
+    /// selected components added or changed in the last tick.
+    #[serde(default)]
+    pub changed: Vec<BrpQueryRow>,
+
+    /// Entities that stopped matching the query in the last tick, either because
+    /// they were despawned or because their components no longer satisfy it.
+    #[serde(default)]
+    pub removed: Vec<Entity>,
+}
+
 /// One query match result: a single entity paired with the requested components.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct BrpQueryRow {
@@ -739,15 +1113,7 @@ impl Default for ComponentSelector {
 
 /// Handles a `world.query` request coming from a client.
 pub fn process_remote_query_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
-    let BrpQueryParams {
-        data: BrpQuery {
-            components,
-            option,
-            has,
-        },
-        filter,
-        strict,
-    } = match params {
+    let params = match params {
         Some(params) => parse_some(Some(params))?,
         None => BrpQueryParams {
             data: BrpQuery {
@@ -760,6 +1126,186 @@ pub fn process_remote_query_request(In(params): In<Option<Value>>, world: &mut W
         },
     };
 
+    let response = collect_query_rows(world, params, |_| true)?;
+    serde_json::to_value(response).map_err(BrpError::internal)
+}
+
+/// Handles a `world.query+watch` request coming from a client.
+///
+/// Rather than diffing the whole result set, this relies on change ticks and on the
+/// component removal events of the current frame, so only the rows that are affected
+/// by the last tick are ever serialized.
+pub fn process_remote_query_watching_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult<Option<Value>> {
+    let params: BrpQueryParams = parse_some(params)?;
+
+    let app_type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = app_type_registry.read();
+
+    // Components that must be present for an entity to match.
+    let (required, unregistered_in_required) = get_component_ids(
+        &type_registry,
+        world,
+        params.data.components.clone(),
+        params.strict,
+    )
+    .map_err(BrpError::component_error)?;
+    let (with, unregistered_in_with) = get_component_ids(
+        &type_registry,
+        world,
+        params.filter.with.clone(),
+        params.strict,
+    )
+    .map_err(BrpError::component_error)?;
+    // Components that must be absent for an entity to match.
+    let (without, _) = get_component_ids(
+        &type_registry,
+        world,
+        params.filter.without.clone(),
+        params.strict,
+    )
+    .map_err(BrpError::component_error)?;
+    // Components whose values are reported, and so whose changes are reported too.
+    let optional = match &params.data.option {
+        ComponentSelector::Paths(paths) => Some(
+            get_component_ids(&type_registry, world, paths.clone(), params.strict)
+                .map_err(BrpError::component_error)?
+                .0,
+        ),
+        ComponentSelector::All => None,
+    };
+    drop(type_registry);
+
+    // Mirror `world.query`: a hard requirement that can never be satisfied means that
+    // nothing can ever match, so there is nothing to report.
+    if !unregistered_in_required.is_empty() || !unregistered_in_with.is_empty() {
+        return Ok(None);
+    }
+
+    let last_change_tick = world.last_change_tick();
+    let this_run = world.read_change_tick();
+
+    let requirements = required
+        .iter()
+        .chain(with.iter())
+        .map(|(_, component_id)| *component_id)
+        .collect::<Vec<_>>();
+
+    // Entities that lost a required component in the last tick (including through
+    // despawning) may have stopped matching.
+    let mut removal_candidates = <HashSet<Entity>>::default();
+    for component_id in &requirements {
+        if let Some(events) = world.removed_components().get(*component_id) {
+            removal_candidates.extend(
+                events
+                    .iter_current_update_events()
+                    .map(|event| Entity::from(event.clone())),
+            );
+        }
+    }
+
+    // Entities that lost an excluded component in the last tick may have started matching.
+    let mut lost_excluded = <HashSet<Entity>>::default();
+    for (_, component_id) in &without {
+        if let Some(events) = world.removed_components().get(*component_id) {
+            lost_excluded.extend(
+                events
+                    .iter_current_update_events()
+                    .map(|event| Entity::from(event.clone())),
+            );
+        }
+    }
+
+    // Entities that gained an excluded component in the last tick may have stopped matching.
+    for (_, component_id) in &without {
+        let mut query = QueryBuilder::<EntityRef>::new(world)
+            .ref_id(*component_id)
+            .build();
+        removal_candidates.extend(query.iter(world).filter_map(|entity_ref| {
+            entity_ref
+                .get_change_ticks_by_id(*component_id)
+                .filter(|ticks| ticks.is_added(last_change_tick, this_run))
+                .map(|_| entity_ref.id())
+        }));
+    }
+
+    let mut matching = <HashSet<Entity>>::default();
+    let mut added = <HashSet<Entity>>::default();
+    let rows = collect_query_rows(world, params, |entity_ref| {
+        let entity = entity_ref.id();
+        matching.insert(entity);
+
+        let is_added = |component_id: ComponentId| {
+            entity_ref
+                .get_change_ticks_by_id(component_id)
+                .is_some_and(|ticks| ticks.is_added(last_change_tick, this_run))
+        };
+        let is_changed = |component_id: ComponentId| {
+            entity_ref
+                .get_change_ticks_by_id(component_id)
+                .is_some_and(|ticks| ticks.is_changed(last_change_tick, this_run))
+        };
+
+        if lost_excluded.contains(&entity) || requirements.iter().copied().any(is_added) {
+            added.insert(entity);
+            return true;
+        }
+
+        match &optional {
+            Some(optional) => required
+                .iter()
+                .chain(optional.iter())
+                .any(|(_, component_id)| is_changed(*component_id)),
+            None => entity_ref.archetype().components().any(is_changed),
+        }
+    })?;
+
+    let mut response = BrpQueryWatchingResponse::default();
+    for row in rows {
+        if added.contains(&row.entity) {
+            response.added.push(row);
+        } else {
+            response.changed.push(row);
+        }
+    }
+    response.removed = removal_candidates
+        .into_iter()
+        .filter(|entity| !matching.contains(entity))
+        .collect();
+    // Sort to keep the output stable regardless of the hashing order.
+    response.removed.sort();
+
+    if response.added.is_empty() && response.changed.is_empty() && response.removed.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        serde_json::to_value(response).map_err(BrpError::internal)?,
+    ))
+}
+
+/// Runs the query described by `params` and serializes the matching rows.
+///
+/// Only the entities for which `include` returns `true` are serialized, which lets
+/// callers skip the (comparatively expensive) reflection of rows they are not
+/// interested in. `include` is still called for every matching entity.
+fn collect_query_rows(
+    world: &mut World,
+    params: BrpQueryParams,
+    mut include: impl FnMut(EntityRef) -> bool,
+) -> BrpResult<BrpQueryResponse> {
+    let BrpQueryParams {
+        data: BrpQuery {
+            components,
+            option,
+            has,
+        },
+        filter,
+        strict,
+    } = params;
+
     let app_type_registry = world.resource::<AppTypeRegistry>().clone();
     let type_registry = app_type_registry.read();
 
@@ -795,7 +1341,7 @@ pub fn process_remote_query_request(In(params): In<Option<Value>>, world: &mut W
     // response since they specify hard requirements.
     // If strict, fail if any required or with components are unregistered
     if !unregistered_in_required.is_empty() || !unregistered_in_with.is_empty() {
-        return serde_json::to_value(BrpQueryResponse::default()).map_err(BrpError::internal);
+        return Ok(BrpQueryResponse::default());
     }
 
     let mut query = QueryBuilder::<FilteredEntityRef>::new(world);
@@ -833,6 +1379,10 @@ pub fn process_remote_query_request(In(params): In<Option<Value>>, world: &mut W
         let entity_id = row.id();
         let entity_ref = world.get_entity(entity_id).expect("Entity should exist");
 
+        if !include(entity_ref) {
+            continue;
+        }
+
         // Required components
         let mut components_map = serialize_components(
             entity_ref,
@@ -897,7 +1447,7 @@ pub fn process_remote_query_request(In(params): In<Option<Value>>, world: &mut W
         response.push(query_row);
     }
 
-    serde_json::to_value(response).map_err(BrpError::internal)
+    Ok(response)
 }
 
 /// Serializes the specified components for an entity.
@@ -987,6 +1537,20 @@ pub fn process_remote_list_methods_request(
     #[cfg(any(not(feature = "http"), target_family = "wasm"))]
     let servers = None;
 
+    #[cfg(all(feature = "websocket", not(target_family = "wasm")))]
+    let servers = match world.get_resource::<crate::websocket::WebSocketHost>() {
+        Some(host) => {
+            let mut servers = servers.unwrap_or_default();
+            servers.push(ServerObject {
+                name: "WebSocket Server".to_owned(),
+                url: format!("ws://{}:{}", host.address.0, host.port.0),
+                ..default()
+            });
+            Some(servers)
+        }
+        None => servers,
+    };
+
     let doc = OpenRpcDocument {
         info: Default::default(),
         methods: remote_methods.into(),
@@ -1352,6 +1916,536 @@ pub fn process_remote_list_components_watching_request(
     }
 }
 
+/// The number of frames that still have to be stepped through to fulfill `app.step` requests.
+///
+/// Every frame this is non-zero, [`step_remote_frames`] asks [`Stepping`] to run through one
+/// more frame.
+#[derive(Debug, Resource, Default)]
+pub struct RemotePendingSteps(pub u32);
+
+/// A system that drives [`Stepping`] forward while there are pending `app.step` frames.
+pub fn step_remote_frames(
+    mut pending: ResMut<RemotePendingSteps>,
+    stepping: Option<ResMut<Stepping>>,
+) {
+    let Some(mut stepping) = stepping else {
+        pending.0 = 0;
+        return;
+    };
+    if pending.0 > 0 {
+        stepping.continue_frame();
+        pending.0 -= 1;
+    }
+}
+
+/// Handles a `app.pause` request coming from a client.
+///
+/// This requires the `bevy_debug_stepping` feature; without it, [`Stepping`] can't be
+/// enabled, so the request fails instead of reporting a pause that never happens.
+pub fn process_remote_pause_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    if !cfg!(feature = "bevy_debug_stepping") {
+        return Err(BrpError {
+            code: error_codes::INVALID_REQUEST,
+            message: String::from(
+                "Pausing requires bevy_remote to be compiled with the `bevy_debug_stepping` feature",
+            ),
+            data: None,
+        });
+    }
+
+    let BrpPauseParams { schedules } = match params {
+        Some(params) => parse(params)?,
+        None => BrpPauseParams::default(),
+    };
+
+    let labels = schedules
+        .iter()
+        .map(|name| get_schedule_label(world, name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stepping = world.get_resource_or_init::<Stepping>();
+    for label in labels {
+        stepping.add_schedule(label);
+    }
+    stepping.enable();
+
+    Ok(Value::Null)
+}
+
+/// Handles a `app.resume` request coming from a client.
+pub fn process_remote_resume_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    world.resource_mut::<RemotePendingSteps>().0 = 0;
+    if let Some(mut stepping) = world.get_resource_mut::<Stepping>() {
+        stepping.disable();
+    }
+
+    Ok(Value::Null)
+}
+
+/// Handles a `app.step` request coming from a client.
+pub fn process_remote_step_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let BrpStepParams { frames } = match params {
+        Some(params) => parse(params)?,
+        None => BrpStepParams::default(),
+    };
+
+    if !world.contains_resource::<Stepping>() {
+        return Err(BrpError {
+            code: error_codes::INVALID_REQUEST,
+            message: String::from("The app must be paused with `app.pause` before stepping"),
+            data: None,
+        });
+    }
+
+    let mut pending = world.resource_mut::<RemotePendingSteps>();
+    pending.0 = pending.0.saturating_add(frames);
+
+    Ok(Value::Null)
+}
+
+/// Handles a `app.status` request coming from a client.
+pub fn process_remote_status_request(In(_params): In<Option<Value>>, world: &World) -> BrpResult {
+    let response = BrpStatusResponse {
+        paused: world
+            .get_resource::<Stepping>()
+            .is_some_and(Stepping::is_enabled),
+        pending_frames: world.resource::<RemotePendingSteps>().0,
+    };
+
+    serde_json::to_value(response).map_err(BrpError::internal)
+}
+
+/// Handles a `schedule.run` request coming from a client.
+pub fn process_remote_run_schedule_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpRunScheduleParams { label: name } = parse_some(params)?;
+
+    let label = get_schedule_label(world, &name)?;
+    // The schedules that are currently running (such as `Main`) are not available.
+    world
+        .try_run_schedule(label)
+        .map_err(|_| BrpError::schedule_not_found(&name))?;
+
+    Ok(Value::Null)
+}
+
+/// Handles a `time.set_relative_speed` request coming from a client.
+#[cfg(feature = "bevy_time")]
+pub fn process_remote_set_relative_speed_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpSetRelativeSpeedParams { speed } = parse_some(params)?;
+
+    if !speed.is_finite() || speed < 0.0 {
+        return Err(BrpError {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Relative speed must be finite and non-negative, got {speed}"),
+            data: None,
+        });
+    }
+
+    let Some(mut time) = world.get_resource_mut::<Time<Virtual>>() else {
+        return Err(BrpError::resource_not_present(Time::<Virtual>::type_path()));
+    };
+    time.set_relative_speed_f64(speed);
+
+    Ok(Value::Null)
+}
+
+/// Handles a `schedule.graph` request coming from a client.
+///
+/// Schedules that are currently running, such as `Main`, can't be exported.
+pub fn export_schedule_graphs(In(params): In<Option<Value>>, world: &World) -> BrpResult {
+    let BrpScheduleGraphParams { schedules: names } = match params {
+        Some(params) => parse(params)?,
+        None => BrpScheduleGraphParams::default(),
+    };
+
+    let Some(schedules) = world.get_resource::<Schedules>() else {
+        return Err(BrpError::resource_not_present(core::any::type_name::<
+            Schedules,
+        >()));
+    };
+    let mut graphs = if names.is_empty() {
+        schedules
+            .iter()
+            .map(|(_, schedule)| export_schedule_graph(schedule, world.components()))
+            .collect::<Vec<_>>()
+    } else {
+        names
+            .iter()
+            .map(|name| {
+                let label = get_schedule_label(world, name)?;
+                let schedule = schedules
+                    .get(label)
+                    .ok_or_else(|| BrpError::schedule_not_found(name))?;
+                Ok(export_schedule_graph(schedule, world.components()))
+            })
+            .collect::<Result<Vec<_>, BrpError>>()?
+    };
+    graphs.sort_by(|a, b| a.label.cmp(&b.label));
+
+    serde_json::to_value(graphs).map_err(BrpError::internal)
+}
+
+/// Handles a `world.send_event` request coming from a client.
+pub fn process_remote_send_event_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpSendEventParams {
+        event: event_path,
+        value,
+    } = parse_some(params)?;
+
+    let app_type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = app_type_registry.read();
+
+    let registration =
+        get_event_type_registration(&type_registry, &event_path).map_err(BrpError::event_error)?;
+    let reflect_buffered_event = registration.data::<ReflectBufferedEvent>().ok_or_else(|| {
+        BrpError::event_error(format!("Buffered event `{event_path}` isn't reflectable"))
+    })?;
+    let reflected_event = deserialize_event(&type_registry, registration, &event_path, value)
+        .map_err(BrpError::event_error)?;
+
+    if !reflect_buffered_event.write(world, &*reflected_event, &type_registry) {
+        return Err(BrpError::event_error(format!(
+            "Events of type `{event_path}` are not present in the world"
+        )));
+    }
+
+    Ok(Value::Null)
+}
+
+/// Handles a `world.trigger_event` request coming from a client.
+pub fn process_remote_trigger_event_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpTriggerEventParams {
+        event: event_path,
+        value,
+        targets,
+    } = parse_some(params)?;
+
+    for &entity in &targets {
+        get_entity(world, entity)?;
+    }
+
+    let app_type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = app_type_registry.read();
+
+    let registration =
+        get_event_type_registration(&type_registry, &event_path).map_err(BrpError::event_error)?;
+    let reflected_event = deserialize_event(&type_registry, registration, &event_path, value)
+        .map_err(BrpError::event_error)?;
+
+    if targets.is_empty() {
+        let reflect_event = registration.data::<ReflectEvent>().ok_or_else(|| {
+            BrpError::event_error(format!("Event `{event_path}` isn't reflectable"))
+        })?;
+        reflect_event.trigger(world, &*reflected_event, &type_registry);
+    } else {
+        let reflect_entity_event = registration.data::<ReflectEntityEvent>().ok_or_else(|| {
+            BrpError::event_error(format!("Entity event `{event_path}` isn't reflectable"))
+        })?;
+        reflect_entity_event.trigger_targets(world, &*reflected_event, &targets, &type_registry);
+    }
+
+    Ok(Value::Null)
+}
+
+/// The component values recorded for `world.history` requests.
+///
+/// Components start being recorded with `world.watch_history`. Their values are recorded by
+/// [`record_remote_history`] at the end of every frame in which they changed.
+#[derive(Debug, Resource, Default)]
+pub struct RemoteHistory {
+    histories: HashMap<(Entity, String), ComponentHistory>,
+}
+
+/// The recorded values of a single component of an entity.
+#[derive(Debug)]
+struct ComponentHistory {
+    component_id: ComponentId,
+    capacity: usize,
+    records: VecDeque<BrpHistoryRecord>,
+    last_changed: Option<Tick>,
+}
+
+impl ComponentHistory {
+    fn push(&mut self, record: BrpHistoryRecord) {
+        self.records.push_back(record);
+        while self.records.len() > self.capacity {
+            self.records.pop_front();
+        }
+    }
+}
+
+/// Returns the current `FrameCount`, or 0 if it isn't available, like without the `bevy_diagnostic`
+/// feature.
+#[cfg_attr(
+    not(feature = "bevy_diagnostic"),
+    expect(
+        unused_variables,
+        reason = "The frame count requires the `bevy_diagnostic` feature."
+    )
+)]
+fn current_frame(world: &World) -> u32 {
+    #[cfg(feature = "bevy_diagnostic")]
+    return world
+        .get_resource::<bevy_diagnostic::FrameCount>()
+        .map_or(0, |frame| frame.0);
+    #[cfg(not(feature = "bevy_diagnostic"))]
+    0
+}
+
+/// A system that records the components watched with `world.watch_history` that have changed,
+/// been removed or been despawned since they were last recorded.
+pub fn record_remote_history(world: &mut World) {
+    if world.resource::<RemoteHistory>().histories.is_empty() {
+        return;
+    }
+
+    world.resource_scope(|world, mut history: Mut<RemoteHistory>| {
+        let frame = current_frame(world);
+        let app_type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = app_type_registry.read();
+
+        for ((entity, component_path), history) in &mut history.histories {
+            let entity_ref = world.get_entity(*entity).ok();
+            let ticks = entity_ref
+                .and_then(|entity_ref| entity_ref.get_change_ticks_by_id(history.component_id));
+
+            let record = match (entity_ref, ticks) {
+                (Some(entity_ref), Some(ticks)) => {
+                    if history.last_changed == Some(ticks.changed) {
+                        continue;
+                    }
+                    history.last_changed = Some(ticks.changed);
+                    let value =
+                        reflect_component(component_path, *entity, entity_ref, &type_registry)
+                            .ok()
+                            .and_then(|mut serialized| serialized.remove(component_path))
+                            .unwrap_or_default();
+                    BrpHistoryRecord {
+                        frame,
+                        tick: ticks.changed.get(),
+                        value,
+                    }
+                }
+                _ => {
+                    let was_removed = history
+                        .records
+                        .back()
+                        .is_some_and(|record| record.value.is_null());
+                    if was_removed {
+                        continue;
+                    }
+                    history.last_changed = None;
+                    BrpHistoryRecord {
+                        frame,
+                        tick: world.change_tick().get(),
+                        value: Value::Null,
+                    }
+                }
+            };
+            history.push(record);
+        }
+    });
+}
+
+/// Handles a `world.watch_history` request coming from a client.
+pub fn process_remote_watch_history_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpWatchHistoryParams {
+        entity,
+        components,
+        capacity,
+    } = parse_some(params)?;
+
+    get_entity(world, entity)?;
+
+    let app_type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = app_type_registry.read();
+
+    let component_ids = components
+        .iter()
+        .map(|component_path| {
+            let reflect_component = get_reflect_component(&type_registry, component_path)
+                .map_err(BrpError::component_error)?;
+            Ok(reflect_component.register_component(world))
+        })
+        .collect::<Result<Vec<_>, BrpError>>()?;
+
+    let mut history = world.resource_mut::<RemoteHistory>();
+    for (component_path, component_id) in components.into_iter().zip(component_ids) {
+        history
+            .histories
+            .entry((entity, component_path))
+            .and_modify(|history| history.capacity = capacity.max(1))
+            .or_insert_with(|| ComponentHistory {
+                component_id,
+                capacity: capacity.max(1),
+                records: VecDeque::new(),
+                last_changed: None,
+            });
+    }
+
+    Ok(Value::Null)
+}
+
+/// Handles a `world.unwatch_history` request coming from a client.
+pub fn process_remote_unwatch_history_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpUnwatchHistoryParams { entity, components } = parse_some(params)?;
+
+    let mut history = world.resource_mut::<RemoteHistory>();
+    history
+        .histories
+        .retain(|(watched_entity, component_path), _| {
+            *watched_entity != entity
+                || (!components.is_empty() && !components.contains(component_path))
+        });
+
+    Ok(Value::Null)
+}
+
+/// Handles a `world.history` request coming from a client.
+pub fn process_remote_history_request(In(params): In<Option<Value>>, world: &World) -> BrpResult {
+    let BrpHistoryParams {
+        entity,
+        components,
+        frames,
+    } = parse_some(params)?;
+
+    let frame = current_frame(world);
+    let history = world.resource::<RemoteHistory>();
+
+    let mut response = BrpHistoryResponse {
+        frame,
+        components: HashMap::default(),
+    };
+    for component_path in components {
+        let Some(component_history) = history.histories.get(&(entity, component_path.clone()))
+        else {
+            return Err(BrpError {
+                code: error_codes::INVALID_PARAMS,
+                message: format!(
+                    "Component `{component_path}` of entity {entity} is not watched with `{}`",
+                    BRP_WATCH_HISTORY_METHOD
+                ),
+                data: None,
+            });
+        };
+
+        let records = &component_history.records;
+        // Keep the last value recorded before the window as well, since it is the value the
+        // component had when the window started.
+        let first = match frames {
+            Some(frames) => records
+                .iter()
+                .rposition(|record| frame.wrapping_sub(record.frame) >= frames)
+                .unwrap_or(0),
+            None => 0,
+        };
+        response
+            .components
+            .insert(component_path, records.range(first..).cloned().collect());
+    }
+
+    serde_json::to_value(response).map_err(BrpError::internal)
+}
+
+/// Handles a `world.snapshot` request coming from a client.
+#[cfg(feature = "bevy_scene")]
+pub fn process_remote_snapshot_request(In(params): In<Option<Value>>, world: &World) -> BrpResult {
+    let BrpSnapshotParams {
+        components,
+        resources,
+    } = match params {
+        Some(params) => parse(params)?,
+        None => BrpSnapshotParams::default(),
+    };
+
+    let app_type_registry = world.resource::<AppTypeRegistry>();
+    let type_registry = app_type_registry.read();
+
+    let component_filter =
+        build_scene_filter(&type_registry, components).map_err(BrpError::component_error)?;
+    let resource_filter =
+        build_scene_filter(&type_registry, resources).map_err(BrpError::resource_error)?;
+
+    let scene = DynamicSceneBuilder::from_world(world)
+        .with_component_filter(component_filter)
+        .with_resource_filter(resource_filter)
+        .extract_entities(
+            // Go through the archetypes rather than a query, so that disabled entities are
+            // captured as well.
+            world
+                .archetypes()
+                .iter()
+                .flat_map(bevy_ecs::archetype::Archetype::entities)
+                .map(bevy_ecs::archetype::ArchetypeEntity::id),
+        )
+        .extract_resources()
+        .build();
+
+    serde_json::to_value(SceneSerializer::new(&scene, &type_registry)).map_err(BrpError::internal)
+}
+
+/// Handles a `world.restore` request coming from a client.
+#[cfg(feature = "bevy_scene")]
+pub fn process_remote_restore_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpRestoreParams { scene, entity_map } = parse_some(params)?;
+
+    for &entity in entity_map.values() {
+        get_entity(world, entity)?;
+    }
+
+    let app_type_registry = world.resource::<AppTypeRegistry>().clone();
+    let scene = {
+        let type_registry = app_type_registry.read();
+        SceneDeserializer {
+            type_registry: &type_registry,
+        }
+        .deserialize(scene)
+        .map_err(|err| BrpError {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Scene could not be deserialized: {err}"),
+            data: None,
+        })?
+    };
+
+    let mut entity_map: EntityHashMap<Entity> = entity_map.into_iter().collect();
+    scene
+        .write_to_world_with(world, &mut entity_map, &app_type_registry)
+        .map_err(|err| BrpError {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Scene could not be written to the world: {err}"),
+            data: None,
+        })?;
+
+    let response = BrpRestoreResponse {
+        entities: entity_map.into_iter().collect(),
+    };
+    serde_json::to_value(response).map_err(BrpError::internal)
+}
+
 /// Handles a `registry.schema` request (list all registry types in form of schema) coming from a client.
 pub fn export_registry_types(In(params): In<Option<Value>>, world: &World) -> BrpResult {
     let filter: BrpJsonSchemaQueryFilter = match params {
@@ -1421,6 +2515,20 @@ fn get_entity_mut(world: &mut World, entity: Entity) -> Result<EntityWorldMut<'_
         .map_err(|_| BrpError::entity_not_found(entity))
 }
 
+/// Finds the label of the schedule whose [`Debug`](core::fmt::Debug) representation is `name`,
+/// returning an error if there is no such schedule in the [`World`].
+fn get_schedule_label(world: &World, name: &str) -> Result<InternedScheduleLabel, BrpError> {
+    world
+        .get_resource::<Schedules>()
+        .and_then(|schedules| {
+            schedules
+                .iter()
+                .map(|(_, schedule)| schedule.label())
+                .find(|label| format!("{label:?}") == name)
+        })
+        .ok_or_else(|| BrpError::schedule_not_found(name))
+}
+
 /// Given components full path, returns a tuple that contains
 /// - A list of corresponding [`TypeId`] and [`ComponentId`] for registered components.
 /// - A list of unregistered component paths.
@@ -1549,6 +2657,20 @@ fn deserialize_resource(
     Ok(reflected)
 }
 
+/// Given an event's type `registration`, deserialize its `value` into a reflected event.
+fn deserialize_event(
+    type_registry: &TypeRegistry,
+    registration: &TypeRegistration,
+    event_path: &str,
+    value: Value,
+) -> AnyhowResult<Box<dyn PartialReflect>> {
+    let reflected: Box<dyn PartialReflect> =
+        TypedReflectDeserializer::new(registration, type_registry)
+            .deserialize(&value)
+            .map_err(|err| anyhow!("{event_path} is invalid: {err}"))?;
+    Ok(reflected)
+}
+
 /// Given a collection `reflect_components` of reflected component values, insert them into
 /// the given entity (`entity_world_mut`).
 fn insert_reflected_components(
@@ -1565,6 +2687,35 @@ fn insert_reflected_components(
     Ok(())
 }
 
+/// Converts a [`BrpSceneFilter`] into a [`SceneFilter`], resolving its type paths with the
+/// given `type_registry`.
+#[cfg(feature = "bevy_scene")]
+fn build_scene_filter(
+    type_registry: &TypeRegistry,
+    filter: BrpSceneFilter,
+) -> AnyhowResult<SceneFilter> {
+    let type_ids = |type_paths: Vec<String>| {
+        type_paths.into_iter().map(|type_path| {
+            type_registry
+                .get_with_type_path(&type_path)
+                .map(TypeRegistration::type_id)
+                .ok_or_else(|| anyhow!("Unknown type: `{}`", type_path))
+        })
+    };
+
+    match filter {
+        BrpSceneFilter::All => Ok(SceneFilter::allow_all()),
+        BrpSceneFilter::Allow(type_paths) => type_ids(type_paths)
+            .try_fold(SceneFilter::deny_all(), |filter, type_id| {
+                Ok(filter.allow_by_id(type_id?))
+            }),
+        BrpSceneFilter::Deny(type_paths) => type_ids(type_paths)
+            .try_fold(SceneFilter::allow_all(), |filter, type_id| {
+                Ok(filter.deny_by_id(type_id?))
+            }),
+    }
+}
+
 /// Given a component's type path, return the associated [`ReflectComponent`] from the given
 /// `type_registry` if possible.
 fn get_reflect_component<'r>(
@@ -1613,6 +2764,17 @@ fn get_resource_type_registration<'r>(
         .ok_or_else(|| anyhow!("Unknown resource type: `{}`", resource_path))
 }
 
+/// Given an event's type path, return the associated [`TypeRegistration`] from the given
+/// `type_registry` if possible.
+fn get_event_type_registration<'r>(
+    type_registry: &'r TypeRegistry,
+    event_path: &str,
+) -> AnyhowResult<&'r TypeRegistration> {
+    type_registry
+        .get_with_type_path(event_path)
+        .ok_or_else(|| anyhow!("Unknown event type: `{}`", event_path))
+}
+
 #[cfg(test)]
 mod tests {
     /// A generic function that tests serialization and deserialization of any type
@@ -1644,6 +2806,15 @@ mod tests {
             has: Default::default(),
         });
         test_serialize_deserialize(BrpListComponentsWatchingResponse::default());
+        test_serialize_deserialize(BrpQueryWatchingResponse {
+            added: vec![BrpQueryRow {
+                components: Default::default(),
+                entity: Entity::from_raw_u32(1).unwrap(),
+                has: Default::default(),
+            }],
+            changed: Vec::new(),
+            removed: vec![Entity::from_raw_u32(2).unwrap()],
+        });
         test_serialize_deserialize(BrpQuery::default());
         test_serialize_deserialize(BrpJsonSchemaQueryFilter::default());
         test_serialize_deserialize(BrpJsonSchemaQueryFilter {
@@ -1657,4 +2828,504 @@ mod tests {
             entity: Entity::from_raw_u32(0).unwrap(),
         });
     }
+
+    #[cfg(feature = "bevy_diagnostic")]
+    #[test]
+    fn history_records_changes_per_frame() {
+        use bevy_diagnostic::FrameCount;
+        use bevy_ecs::{component::Component, system::RunSystemOnce};
+        use bevy_reflect::{Reflect, TypePath};
+
+        #[derive(Component, Reflect)]
+        #[reflect(Component)]
+        struct Health(u32);
+
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<RemoteHistory>();
+        world.init_resource::<FrameCount>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Health>();
+        let entity = world.spawn(Health(10)).id();
+
+        world
+            .run_system_once_with(
+                process_remote_watch_history_request,
+                Some(serde_json::json!({
+                    "entity": entity,
+                    "components": [Health::type_path()],
+                    "capacity": 3,
+                })),
+            )
+            .unwrap()
+            .unwrap();
+
+        let next_frame = |world: &mut World, health: Option<u32>| {
+            world.resource_mut::<FrameCount>().0 += 1;
+            match health {
+                Some(health) => world.entity_mut(entity).get_mut::<Health>().unwrap().0 = health,
+                None => {
+                    world.entity_mut(entity).remove::<Health>();
+                }
+            }
+            world.increment_change_tick();
+            world.run_system_once(record_remote_history).unwrap();
+        };
+        world.run_system_once(record_remote_history).unwrap();
+        next_frame(&mut world, Some(9));
+        // Frames without changes are not recorded.
+        world.resource_mut::<FrameCount>().0 += 1;
+        world.run_system_once(record_remote_history).unwrap();
+        next_frame(&mut world, Some(8));
+        next_frame(&mut world, None);
+
+        let history = |world: &mut World, frames: Option<u32>| {
+            let response = world
+                .run_system_once_with(
+                    process_remote_history_request,
+                    Some(serde_json::json!({
+                        "entity": entity,
+                        "components": [Health::type_path()],
+                        "frames": frames,
+                    })),
+                )
+                .unwrap()
+                .unwrap();
+            let mut response: BrpHistoryResponse = serde_json::from_value(response).unwrap();
+            assert_eq!(response.frame, 4);
+            response
+                .components
+                .remove(Health::type_path())
+                .unwrap()
+                .into_iter()
+                .map(|record| (record.frame, record.value))
+                .collect::<Vec<_>>()
+        };
+
+        // The capacity of 3 discarded the initial value.
+        assert_eq!(
+            history(&mut world, None),
+            [
+                (1, serde_json::json!(9)),
+                (3, serde_json::json!(8)),
+                (4, Value::Null)
+            ]
+        );
+        // The value at frame 2 was recorded at frame 1.
+        assert_eq!(
+            history(&mut world, Some(2)),
+            [
+                (1, serde_json::json!(9)),
+                (3, serde_json::json!(8)),
+                (4, Value::Null)
+            ]
+        );
+        assert_eq!(
+            history(&mut world, Some(1)),
+            [(3, serde_json::json!(8)), (4, Value::Null)]
+        );
+
+        world
+            .run_system_once_with(
+                process_remote_unwatch_history_request,
+                Some(serde_json::json!({ "entity": entity })),
+            )
+            .unwrap()
+            .unwrap();
+        assert!(world.resource::<RemoteHistory>().histories.is_empty());
+    }
+
+    #[test]
+    fn query_watching_reports_changes_since_last_tick() {
+        use bevy_ecs::{component::Component, reflect::ReflectComponent};
+        use bevy_reflect::{Reflect, TypePath};
+
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component)]
+        struct Health(u32);
+
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component)]
+        struct Dead;
+
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let mut registry = world.resource::<AppTypeRegistry>().write();
+            registry.register::<Health>();
+            registry.register::<Dead>();
+        }
+        world.register_component::<Dead>();
+
+        let changed = world.spawn(Health(10)).id();
+        let killed = world.spawn(Health(10)).id();
+        let untouched = world.spawn(Health(10)).id();
+
+        let params = serde_json::to_value(BrpQueryParams {
+            data: BrpQuery {
+                components: vec![Health::type_path().to_owned()],
+                ..Default::default()
+            },
+            filter: BrpQueryFilter {
+                without: vec![Dead::type_path().to_owned()],
+                ..Default::default()
+            },
+            strict: true,
+        })
+        .unwrap();
+        let system = world.register_system(process_remote_query_watching_request);
+        let watch = |world: &mut World| {
+            let response = world
+                .run_system_with(system, Some(params.clone()))
+                .unwrap()
+                .unwrap()
+                .map(|value| parse::<BrpQueryWatchingResponse>(value).unwrap());
+            world.clear_trackers();
+            response
+        };
+
+        // The first run reports everything that was added before the watcher started.
+        assert_eq!(watch(&mut world).unwrap().added.len(), 3);
+        assert_eq!(watch(&mut world), None);
+
+        world.get_mut::<Health>(changed).unwrap().0 = 5;
+        world.entity_mut(killed).insert(Dead);
+        let spawned = world.spawn(Health(10)).id();
+        let response = watch(&mut world).unwrap();
+        assert_eq!(
+            response
+                .added
+                .iter()
+                .map(|row| row.entity)
+                .collect::<Vec<_>>(),
+            vec![spawned]
+        );
+        assert_eq!(
+            response
+                .changed
+                .iter()
+                .map(|row| row.entity)
+                .collect::<Vec<_>>(),
+            vec![changed]
+        );
+        assert_eq!(response.removed, vec![killed]);
+
+        world.entity_mut(killed).remove::<Dead>();
+        world.despawn(untouched);
+        let response = watch(&mut world).unwrap();
+        assert_eq!(
+            response
+                .added
+                .iter()
+                .map(|row| row.entity)
+                .collect::<Vec<_>>(),
+            vec![killed]
+        );
+        assert!(response.changed.is_empty());
+        assert_eq!(response.removed, vec![untouched]);
+    }
+
+    #[test]
+    fn schedule_and_time_control() {
+        use bevy_ecs::{
+            schedule::{Schedule, ScheduleLabel},
+            system::{ResMut, RunSystemOnce},
+        };
+
+        #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct Simulate;
+
+        #[derive(Resource, Default)]
+        struct Ticks(u32);
+
+        let mut world = World::new();
+        world.init_resource::<Ticks>();
+        world.init_resource::<RemotePendingSteps>();
+        let mut schedule = Schedule::new(Simulate);
+        schedule.add_systems(|mut ticks: ResMut<Ticks>| ticks.0 += 1);
+        world.add_schedule(schedule);
+
+        let run = |world: &mut World, label: &str| {
+            world.run_system_once_with(
+                process_remote_run_schedule_request,
+                Some(serde_json::json!({ "label": label })),
+            )
+        };
+        run(&mut world, "Simulate").unwrap().unwrap();
+        run(&mut world, "Simulate").unwrap().unwrap();
+        assert_eq!(world.resource::<Ticks>().0, 2);
+        let err = run(&mut world, "Unknown").unwrap().unwrap_err();
+        assert_eq!(err.code, error_codes::SCHEDULE_NOT_FOUND);
+    }
+
+    #[cfg(feature = "bevy_debug_stepping")]
+    #[test]
+    fn pause_and_step_schedules() {
+        use bevy_ecs::{
+            schedule::{Schedule, ScheduleLabel},
+            system::{ResMut, RunSystemOnce},
+        };
+
+        #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct Simulate;
+
+        #[derive(Resource, Default)]
+        struct Ticks(u32);
+
+        let mut world = World::new();
+        world.init_resource::<Ticks>();
+        world.init_resource::<RemotePendingSteps>();
+        let mut schedule = Schedule::new(Simulate);
+        schedule.add_systems(|mut ticks: ResMut<Ticks>| ticks.0 += 1);
+        world.add_schedule(schedule);
+
+        // Runs one app update, the way `Main` and `RemoteLast` would.
+        let update = |world: &mut World| {
+            world.run_system_once(Stepping::begin_frame).unwrap();
+            world.run_schedule(Simulate);
+            world.run_system_once(step_remote_frames).unwrap();
+            world.resource::<Ticks>().0
+        };
+        let status = |world: &mut World| {
+            let status = world
+                .run_system_once_with(process_remote_status_request, None)
+                .unwrap()
+                .unwrap();
+            parse::<BrpStatusResponse>(status).unwrap()
+        };
+
+        assert_eq!(update(&mut world), 1);
+
+        // Stepping requires the app to be paused first.
+        let err = world
+            .run_system_once_with(process_remote_step_request, None)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.code, error_codes::INVALID_REQUEST);
+
+        world
+            .run_system_once_with(
+                process_remote_pause_request,
+                Some(serde_json::json!({ "schedules": ["Simulate"] })),
+            )
+            .unwrap()
+            .unwrap();
+        // The systems stop running while paused.
+        assert_eq!(update(&mut world), 1);
+        assert_eq!(update(&mut world), 1);
+        assert!(status(&mut world).paused);
+
+        world
+            .run_system_once_with(
+                process_remote_step_request,
+                Some(serde_json::json!({ "frames": 2 })),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(status(&mut world).pending_frames, 2);
+        // The steps are taken one update at a time, starting with the update after the request.
+        assert_eq!(update(&mut world), 1);
+        assert_eq!(update(&mut world), 2);
+        assert_eq!(update(&mut world), 3);
+        assert_eq!(update(&mut world), 3);
+        assert_eq!(status(&mut world).pending_frames, 0);
+
+        world
+            .run_system_once_with(process_remote_resume_request, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(update(&mut world), 4);
+        assert_eq!(update(&mut world), 5);
+        assert!(!status(&mut world).paused);
+    }
+
+    #[cfg(not(feature = "bevy_debug_stepping"))]
+    #[test]
+    fn pause_requires_stepping() {
+        use bevy_ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<RemotePendingSteps>();
+        let err = world
+            .run_system_once_with(process_remote_pause_request, None)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.code, error_codes::INVALID_REQUEST);
+        let status = world
+            .run_system_once_with(process_remote_status_request, None)
+            .unwrap()
+            .unwrap();
+        assert!(!parse::<BrpStatusResponse>(status).unwrap().paused);
+    }
+
+    #[test]
+    fn send_and_trigger_events() {
+        use bevy_ecs::{
+            event::{BufferedEvent, EntityEvent, Events},
+            observer::On,
+            system::RunSystemOnce,
+        };
+        use bevy_reflect::{Reflect, TypePath};
+
+        #[derive(EntityEvent, BufferedEvent, Reflect, Clone, Copy)]
+        #[reflect(Event, EntityEvent, BufferedEvent)]
+        struct Damage(u32);
+
+        #[derive(Resource, Default)]
+        struct Received(Vec<(Entity, u32)>);
+
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<Received>();
+        world.init_resource::<Events<Damage>>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Damage>();
+        world.add_observer(|trigger: On<Damage>, mut received: ResMut<Received>| {
+            received.0.push((trigger.target(), trigger.event().0));
+        });
+        let target = world.spawn_empty().id();
+
+        world
+            .run_system_once_with(
+                process_remote_send_event_request,
+                Some(serde_json::json!({ "event": Damage::type_path(), "value": 1 })),
+            )
+            .unwrap()
+            .unwrap();
+        let sent: Vec<_> = world
+            .resource::<Events<Damage>>()
+            .iter_current_update_events()
+            .map(|event| event.0)
+            .collect();
+        assert_eq!(sent, [1]);
+
+        let trigger = |world: &mut World, params: Value| {
+            world
+                .run_system_once_with(process_remote_trigger_event_request, Some(params))
+                .unwrap()
+        };
+        trigger(
+            &mut world,
+            serde_json::json!({ "event": Damage::type_path(), "value": 2 }),
+        )
+        .unwrap();
+        trigger(
+            &mut world,
+            serde_json::json!({ "event": Damage::type_path(), "value": 3, "targets": [target] }),
+        )
+        .unwrap();
+        assert_eq!(
+            world.resource::<Received>().0,
+            [(Entity::PLACEHOLDER, 2), (target, 3)]
+        );
+
+        let err = trigger(
+            &mut world,
+            serde_json::json!({ "event": "unknown::Event", "value": 4 }),
+        )
+        .unwrap_err();
+        assert_eq!(err.code, error_codes::EVENT_ERROR);
+    }
+
+    #[cfg(feature = "bevy_scene")]
+    #[test]
+    fn snapshot_and_restore() {
+        use bevy_ecs::{component::Component, hierarchy::Children, system::RunSystemOnce};
+        use bevy_reflect::{Reflect, TypePath};
+
+        #[derive(Component, Reflect, Debug, PartialEq)]
+        #[reflect(Component)]
+        struct Health(u32);
+
+        #[derive(Component, Reflect)]
+        #[reflect(Component)]
+        struct Secret;
+
+        let new_world = || {
+            let mut world = World::new();
+            world.init_resource::<AppTypeRegistry>();
+            {
+                let mut registry = world.resource::<AppTypeRegistry>().write();
+                registry.register::<Health>();
+                registry.register::<Secret>();
+                registry.register::<ChildOf>();
+                registry.register::<Children>();
+            }
+            world
+        };
+
+        let mut source = new_world();
+        let parent = source.spawn((Health(10), Secret)).id();
+        source.spawn((Health(5), ChildOf(parent)));
+
+        let scene = source
+            .run_system_once_with(
+                process_remote_snapshot_request,
+                Some(serde_json::json!({
+                    "components": { "deny": [Secret::type_path()] },
+                })),
+            )
+            .unwrap()
+            .unwrap();
+
+        let mut target = new_world();
+        // Shift the entity indices, so that remapping can't go unnoticed.
+        target.spawn_empty();
+        let response = target
+            .run_system_once_with(
+                process_remote_restore_request,
+                Some(serde_json::json!({ "scene": scene })),
+            )
+            .unwrap()
+            .unwrap();
+        let BrpRestoreResponse { entities } = serde_json::from_value(response).unwrap();
+        assert_eq!(entities.len(), 2);
+
+        let new_parent = entities[&parent];
+        assert_ne!(new_parent, parent);
+        assert_eq!(target.get::<Health>(new_parent), Some(&Health(10)));
+        assert!(target.get::<Secret>(new_parent).is_none());
+        let children = target.get::<Children>(new_parent).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(target.get::<Health>(children[0]), Some(&Health(5)));
+        assert_eq!(
+            target.get::<ChildOf>(children[0]).unwrap().parent(),
+            new_parent
+        );
+
+        let err = target
+            .run_system_once_with(
+                process_remote_snapshot_request,
+                Some(serde_json::json!({ "components": { "allow": ["unknown::Type"] } })),
+            )
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.code, error_codes::COMPONENT_ERROR);
+    }
+
+    #[cfg(feature = "bevy_time")]
+    #[test]
+    fn set_relative_speed() {
+        use bevy_ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<Time<Virtual>>();
+
+        let set_speed = |world: &mut World, speed: f64| {
+            world
+                .run_system_once_with(
+                    process_remote_set_relative_speed_request,
+                    Some(serde_json::json!({ "speed": speed })),
+                )
+                .unwrap()
+        };
+        set_speed(&mut world, 0.5).unwrap();
+        assert_eq!(world.resource::<Time<Virtual>>().relative_speed_f64(), 0.5);
+        let err = set_speed(&mut world, -1.0).unwrap_err();
+        assert_eq!(err.code, error_codes::INVALID_PARAMS);
+    }
 }
@@ -53,7 +53,7 @@ pub const DEFAULT_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 ///
 #[derive(Debug, Resource, Clone)]
 pub struct Headers {
-    headers: HashMap<HeaderName, HeaderValue>,
+    pub(crate) headers: HashMap<HeaderName, HeaderValue>,
 }
 
 impl Headers {
@@ -4,7 +4,9 @@
 //! starting any transports. To start accepting remote connections you will need to
 //! add a second plugin like the [`RemoteHttpPlugin`](http::RemoteHttpPlugin) to enable communication
 //! over HTTP. These *remote clients* can inspect and alter the state of the
-//! entity-component system.
+//! entity-component system. With the `websocket` feature, the
+//! `RemoteWebSocketPlugin` from the `websocket` module can also be added to multiplex
+//! many requests and watches over a single WebSocket connection.
 //!
 //! The Bevy Remote Protocol is based on the JSON-RPC 2.0 protocol.
 //!
@@ -408,6 +410,26 @@
 //! - `removed`: An array of fully-qualified type names of components removed from the entity
 //!   in the last tick.
 //!
+//! ### `world.query+watch`
+//!
+//! Watch the results of a query, receiving only the rows that were affected since the
+//! last tick.
+//!
+//! `params`: The same parameters as `world.query`, with the exception that
+//! `params` may not be omitted.
+//!
+//! `result`:
+//! - `added`: An array of rows (as returned by `world.query`) for entities that started
+//!   matching the query in the last tick.
+//! - `changed`: An array of rows for entities that still match the query but had one of
+//!   their selected components added or changed in the last tick.
+//! - `removed`: An array of entity IDs of entities that stopped matching the query in the
+//!   last tick, either because they were despawned or because their components changed.
+//!
+//! Changes are detected using change ticks rather than by diffing the results, so a watch
+//! is not guaranteed to report rows that were already matching when it started. Use
+//! `world.query` to fetch the current results before starting to watch.
+//!
 //! ### `world.get_resources`
 //!
 //! Extract the value of a given resource from the world.
@@ -455,6 +477,175 @@
 //!
 //! `result`: An array of [fully-qualified type names] of registered resource types.
 //!
+//! ### `world.watch_history`
+//!
+//! Start recording the values of components of an entity. A value is recorded at the end of every
+//! frame in which the component changed, was removed or its entity was despawned; only the most
+//! recent values are kept.
+//!
+//! `params`:
+//! - `entity`: The ID of the entity whose components will be recorded.
+//! - `components`: An array of [fully-qualified type names] of components to record.
+//! - `capacity` (optional): The number of values to keep per component. Defaults to 300.
+//!
+//! `result`: null.
+//!
+//! ### `world.unwatch_history`
+//!
+//! Stop recording components of an entity, and discard their recorded values.
+//!
+//! `params`:
+//! - `entity`: The ID of the entity whose components should no longer be recorded.
+//! - `components` (optional): An array of [fully-qualified type names] of components to stop
+//!   recording. Defaults to every recorded component of the entity.
+//!
+//! `result`: null.
+//!
+//! ### `world.history`
+//!
+//! Get the recorded values of components of an entity.
+//!
+//! `params`:
+//! - `entity`: The ID of the entity whose recorded values are requested.
+//! - `components`: An array of [fully-qualified type names] of recorded components.
+//! - `frames` (optional): Only return the values recorded over this many last frames, along with
+//!   the value at the start of that window. Defaults to every recorded value.
+//!
+//! `result`:
+//! - `frame`: The current `FrameCount`, or 0 without the `bevy_diagnostic` feature.
+//! - `components`: A map associating each component with its recorded values, from oldest to
+//!   newest. Each record has the `frame` and change `tick` at which it was recorded, and the
+//!   `value` of the component, which is null if it was removed.
+//!
+//! ### `world.send_event`
+//!
+//! Write a [`BufferedEvent`](bevy_ecs::event::BufferedEvent) to its `Events` resource. The event
+//! type must be registered with `#[reflect(BufferedEvent)]`.
+//!
+//! `params`:
+//! - `event`: The [fully-qualified type name] of the event to send.
+//! - `value`: The value of the event.
+//!
+//! `result`: null.
+//!
+//! ### `world.trigger_event`
+//!
+//! Trigger an [`Event`](bevy_ecs::event::Event), running the observers watching for it. The event
+//! type must be registered with `#[reflect(Event)]`, or with `#[reflect(EntityEvent)]` when
+//! targeting entities.
+//!
+//! `params`:
+//! - `event`: The [fully-qualified type name] of the event to trigger.
+//! - `value`: The value of the event.
+//! - `targets` (optional): The entities to trigger the event on. If this is absent or empty, the
+//!   event is triggered without a target.
+//!
+//! `result`: null.
+//!
+//! ### `world.snapshot`
+//!
+//! Serialize the entities and resources of the world into a
+//! [`DynamicScene`](bevy_scene::DynamicScene). Only reflected components and resources are
+//! captured. This requires the `bevy_scene` feature.
+//!
+//! `params` (optional):
+//! - `components` (optional): Which components to capture. Either `"all"` (the default),
+//!   `{ "allow": [...] }` or `{ "deny": [...] }`, with arrays of [fully-qualified type names].
+//! - `resources` (optional): Which resources to capture, in the same form as `components`.
+//!
+//! `result`: The scene, in the format of [`SceneSerializer`](bevy_scene::serde::SceneSerializer).
+//!
+//! ### `world.restore`
+//!
+//! Write a scene returned by `world.snapshot` into the world. Entity references in the scene are
+//! remapped to the entities they are written to. This requires the `bevy_scene` feature.
+//!
+//! `params`:
+//! - `scene`: The scene to restore.
+//! - `entity_map` (optional): A map from scene entities to existing entities of the world, which
+//!   are updated in place. Other scene entities are spawned.
+//!
+//! `result`:
+//! - `entities`: A map from each entity of the scene to the entity it was written to.
+//!
+//! ### `app.pause`
+//!
+//! Pause one or more schedules using [`Stepping`](bevy_ecs::schedule::Stepping), so that their
+//! systems only run when stepped with `app.step`. This requires the `bevy_debug_stepping` feature;
+//! without it, the request fails.
+//!
+//! `params` (optional):
+//! - `schedules` (optional): An array of the names of the schedules to pause, e.g. `"FixedUpdate"`.
+//!   Defaults to `["Update"]`.
+//!
+//! `result`: null.
+//!
+//! ### `app.resume`
+//!
+//! Resume all paused schedules, discarding any pending steps. This method has no parameters.
+//!
+//! `result`: null.
+//!
+//! ### `app.step`
+//!
+//! Run the paused schedules for a number of frames. One frame is stepped through per app update,
+//! starting with the next one.
+//!
+//! `params` (optional):
+//! - `frames` (optional): The number of frames to step through. Defaults to 1.
+//!
+//! `result`: null.
+//!
+//! ### `app.status`
+//!
+//! Report whether the app is paused. This method has no parameters.
+//!
+//! `result`:
+//! - `paused`: Whether stepping is enabled.
+//! - `pending_frames`: The number of frames requested by `app.step` that have not been run yet.
+//!
+//! ### `schedule.run`
+//!
+//! Run a schedule once, immediately. Schedules that are already running, like `Main`, can't be run.
+//!
+//! `params`:
+//! - `label`: The name of the schedule to run, e.g. `"FixedUpdate"`.
+//!
+//! `result`: null.
+//!
+//! ### `schedule.graph`
+//!
+//! Export the systems and system sets of schedules, the ordering and hierarchy edges between them,
+//! the ambiguities between systems and the components and resources accessed by each system.
+//! Schedules that are currently running, like `Main`, are not exported.
+//!
+//! `params` (optional):
+//! - `schedules` (optional): An array of the names of the schedules to export, e.g.
+//!   `"FixedUpdate"`. Defaults to every schedule.
+//!
+//! `result`: An array of schedules, each with:
+//! - `label`: The name of the schedule.
+//! - `systems`: An array of systems, each with a `name`, whether it is `exclusive`, and its
+//!   `access`: the names of the components and resources it reads and writes.
+//! - `sets`: An array of system sets, each with a `name`, and whether it is the `system_type` set
+//!   used to refer to a system function.
+//! - `hierarchy`: An array of `[set, child]` pairs.
+//! - `dependencies`: An array of `[before, after]` pairs.
+//! - `ambiguities`: An array of pairs of `systems` that have `conflicts` in their access but no
+//!   ordering between them.
+//!
+//! Systems and sets are referred to by their index in `systems` or `sets`, as `{ "system": index }`
+//! or `{ "set": index }`.
+//!
+//! ### `time.set_relative_speed`
+//!
+//! Set the speed at which virtual time advances relative to real time.
+//!
+//! `params`:
+//! - `speed`: The new relative speed, e.g. `2.0` to run twice as fast. Must be non-negative.
+//!
+//! `result`: null.
+//!
 //! ## Custom methods
 //!
 //! In addition to the provided methods, the Bevy Remote Protocol can be extended to include custom
@@ -520,6 +711,8 @@ pub mod builtin_methods;
 #[cfg(feature = "http")]
 pub mod http;
 pub mod schemas;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 const CHANNEL_SIZE: usize = 16;
 
@@ -574,7 +767,7 @@ impl RemotePlugin {
 
 impl Default for RemotePlugin {
     fn default() -> Self {
-        Self::empty()
+        let plugin = Self::empty()
             .with_method(
                 builtin_methods::BRP_GET_COMPONENTS_METHOD,
                 builtin_methods::process_remote_get_components_request,
@@ -623,6 +816,10 @@ impl Default for RemotePlugin {
                 builtin_methods::BRP_LIST_COMPONENTS_AND_WATCH_METHOD,
                 builtin_methods::process_remote_list_components_watching_request,
             )
+            .with_watching_method(
+                builtin_methods::BRP_QUERY_AND_WATCH_METHOD,
+                builtin_methods::process_remote_query_watching_request,
+            )
             .with_method(
                 builtin_methods::BRP_GET_RESOURCE_METHOD,
                 builtin_methods::process_remote_get_resources_request,
@@ -647,6 +844,69 @@ impl Default for RemotePlugin {
                 builtin_methods::BRP_REGISTRY_SCHEMA_METHOD,
                 builtin_methods::export_registry_types,
             )
+            .with_method(
+                builtin_methods::BRP_PAUSE_METHOD,
+                builtin_methods::process_remote_pause_request,
+            )
+            .with_method(
+                builtin_methods::BRP_RESUME_METHOD,
+                builtin_methods::process_remote_resume_request,
+            )
+            .with_method(
+                builtin_methods::BRP_STEP_METHOD,
+                builtin_methods::process_remote_step_request,
+            )
+            .with_method(
+                builtin_methods::BRP_STATUS_METHOD,
+                builtin_methods::process_remote_status_request,
+            )
+            .with_method(
+                builtin_methods::BRP_RUN_SCHEDULE_METHOD,
+                builtin_methods::process_remote_run_schedule_request,
+            )
+            .with_method(
+                builtin_methods::BRP_SCHEDULE_GRAPH_METHOD,
+                builtin_methods::export_schedule_graphs,
+            )
+            .with_method(
+                builtin_methods::BRP_WATCH_HISTORY_METHOD,
+                builtin_methods::process_remote_watch_history_request,
+            )
+            .with_method(
+                builtin_methods::BRP_UNWATCH_HISTORY_METHOD,
+                builtin_methods::process_remote_unwatch_history_request,
+            )
+            .with_method(
+                builtin_methods::BRP_HISTORY_METHOD,
+                builtin_methods::process_remote_history_request,
+            )
+            .with_method(
+                builtin_methods::BRP_SEND_EVENT_METHOD,
+                builtin_methods::process_remote_send_event_request,
+            )
+            .with_method(
+                builtin_methods::BRP_TRIGGER_EVENT_METHOD,
+                builtin_methods::process_remote_trigger_event_request,
+            );
+
+        #[cfg(feature = "bevy_time")]
+        let plugin = plugin.with_method(
+            builtin_methods::BRP_SET_RELATIVE_SPEED_METHOD,
+            builtin_methods::process_remote_set_relative_speed_request,
+        );
+
+        #[cfg(feature = "bevy_scene")]
+        let plugin = plugin
+            .with_method(
+                builtin_methods::BRP_SNAPSHOT_METHOD,
+                builtin_methods::process_remote_snapshot_request,
+            )
+            .with_method(
+                builtin_methods::BRP_RESTORE_METHOD,
+                builtin_methods::process_remote_restore_request,
+            );
+
+        plugin
     }
 }
 
@@ -677,6 +937,8 @@ impl Plugin for RemotePlugin {
         app.insert_resource(remote_methods)
             .init_resource::<schemas::SchemaTypesMetadata>()
             .init_resource::<RemoteWatchingRequests>()
+            .init_resource::<builtin_methods::RemotePendingSteps>()
+            .init_resource::<builtin_methods::RemoteHistory>()
             .add_systems(PreStartup, setup_mailbox_channel)
             .configure_sets(
                 RemoteLast,
@@ -685,7 +947,12 @@ impl Plugin for RemotePlugin {
             .add_systems(
                 RemoteLast,
                 (
-                    (process_remote_requests, process_ongoing_watching_requests)
+                    (
+                        builtin_methods::record_remote_history,
+                        process_remote_requests,
+                        process_ongoing_watching_requests,
+                        builtin_methods::step_remote_frames,
+                    )
                         .chain()
                         .in_set(RemoteSystems::ProcessRequests),
                     remove_closed_watching_requests.in_set(RemoteSystems::Cleanup),
@@ -955,6 +1222,16 @@ impl BrpError {
         }
     }
 
+    /// An arbitrary event error. Possibly related to reflection.
+    #[must_use]
+    pub fn event_error<E: ToString>(error: E) -> Self {
+        Self {
+            code: error_codes::EVENT_ERROR,
+            message: error.to_string(),
+            data: None,
+        }
+    }
+
     /// An arbitrary internal error.
     #[must_use]
     pub fn internal<E: ToString>(error: E) -> Self {
@@ -965,6 +1242,16 @@ impl BrpError {
         }
     }
 
+    /// Schedule wasn't found, or is not available to be run.
+    #[must_use]
+    pub fn schedule_not_found(schedule: &str) -> Self {
+        Self {
+            code: error_codes::SCHEDULE_NOT_FOUND,
+            message: format!("Schedule `{schedule}` not found"),
+            data: None,
+        }
+    }
+
     /// Attempt to reparent an entity to itself.
     #[must_use]
     pub fn self_reparent(entity: Entity) -> Self {
@@ -1015,6 +1302,12 @@ pub mod error_codes {
 
     /// Could not find resource in the world.
     pub const RESOURCE_NOT_PRESENT: i16 = -23502;
+
+    /// Could not find schedule in the world.
+    pub const SCHEDULE_NOT_FOUND: i16 = -23601;
+
+    /// Encountered an error while sending or triggering an event.
+    pub const EVENT_ERROR: i16 = -23701;
 }
 
 /// The result of a request.
@@ -0,0 +1,303 @@
+//! Module with the schema of a [`Schedule`]: its systems and system sets,
+//! the edges between them and the data each system accesses.
+use bevy_ecs::{
+    component::{ComponentId, Components},
+    query::{Access, ComponentAccessKind},
+    schedule::{graph::DiGraph, NodeId, Schedule},
+    system::System,
+};
+use bevy_platform::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Exported graph of a single [`Schedule`].
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ScheduleGraphSchema {
+    /// The name of the schedule, as printed by its [`Debug`](core::fmt::Debug) implementation.
+    pub label: String,
+    /// The systems of the schedule.
+    pub systems: Vec<SystemSchema>,
+    /// The system sets of the schedule.
+    pub sets: Vec<SystemSetSchema>,
+    /// The `[set, child]` pairs describing which systems and sets belong to which sets.
+    pub hierarchy: Vec<[ScheduleNodeRef; 2]>,
+    /// The `[before, after]` pairs describing the ordering constraints between systems and sets.
+    pub dependencies: Vec<[ScheduleNodeRef; 2]>,
+    /// The pairs of systems that have conflicting access, but no ordering between them.
+    ///
+    /// This is only computed once the schedule has been built.
+    pub ambiguities: Vec<AmbiguitySchema>,
+}
+
+/// Exported information about a system of a [`Schedule`].
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SystemSchema {
+    /// The name of the system.
+    pub name: String,
+    /// Whether the system requires exclusive access to the [`World`](bevy_ecs::world::World).
+    pub exclusive: bool,
+    /// The data accessed by the system.
+    ///
+    /// This is empty until the schedule has been initialized.
+    pub access: SystemAccessSchema,
+}
+
+/// Exported information about a system set of a [`Schedule`].
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SystemSetSchema {
+    /// The name of the set, as printed by its [`Debug`](core::fmt::Debug) implementation.
+    pub name: String,
+    /// Whether the set was created implicitly to refer to a system function, for example by
+    /// ordering another system `.after(my_system)`.
+    pub system_type: bool,
+}
+
+/// A reference to a system or system set, by its index in [`ScheduleGraphSchema::systems`]
+/// or [`ScheduleGraphSchema::sets`].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleNodeRef {
+    /// A system.
+    System(usize),
+    /// A system set.
+    Set(usize),
+}
+
+/// A pair of systems that can run in any order relative to each other, despite
+/// conflicting access.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct AmbiguitySchema {
+    /// The indices of the two systems in [`ScheduleGraphSchema::systems`].
+    pub systems: [usize; 2],
+    /// The names of the components and resources the systems conflict on.
+    ///
+    /// If this is empty, the systems conflict on access to the whole world.
+    pub conflicts: Vec<String>,
+}
+
+/// Exported data access of a system, as described by its combined [`Access`].
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SystemAccessSchema {
+    /// The components that are read, but not written.
+    pub component_reads: Vec<String>,
+    /// The components that are written.
+    pub component_writes: Vec<String>,
+    /// The components whose presence is checked, but whose values are not accessed.
+    pub archetypal: Vec<String>,
+    /// The resources that are read, but not written.
+    pub resource_reads: Vec<String>,
+    /// The resources that are written.
+    pub resource_writes: Vec<String>,
+    /// Whether every component may be read.
+    pub reads_all_components: bool,
+    /// Whether every component may be written.
+    pub writes_all_components: bool,
+    /// Whether every resource may be read.
+    pub reads_all_resources: bool,
+    /// Whether every resource may be written.
+    pub writes_all_resources: bool,
+}
+
+impl SystemAccessSchema {
+    /// Exports the given `access`, naming components and resources with `components`.
+    pub fn new(access: &Access, components: &Components) -> Self {
+        let name = |id: ComponentId| component_name(components, id);
+        let mut schema = Self {
+            resource_reads: access.resource_reads().map(name).collect(),
+            resource_writes: access.resource_writes().map(name).collect(),
+            reads_all_components: access.has_read_all_components(),
+            writes_all_components: access.has_write_all_components(),
+            reads_all_resources: access.has_read_all_resources(),
+            writes_all_resources: access.has_write_all_resources(),
+            ..Default::default()
+        };
+        // Accesses to "all components except some" can't be listed.
+        if let Ok(component_access) = access.try_iter_component_access() {
+            for kind in component_access {
+                let list = match kind {
+                    ComponentAccessKind::Shared(_) => &mut schema.component_reads,
+                    ComponentAccessKind::Exclusive(_) => &mut schema.component_writes,
+                    ComponentAccessKind::Archetypal(_) => &mut schema.archetypal,
+                };
+                list.push(name(*kind.index()));
+            }
+        }
+        schema
+    }
+}
+
+/// Exports the graph of the given `schedule`, naming components and resources with `components`.
+pub fn export_schedule_graph(schedule: &Schedule, components: &Components) -> ScheduleGraphSchema {
+    let graph = schedule.graph();
+    let mut node_refs = HashMap::<NodeId, ScheduleNodeRef>::default();
+
+    // Once the schedule has been built, its systems live in the executable schedule rather than
+    // in the graph. Systems added since then are still in the graph.
+    let built_systems = schedule.systems_with_access().into_iter().flatten();
+    let pending_systems = graph
+        .systems
+        .iter()
+        .map(|(key, ..)| (key, &graph.systems[key]));
+    let systems = built_systems
+        .chain(pending_systems)
+        .enumerate()
+        .map(|(index, (key, system))| {
+            node_refs.insert(NodeId::System(key), ScheduleNodeRef::System(index));
+            SystemSchema {
+                name: system.name().to_string(),
+                exclusive: system.is_exclusive(),
+                access: SystemAccessSchema::new(system.access.combined_access(), components),
+            }
+        })
+        .collect();
+
+    let sets = graph
+        .system_sets
+        .iter()
+        .enumerate()
+        .map(|(index, (key, set, _))| {
+            node_refs.insert(NodeId::Set(key), ScheduleNodeRef::Set(index));
+            SystemSetSchema {
+                name: format!("{set:?}"),
+                system_type: set.system_type().is_some(),
+            }
+        })
+        .collect();
+
+    let edges = |graph: &DiGraph<NodeId>| {
+        graph
+            .all_edges()
+            .filter_map(|(a, b)| Some([*node_refs.get(&a)?, *node_refs.get(&b)?]))
+            .collect()
+    };
+    let hierarchy = edges(graph.hierarchy().graph());
+    let dependencies = edges(graph.dependency().graph());
+
+    let ambiguities = graph
+        .conflicting_systems()
+        .iter()
+        .filter_map(|(a, b, conflicts)| {
+            let (Some(&ScheduleNodeRef::System(a)), Some(&ScheduleNodeRef::System(b))) = (
+                node_refs.get(&NodeId::System(*a)),
+                node_refs.get(&NodeId::System(*b)),
+            ) else {
+                return None;
+            };
+            Some(AmbiguitySchema {
+                systems: [a, b],
+                conflicts: conflicts
+                    .iter()
+                    .map(|&id| component_name(components, id))
+                    .collect(),
+            })
+        })
+        .collect();
+
+    ScheduleGraphSchema {
+        label: format!("{:?}", schedule.label()),
+        systems,
+        sets,
+        hierarchy,
+        dependencies,
+        ambiguities,
+    }
+}
+
+fn component_name(components: &Components, id: ComponentId) -> String {
+    components
+        .get_name(id)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("{id:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        component::Component,
+        resource::Resource,
+        schedule::{IntoScheduleConfigs, ScheduleLabel},
+        system::{Query, Res, ResMut},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct Position;
+
+    #[derive(Component)]
+    struct Velocity;
+
+    #[derive(Resource, Default)]
+    struct Gravity;
+
+    #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+    struct Simulate;
+
+    fn apply_gravity(_gravity: Res<Gravity>, _query: Query<&mut Velocity>) {}
+
+    fn integrate(_query: Query<(&mut Position, &Velocity)>) {}
+
+    fn reset_gravity(_gravity: ResMut<Gravity>) {}
+
+    fn count_velocities(_query: Query<&mut Velocity>) {}
+
+    #[test]
+    fn export_systems_edges_and_access() {
+        let mut world = World::new();
+        world.init_resource::<Gravity>();
+        let mut schedule = Schedule::new(Simulate);
+        schedule.set_build_settings(bevy_ecs::schedule::ScheduleBuildSettings {
+            ambiguity_detection: bevy_ecs::schedule::LogLevel::Warn,
+            ..Default::default()
+        });
+        schedule.add_systems((
+            (apply_gravity, integrate).chain(),
+            reset_gravity.before(apply_gravity),
+            count_velocities,
+        ));
+        schedule.initialize(&mut world).unwrap();
+
+        let schema = export_schedule_graph(&schedule, world.components());
+        assert_eq!(schema.label, "Simulate");
+        assert_eq!(schema.systems.len(), 4);
+
+        let index_of = |name: &str| {
+            schema
+                .systems
+                .iter()
+                .position(|system| system.name.ends_with(name))
+                .unwrap()
+        };
+        let integrate = &schema.systems[index_of("integrate")];
+        assert!(integrate.access.component_writes[0].ends_with("Position"));
+        assert!(integrate.access.component_reads[0].ends_with("Velocity"));
+        let apply_gravity = &schema.systems[index_of("apply_gravity")];
+        assert!(apply_gravity.access.resource_reads[0].ends_with("Gravity"));
+
+        // `reset_gravity.before(apply_gravity)` orders against the set of `apply_gravity`.
+        let gravity_set = schema
+            .sets
+            .iter()
+            .position(|set| set.system_type && set.name.contains("apply_gravity"))
+            .unwrap();
+        assert!(schema.dependencies.contains(&[
+            ScheduleNodeRef::System(index_of("reset_gravity")),
+            ScheduleNodeRef::Set(gravity_set),
+        ]));
+        assert!(schema.hierarchy.contains(&[
+            ScheduleNodeRef::Set(gravity_set),
+            ScheduleNodeRef::System(index_of("apply_gravity")),
+        ]));
+
+        // `count_velocities` isn't ordered with the systems writing `Velocity`.
+        let count_velocities = index_of("count_velocities");
+        let ambiguous_with: Vec<_> = schema
+            .ambiguities
+            .iter()
+            .filter(|ambiguity| ambiguity.systems.contains(&count_velocities))
+            .collect();
+        assert_eq!(ambiguous_with.len(), 2);
+        assert!(ambiguous_with
+            .iter()
+            .all(|ambiguity| ambiguity.conflicts[0].ends_with("Velocity")));
+    }
+}
@@ -0,0 +1,615 @@
+//! The BRP transport using JSON-RPC over WebSocket.
+//!
+//! Adding the [`RemoteWebSocketPlugin`] to your [`App`] causes Bevy to accept
+//! WebSocket connections (by default, on port 15703) while your app is running.
+//!
+//! Unlike the HTTP transport, a single connection can carry any number of concurrent
+//! requests: clients send JSON-RPC requests (or batches) as text messages, and every
+//! response is sent back as its own message tagged with the `id` of the request it
+//! answers. Watching requests (`+watch` methods) push a new message each time their
+//! handler reports a change, until the connection is closed.
+
+#![cfg(not(target_family = "wasm"))]
+
+use crate::{
+    error_codes,
+    http::{Headers, HostAddress, HostPort, DEFAULT_ADDR},
+    BrpBatch, BrpError, BrpMessage, BrpRequest, BrpResponse, BrpResult, BrpSender,
+};
+use anyhow::Result as AnyhowResult;
+use async_channel::{Receiver, Sender};
+use async_io::Async;
+use async_tungstenite::{
+    tungstenite::{
+        handshake::server::{Callback, ErrorResponse, Request, Response},
+        Message,
+    },
+    WebSocketStream,
+};
+use bevy_app::{App, Plugin, Startup};
+use bevy_ecs::{resource::Resource, system::Res};
+use bevy_tasks::{futures_lite::StreamExt, IoTaskPool};
+use core::net::IpAddr;
+use hyper::header::{HeaderName, HeaderValue};
+use serde_json::Value;
+use std::net::{TcpListener, TcpStream};
+
+/// The default port that Bevy will listen on for WebSocket connections.
+///
+/// This is the port right after [`DEFAULT_PORT`](crate::http::DEFAULT_PORT), so that
+/// both transports can be enabled with their default settings.
+pub const DEFAULT_WEBSOCKET_PORT: u16 = 15703;
+
+/// Add this plugin to your [`App`] to allow remote connections over WebSocket to inspect and
+/// modify entities. It requires the [`RemotePlugin`](super::RemotePlugin).
+///
+/// This BRP transport can be used alongside the [`RemoteHttpPlugin`](crate::http::RemoteHttpPlugin),
+/// and it cannot be used when targeting WASM.
+///
+/// The defaults are:
+/// - [`DEFAULT_ADDR`] : 127.0.0.1.
+/// - [`DEFAULT_WEBSOCKET_PORT`] : 15703.
+///
+pub struct RemoteWebSocketPlugin {
+    /// The address that Bevy will bind to.
+    address: IpAddr,
+    /// The port that Bevy will listen on.
+    port: u16,
+    /// The headers that Bevy will include in its handshake responses
+    headers: Headers,
+}
+
+impl Default for RemoteWebSocketPlugin {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_ADDR,
+            port: DEFAULT_WEBSOCKET_PORT,
+            headers: Headers::new(),
+        }
+    }
+}
+
+impl Plugin for RemoteWebSocketPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WebSocketHost {
+            address: HostAddress(self.address),
+            port: HostPort(self.port),
+            headers: self.headers.clone(),
+        })
+        .add_systems(Startup, start_websocket_server);
+    }
+}
+
+impl RemoteWebSocketPlugin {
+    /// Set the IP address that the server will use.
+    #[must_use]
+    pub fn with_address(mut self, address: impl Into<IpAddr>) -> Self {
+        self.address = address.into();
+        self
+    }
+    /// Set the remote port that the server will listen on.
+    #[must_use]
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+    /// Set the extra headers that the handshake response will include.
+    #[must_use]
+    pub fn with_headers(mut self, headers: Headers) -> Self {
+        self.headers = headers;
+        self
+    }
+    /// Add a single header to the handshake response headers.
+    #[must_use]
+    pub fn with_header(
+        mut self,
+        name: impl TryInto<HeaderName>,
+        value: impl TryInto<HeaderValue>,
+    ) -> Self {
+        self.headers = self.headers.insert(name, value);
+        self
+    }
+}
+
+/// A resource containing the address and port that Bevy will accept WebSocket connections on,
+/// using the same [`HostAddress`] and [`HostPort`] types as the HTTP transport.
+///
+/// Currently, changing this while the application is running has no effect; this merely
+/// reflects the host that is set during the setup of the [`RemoteWebSocketPlugin`].
+#[derive(Debug, Resource)]
+pub struct WebSocketHost {
+    /// The IP address that Bevy will accept WebSocket connections on.
+    pub address: HostAddress,
+    /// The port number that Bevy will accept WebSocket connections on.
+    pub port: HostPort,
+    /// The headers that Bevy will include in its handshake responses.
+    headers: Headers,
+}
+
+/// A system that starts up the Bevy Remote Protocol WebSocket server.
+fn start_websocket_server(request_sender: Res<BrpSender>, host: Res<WebSocketHost>) {
+    IoTaskPool::get()
+        .spawn(server_main(
+            host.address.0,
+            host.port.0,
+            request_sender.clone(),
+            host.headers.clone(),
+        ))
+        .detach();
+}
+
+/// The Bevy Remote Protocol WebSocket server main loop.
+async fn server_main(
+    address: IpAddr,
+    port: u16,
+    request_sender: Sender<BrpMessage>,
+    headers: Headers,
+) -> AnyhowResult<()> {
+    listen(
+        Async::<TcpListener>::bind((address, port))?,
+        &request_sender,
+        &headers,
+    )
+    .await
+}
+
+async fn listen(
+    listener: Async<TcpListener>,
+    request_sender: &Sender<BrpMessage>,
+    headers: &Headers,
+) -> AnyhowResult<()> {
+    loop {
+        let (client, _) = listener.accept().await?;
+
+        let request_sender = request_sender.clone();
+        let headers = headers.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                let _ = handle_client(client, request_sender, headers).await;
+            })
+            .detach();
+    }
+}
+
+async fn handle_client(
+    client: Async<TcpStream>,
+    request_sender: Sender<BrpMessage>,
+    headers: Headers,
+) -> AnyhowResult<()> {
+    let socket = async_tungstenite::accept_hdr_async(client, HandshakeHeaders(&headers)).await?;
+
+    serve_connection(socket, request_sender).await
+}
+
+/// Adds the configured [`Headers`] to the response of the WebSocket handshake.
+struct HandshakeHeaders<'a>(&'a Headers);
+
+impl Callback for HandshakeHeaders<'_> {
+    fn on_request(
+        self,
+        _request: &Request,
+        mut response: Response,
+    ) -> Result<Response, ErrorResponse> {
+        for (key, value) in &self.0.headers {
+            response.headers_mut().insert(key, value.clone());
+        }
+        Ok(response)
+    }
+}
+
+/// The result channels of all watching requests of a connection.
+///
+/// Dropping this closes the channels, which stops the watching handlers, so the watchers
+/// of a connection are cleaned up however the connection ends.
+#[derive(Default)]
+struct Watchers(Vec<Receiver<BrpResult>>);
+
+impl Drop for Watchers {
+    fn drop(&mut self) {
+        for watcher in self.0.drain(..) {
+            watcher.close();
+        }
+    }
+}
+
+/// Multiplexes all of the requests of a single WebSocket connection.
+///
+/// Incoming messages are processed concurrently, and every response is funneled
+/// through a single channel so that only one task ever writes to the socket.
+/// Requests that fail are answered with a JSON-RPC error; the connection is only
+/// closed when the client goes away.
+async fn serve_connection(
+    socket: WebSocketStream<Async<TcpStream>>,
+    request_sender: Sender<BrpMessage>,
+) -> AnyhowResult<()> {
+    let (mut socket_sender, mut socket_receiver) = socket.split();
+    let (response_sender, response_receiver) = async_channel::unbounded::<String>();
+
+    let writer = IoTaskPool::get().spawn(async move {
+        while let Ok(response) = response_receiver.recv().await {
+            if socket_sender.send(Message::text(response)).await.is_err() {
+                break;
+            }
+        }
+        let _ = socket_sender.close(None).await;
+    });
+
+    let mut watchers = Watchers::default();
+
+    while let Some(message) = socket_receiver.next().await {
+        let batch = match message {
+            Ok(Message::Text(text)) => serde_json::from_str::<BrpBatch>(text.as_str()),
+            Ok(Message::Binary(bytes)) => serde_json::from_slice::<BrpBatch>(&bytes),
+            Ok(Message::Close(_)) | Err(_) => break,
+            // Pings are answered by the WebSocket implementation itself.
+            Ok(_) => continue,
+        };
+
+        match batch {
+            Ok(BrpBatch::Single(request)) => {
+                match process_single_request(request, &request_sender, &response_sender).await {
+                    Ok(Some(watcher)) => watchers.0.push(watcher),
+                    Ok(None) => {}
+                    // The writer has stopped, so the client is gone.
+                    Err(_) => break,
+                }
+            }
+            Ok(BrpBatch::Batch(requests)) => {
+                let request_sender = request_sender.clone();
+                let response_sender = response_sender.clone();
+                IoTaskPool::get()
+                    .spawn(async move {
+                        let _ = process_request_batch(requests, &request_sender, &response_sender)
+                            .await;
+                    })
+                    .detach();
+            }
+            Err(err) => {
+                let err = BrpResponse::new(
+                    None,
+                    Err(BrpError {
+                        code: error_codes::INVALID_REQUEST,
+                        message: err.to_string(),
+                        data: None,
+                    }),
+                );
+                if send_response(&response_sender, &err).await.is_err() {
+                    break;
+                }
+            }
+        }
+
+        watchers.0.retain(|watcher| !watcher.is_closed());
+    }
+
+    drop(watchers);
+    response_sender.close();
+    writer.await;
+
+    Ok(())
+}
+
+/// A helper function for the Bevy Remote Protocol server that handles a batch
+/// of requests coming from a client.
+///
+/// As with the HTTP transport, the responses to a batch are sent back together
+/// and watching requests are not allowed in batches.
+async fn process_request_batch(
+    requests: Vec<Value>,
+    request_sender: &Sender<BrpMessage>,
+    response_sender: &Sender<String>,
+) -> AnyhowResult<()> {
+    let mut responses = Vec::new();
+
+    for request in requests {
+        let request = match parse_request(request) {
+            Ok(request) => request,
+            Err(response) => {
+                responses.push(response);
+                continue;
+            }
+        };
+
+        if is_watching(&request) {
+            responses.push(BrpResponse::new(
+                request.id,
+                Err(BrpError {
+                    code: error_codes::INVALID_REQUEST,
+                    message: "Streaming can not be used in batch requests".to_string(),
+                    data: None,
+                }),
+            ));
+            continue;
+        }
+
+        let (result_sender, result_receiver) = async_channel::bounded(1);
+        let _ = request_sender
+            .send(BrpMessage {
+                method: request.method,
+                params: request.params,
+                sender: result_sender,
+            })
+            .await;
+        let result = result_receiver
+            .recv()
+            .await
+            .unwrap_or_else(|err| Err(BrpError::internal(err)));
+        responses.push(BrpResponse::new(request.id, result));
+    }
+
+    send_response(response_sender, &responses).await
+}
+
+/// Serializes `response` and queues it to be written to the socket, sending an internal
+/// error response in its place if it can't be serialized.
+///
+/// This only fails if the connection's writer has stopped.
+async fn send_response(
+    response_sender: &Sender<String>,
+    response: &impl serde::Serialize,
+) -> AnyhowResult<()> {
+    let serialized = serde_json::to_string(response).unwrap_or_else(|err| {
+        let err = BrpResponse::new(None, Err(BrpError::internal(err)));
+        serde_json::to_string(&err).expect("error responses are always serializable")
+    });
+    response_sender.send(serialized).await?;
+    Ok(())
+}
+
+/// A helper function for the Bevy Remote Protocol server that processes a single
+/// request coming from a client.
+///
+/// The request is answered from a separate task, so that slow handlers don't hold up
+/// the other requests of the connection. If the request is a watching request, the
+/// channel on which its results are received is returned.
+///
+/// This only fails if the connection's writer has stopped.
+async fn process_single_request(
+    request: Value,
+    request_sender: &Sender<BrpMessage>,
+    response_sender: &Sender<String>,
+) -> AnyhowResult<Option<Receiver<BrpResult>>> {
+    let request = match parse_request(request) {
+        Ok(request) => request,
+        Err(response) => {
+            send_response(response_sender, &response).await?;
+            return Ok(None);
+        }
+    };
+
+    let watch = is_watching(&request);
+    let size = if watch { 8 } else { 1 };
+    let (result_sender, result_receiver) = async_channel::bounded(size);
+
+    let id = request.id;
+    if let Err(err) = request_sender
+        .send(BrpMessage {
+            method: request.method,
+            params: request.params,
+            sender: result_sender,
+        })
+        .await
+    {
+        send_response(
+            response_sender,
+            &BrpResponse::new(id, Err(BrpError::internal(err))),
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    let response_sender = response_sender.clone();
+    let results = result_receiver.clone();
+    IoTaskPool::get()
+        .spawn(async move {
+            while let Ok(result) = results.recv().await {
+                let response = BrpResponse::new(id.clone(), result);
+                if send_response(&response_sender, &response).await.is_err() || !watch {
+                    break;
+                }
+            }
+            // Closing the channel lets the `RemotePlugin` know that nobody is watching anymore.
+            results.close();
+        })
+        .detach();
+
+    Ok(watch.then_some(result_receiver))
+}
+
+/// Parses a single JSON-RPC request, returning the error response to send back if it's invalid.
+fn parse_request(request: Value) -> Result<BrpRequest, BrpResponse> {
+    // Reach in and get the request ID early so that we can report it even when parsing fails.
+    let id = request.as_object().and_then(|map| map.get("id")).cloned();
+
+    let request: BrpRequest = serde_json::from_value(request).map_err(|err| {
+        BrpResponse::new(
+            id.clone(),
+            Err(BrpError {
+                code: error_codes::INVALID_REQUEST,
+                message: err.to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    if request.jsonrpc != "2.0" {
+        return Err(BrpResponse::new(
+            id,
+            Err(BrpError {
+                code: error_codes::INVALID_REQUEST,
+                message: String::from("JSON-RPC request requires `\"jsonrpc\": \"2.0\"`"),
+                data: None,
+            }),
+        ));
+    }
+
+    Ok(request)
+}
+
+/// Whether the request is for a watching method, whose results are streamed.
+fn is_watching(request: &BrpRequest) -> bool {
+    request.method.contains("+watch")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_tungstenite::client_async;
+    use bevy_tasks::{futures_lite::future, TaskPool};
+    use core::net::SocketAddr;
+    use serde_json::json;
+
+    /// Starts a server on a free loopback port, with `handler` standing in for the `RemotePlugin`.
+    fn spawn_server(headers: Headers, handler: impl Fn(BrpMessage) + Send + 'static) -> SocketAddr {
+        IoTaskPool::get_or_init(TaskPool::new);
+
+        let (request_sender, request_receiver) = async_channel::bounded::<BrpMessage>(16);
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0)).unwrap();
+        let address = listener.get_ref().local_addr().unwrap();
+        IoTaskPool::get()
+            .spawn(async move {
+                let _ = listen(listener, &request_sender, &headers).await;
+            })
+            .detach();
+        IoTaskPool::get()
+            .spawn(async move {
+                while let Ok(message) = request_receiver.recv().await {
+                    handler(message);
+                }
+            })
+            .detach();
+        address
+    }
+
+    /// Runs `client` to completion while ticking the task pool.
+    fn block_on(client: impl Future<Output = ()>) {
+        // Without the `multi_threaded` feature, spawned tasks only make progress when the
+        // task pool is ticked, which the app would otherwise do every frame.
+        let ticker = async {
+            loop {
+                IoTaskPool::get().with_local_executor(|executor| while executor.try_tick() {});
+                future::yield_now().await;
+            }
+        };
+        future::block_on(future::or(client, ticker));
+    }
+
+    async fn next_response(socket: &mut WebSocketStream<Async<TcpStream>>) -> Value {
+        loop {
+            if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+                return serde_json::from_str(text.as_str()).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn multiplexes_requests_over_loopback() {
+        // Watchers get two updates, everything else is echoed back.
+        let address = spawn_server(Headers::new().insert("x-test", "brp"), |message| {
+            IoTaskPool::get()
+                .spawn(async move {
+                    if message.method.contains("+watch") {
+                        for update in 0..2 {
+                            let _ = message.sender.send(Ok(json!(update))).await;
+                        }
+                    } else {
+                        let _ = message.sender.send(Ok(message.params.unwrap())).await;
+                    }
+                })
+                .detach();
+        });
+
+        block_on(async {
+            let stream = Async::<TcpStream>::connect(address).await.unwrap();
+            let (mut socket, handshake) = client_async(format!("ws://{address}"), stream)
+                .await
+                .unwrap();
+            assert_eq!(handshake.headers()["x-test"], "brp");
+
+            let watch = json!({"jsonrpc": "2.0", "id": 0, "method": "world.query+watch"});
+            let echo = json!({"jsonrpc": "2.0", "id": 1, "method": "echo", "params": "hi"});
+            socket.send(Message::text(watch.to_string())).await.unwrap();
+            socket.send(Message::text(echo.to_string())).await.unwrap();
+
+            let mut responses = Vec::new();
+            while responses.len() < 3 {
+                responses.push(next_response(&mut socket).await);
+            }
+
+            let results = |id: i32| {
+                responses
+                    .iter()
+                    .filter(|response| response["id"] == json!(id))
+                    .map(|response| response["result"].clone())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(results(0), vec![json!(0), json!(1)]);
+            assert_eq!(results(1), vec![json!("hi")]);
+        });
+    }
+
+    #[test]
+    fn errors_keep_the_connection_open_and_watchers_close_on_disconnect() {
+        let (watcher_sender, watcher_receiver) = async_channel::bounded::<Sender<BrpResult>>(1);
+        // Watchers are handed to the test and never report anything, everything else fails.
+        let address = spawn_server(Headers::new(), move |message| {
+            if message.method.contains("+watch") {
+                let _ = watcher_sender.try_send(message.sender);
+            } else {
+                let _ = message.sender.try_send(Err(BrpError::internal("failed")));
+            }
+        });
+
+        block_on(async {
+            let stream = Async::<TcpStream>::connect(address).await.unwrap();
+            let (mut socket, _) = client_async(format!("ws://{address}"), stream)
+                .await
+                .unwrap();
+
+            let watch = json!({"jsonrpc": "2.0", "id": 0, "method": "world.query+watch"});
+            socket.send(Message::text(watch.to_string())).await.unwrap();
+            let watcher = watcher_receiver.recv().await.unwrap();
+
+            // Malformed messages, invalid requests and failing requests are all answered with
+            // errors, and the connection stays open.
+            socket.send(Message::text("not json")).await.unwrap();
+            let response = next_response(&mut socket).await;
+            assert_eq!(
+                response["error"]["code"],
+                json!(error_codes::INVALID_REQUEST)
+            );
+
+            let invalid = json!({"jsonrpc": "1.0", "id": 1, "method": "echo"});
+            socket
+                .send(Message::text(invalid.to_string()))
+                .await
+                .unwrap();
+            let response = next_response(&mut socket).await;
+            assert_eq!(response["id"], json!(1));
+            assert_eq!(
+                response["error"]["code"],
+                json!(error_codes::INVALID_REQUEST)
+            );
+
+            for id in 2..4 {
+                let failing = json!({"jsonrpc": "2.0", "id": id, "method": "fail"});
+                socket
+                    .send(Message::text(failing.to_string()))
+                    .await
+                    .unwrap();
+                let response = next_response(&mut socket).await;
+                assert_eq!(response["id"], json!(id));
+                assert_eq!(
+                    response["error"]["code"],
+                    json!(error_codes::INTERNAL_ERROR)
+                );
+            }
+
+            // Closing the connection stops the watcher it started.
+            socket.close(None).await.unwrap();
+            while !watcher.is_closed() {
+                future::yield_now().await;
+            }
+        });
+    }
+}
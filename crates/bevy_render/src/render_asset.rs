@@ -264,9 +264,10 @@ pub(crate) fn extract_render_asset<A: RenderAsset>(
                     }
                     AssetEvent::Removed { .. } => {
                         // We don't care that the asset was removed from Assets<T> in the main world.
-                        // An asset is only removed from RenderAssets<T> when its last handle is dropped (AssetEvent::Unused).
+                        // An asset is only removed from RenderAssets<T> when its last handle is dropped (AssetEvent::Unused),
+                        // or when it's evicted to stay within the budget of Assets<T> (AssetEvent::Evicted).
                     }
-                    AssetEvent::Unused { id } => {
+                    AssetEvent::Unused { id } | AssetEvent::Evicted { id } => {
                         needs_extracting.remove(id);
                         modified.remove(id);
                         removed.insert(*id);